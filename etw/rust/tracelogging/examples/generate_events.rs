@@ -81,7 +81,7 @@ fn main() {
         str8_json("str8_json", "\"json\""),
         cstr16_xml("cstr16_xml", &[b'<' as u16, b'x' as u16, b'm' as u16, b'l' as u16, b'/' as u16, b'>' as u16]),
 
-        str8("str8", "str8_val"),   // AsRef unwraps &str --> &[u8]
+        str8("str8_val", "str8_val"),   // AsRef unwraps &str --> &[u8]
         str8("string", &string),    // AsRef unwraps &String --> &[u8]
 
         ipv4("ipv4", &[127, 0, 0, 1]),
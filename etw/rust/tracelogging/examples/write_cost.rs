@@ -0,0 +1,191 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+//! Measures the per-write cost of `write_event!`.
+//!
+//! Compares `write_event!` with a hand-written write that uses metadata and an event
+//! descriptor prepared in advance, i.e. the "prepared event" approach, where only the
+//! value descriptors are built for each write. Also compares a `write_event!` loop with
+//! `write_events!`, which does the enabled check once per batch.
+//!
+//! `cargo run --release --example write_cost [ITERATIONS]`
+//!
+//! On Windows, start an ETW session that listens to the provider (the provider id is
+//! printed at startup) at level 5 with keyword 0x1. On other platforms, add
+//! `--features testing` so that the provider is always enabled.
+
+#![allow(clippy::needless_return)]
+
+use std::time::Instant;
+use tracelogging as tlg;
+use tracelogging::_internal as tli;
+
+tlg::define_provider!(PROV, "TraceLoggingWriteCost");
+
+// Never registered, so its events are never enabled.
+tlg::define_provider!(PROV_UNREGISTERED, "TraceLoggingWriteCostUnregistered");
+
+const LEVEL: tlg::Level = tlg::Level::Verbose;
+const KEYWORD: u64 = 0x1;
+
+/// Number of events written by each call to the batch functions.
+const BATCH: u32 = 16;
+
+/// Same descriptor as `write_event!(PROV, "Bench", level(Verbose), keyword(0x1), ...)`.
+const BENCH_DESC: tli::EventDescriptor = tli::EventDescriptor::new(LEVEL, KEYWORD);
+
+/// Same metadata as `write_event!(PROV, "Bench", ..., u32("a"), u64("b"), f64("c"), u32("d"))`:
+/// size, tag, event name, then the name and intype of each field.
+#[rustfmt::skip]
+const BENCH_META: [u8; 21] = [
+    21, 0, // size
+    0, // tag
+    b'B', b'e', b'n', b'c', b'h', 0, // event name
+    b'a', 0, tlg::InType::U32.as_int(),
+    b'b', 0, tlg::InType::U64.as_int(),
+    b'c', 0, tlg::InType::F64.as_int(),
+    b'd', 0, tlg::InType::U32.as_int(),
+];
+
+/// Hand-written equivalent of the `write_event!` in `write_macro`.
+#[inline(never)]
+fn write_prepared(a: u32, b: u64, c: f64, d: u32) -> u32 {
+    if !PROV.enabled(LEVEL, KEYWORD) {
+        return 0;
+    }
+
+    return tli::provider_write_transfer(
+        &PROV,
+        &BENCH_DESC,
+        None,
+        None,
+        &[
+            tli::EventDataDescriptor::from_raw_bytes(PROV.raw_meta(), 2), // provider metadata
+            tli::EventDataDescriptor::from_raw_bytes(&BENCH_META, 1),     // event metadata
+            tli::EventDataDescriptor::from_value(&a),
+            tli::EventDataDescriptor::from_value(&b),
+            tli::EventDataDescriptor::from_value(&c),
+            tli::EventDataDescriptor::from_value(&d),
+        ],
+    );
+}
+
+#[inline(never)]
+fn write_macro(a: u32, b: u64, c: f64, d: u32) -> u32 {
+    return tlg::write_event!(
+        PROV,
+        "Bench",
+        level(Verbose),
+        keyword(0x1),
+        u32("a", &a),
+        u64("b", &b),
+        f64("c", &c),
+        u32("d", &d),
+    );
+}
+
+#[inline(never)]
+fn write_macro_loop(first: u32) -> u32 {
+    let mut result = 0;
+    for a in first..first + BATCH {
+        let err = tlg::write_event!(
+            PROV,
+            "Bench",
+            level(Verbose),
+            keyword(0x1),
+            u32("a", &a),
+            u64("b", &u64::from(a)),
+        );
+        if err != 0 {
+            result = err;
+        }
+    }
+    return result;
+}
+
+#[inline(never)]
+fn write_events_batch(first: u32) -> u32 {
+    return tlg::write_events!(
+        PROV,
+        "Bench",
+        for a in first..first + BATCH,
+        level(Verbose),
+        keyword(0x1),
+        u32("a", &a),
+        u64("b", &u64::from(a)),
+    );
+}
+
+#[inline(never)]
+fn write_macro_not_enabled(a: u32) -> u32 {
+    // Measures only the enabled check.
+    return tlg::write_event!(PROV_UNREGISTERED, "Bench", level(Verbose), u32("a", &a));
+}
+
+/// Calls `write` `iterations` times and prints the average time per event, where each
+/// call writes `events_per_call` events.
+fn measure(name: &str, iterations: u32, events_per_call: u32, mut write: impl FnMut(u32) -> u32) {
+    let mut errors = 0u32;
+    let start = Instant::now();
+    for i in 0..iterations {
+        // Volatile read so the optimizer can't hoist or remove the write.
+        let value = unsafe { core::ptr::read_volatile(&i) };
+        if write(value) != 0 {
+            errors += 1;
+        }
+    }
+    let elapsed = start.elapsed();
+
+    println!(
+        "{:<28} {:>8.1} ns/write ({} errors)",
+        name,
+        elapsed.as_nanos() as f64 / (iterations as f64 * events_per_call as f64),
+        errors
+    );
+}
+
+fn main() {
+    let iterations: u32 = match std::env::args().nth(1) {
+        Some(arg) => arg.parse().expect("ITERATIONS must be a number"),
+        None => 1_000_000,
+    };
+
+    unsafe {
+        PROV.register();
+    }
+    println!("Provider {} id {}", PROV.name(), PROV.id());
+
+    if !PROV.enabled(LEVEL, KEYWORD) {
+        println!(
+            "Note: the provider is not enabled, so the writes return without logging. Start an \
+            ETW session for the provider, or use --features testing on non-Windows platforms."
+        );
+    }
+
+    measure(
+        "write_event! (not enabled)",
+        iterations,
+        1,
+        write_macro_not_enabled,
+    );
+    measure("write_event! (4 fields)", iterations, 1, |i| {
+        write_macro(i, u64::from(i) << 32, f64::from(i), !i)
+    });
+    measure("prepared (4 fields)", iterations, 1, |i| {
+        write_prepared(i, u64::from(i) << 32, f64::from(i), !i)
+    });
+    measure(
+        "write_event! loop (2 fields)",
+        iterations / BATCH,
+        BATCH,
+        write_macro_loop,
+    );
+    measure(
+        "write_events! (2 fields)",
+        iterations / BATCH,
+        BATCH,
+        write_events_batch,
+    );
+
+    PROV.unregister();
+}
@@ -5,6 +5,7 @@
 //! Internal implementation details for tracelogging macros and tracelogging_dynamic.
 //! Contents subject to change without notice.
 
+use core::fmt;
 use core::mem;
 use core::slice;
 use core::time::Duration;
@@ -14,8 +15,18 @@ pub use crate::descriptors::slice_count;
 pub use crate::descriptors::EventDataDescriptor;
 pub use crate::descriptors::EventDescriptor;
 pub use crate::native::ProviderContext;
+pub use crate::provider::provider_defaults;
 pub use crate::provider::provider_new;
 pub use crate::provider::provider_write_transfer;
+pub use crate::provider::provider_write_transfer_ex;
+pub use crate::provider::EventWriteOptions;
+pub use crate::provider::ProviderDefaults;
+
+/// Returned by `write_event!` when a field value is too long to convert into its
+/// fixed-capacity buffer, e.g. a `bool32_native_slice` with more than
+/// [`Bool32Array::CAPACITY`] elements. This is the same error that ETW returns for an
+/// event that is too large.
+pub const ERROR_ARITHMETIC_OVERFLOW: u32 = 534;
 
 const UNIX_EPOCH_FILETIME: u64 = 0x19DB1DED53E8000;
 const FILETIME_PER_SECOND: u64 = 10000000;
@@ -31,6 +42,21 @@ pub fn meta_as_bytes<T>(meta: &T) -> &[u8] {
     }
 }
 
+/// Returns the metadata bytes from a [`RawStruct::RAW_META`](crate::RawStruct::RAW_META)
+/// as an array, for use in a `write_event!` metadata structure.
+pub const fn raw_meta_array<const SIZE: usize>(raw_meta: &[u8]) -> [u8; SIZE] {
+    assert!(raw_meta.len() == SIZE);
+    let mut result = [0; SIZE];
+
+    let mut i = 0;
+    while i != SIZE {
+        result[i] = raw_meta[i];
+        i += 1;
+    }
+
+    return result;
+}
+
 /// Returns the number of bytes needed to encode the specified tag.
 pub const fn tag_size(tag: u32) -> usize {
     return if 0 == (tag & 0x001FFFFF) {
@@ -65,6 +91,402 @@ pub const fn tag_encode<const SIZE: usize>(tag: u32) -> [u8; SIZE] {
     return result;
 }
 
+/// Splits a string into chunks for a `str8_chunked` field. Each chunk is at most 65535
+/// bytes (the limit for a counted field) and, where possible, ends on a UTF-8 character
+/// boundary. Unused chunks are empty. If the string is too long to fit in `N` chunks,
+/// the remainder is truncated.
+pub fn str8_chunks<const N: usize>(value: &[u8]) -> [&[u8]; N] {
+    const CHUNK_MAX: usize = 65535;
+    let mut result: [&[u8]; N] = [&[]; N];
+    let mut rest = value;
+    for chunk in result.iter_mut() {
+        let mut len = rest.len();
+        if len > CHUNK_MAX {
+            len = CHUNK_MAX;
+
+            // Back up to the start of a UTF-8 character (at most 3 continuation bytes).
+            let mut boundary = len;
+            while boundary > CHUNK_MAX - 3 && (rest[boundary] & 0xC0) == 0x80 {
+                boundary -= 1;
+            }
+
+            if (rest[boundary] & 0xC0) != 0x80 {
+                len = boundary;
+            }
+        }
+
+        *chunk = &rest[..len];
+        rest = &rest[len..];
+    }
+
+    return result;
+}
+
+/// Returns the Win32
+/// [`SYSTEMTIME`](https://learn.microsoft.com/windows/win32/api/minwinbase/ns-minwinbase-systemtime)
+/// fields (year, month, day-of-week, day, hour, minute, second, milliseconds)
+/// corresponding to a `chrono::NaiveDateTime`. Day-of-week uses the Win32 convention
+/// (Sunday = 0). Saturates to the range 1601..=30827 supported by `SYSTEMTIME`.
+#[cfg(feature = "chrono")]
+pub fn systemtime_words_from_naive(value: &chrono::NaiveDateTime) -> [u16; 8] {
+    use chrono::{Datelike, NaiveDate, Timelike};
+
+    let min = NaiveDate::from_ymd_opt(1601, 1, 1)
+        .unwrap()
+        .and_hms_milli_opt(0, 0, 0, 0)
+        .unwrap();
+    let max = NaiveDate::from_ymd_opt(30827, 12, 31)
+        .unwrap()
+        .and_hms_milli_opt(23, 59, 59, 999)
+        .unwrap();
+    let value = if *value < min {
+        min
+    } else if *value > max {
+        max
+    } else {
+        *value
+    };
+
+    // nanosecond() exceeds 999,999,999 during a leap second.
+    let millis = (value.nanosecond() / 1000000).min(999);
+
+    return [
+        value.year() as u16,
+        value.month() as u16,
+        value.weekday().num_days_from_sunday() as u16,
+        value.day() as u16,
+        value.hour() as u16,
+        value.minute() as u16,
+        value.second() as u16,
+        millis as u16,
+    ];
+}
+
+/// Returns the `Bool32` value (`0` or `1`) for a `bool32_native` field.
+pub const fn bool32_from_bool(value: &bool) -> i32 {
+    return *value as i32;
+}
+
+/// Fixed-capacity buffer holding the `Bool32` values for a `bool32_native_slice` field.
+/// A value with more than [`Bool32Array::CAPACITY`] elements is not converted.
+pub struct Bool32Array {
+    values: [i32; Bool32Array::CAPACITY],
+    len: Option<usize>,
+}
+
+impl Bool32Array {
+    /// Maximum number of elements logged by a `bool32_native_slice` field.
+    pub const CAPACITY: usize = 256;
+
+    /// Widens each `bool` in `value` to an `i32` if `value` has at most `CAPACITY`
+    /// elements.
+    pub fn new(value: &[bool]) -> Self {
+        let mut result = Self {
+            values: [0; Self::CAPACITY],
+            len: None,
+        };
+        if value.len() <= Self::CAPACITY {
+            for (dest, src) in result.values.iter_mut().zip(value) {
+                *dest = *src as i32;
+            }
+            result.len = Some(value.len());
+        }
+        return result;
+    }
+
+    /// Returns the widened values, or `None` if the value had too many elements.
+    pub fn as_slice(&self) -> Option<&[i32]> {
+        return self.len.map(|len| &self.values[..len]);
+    }
+}
+
+/// Returns the big-endian bytes for a `u32_be` field.
+pub const fn u32_be_bytes(value: &u32) -> [u8; 4] {
+    return value.to_be_bytes();
+}
+
+/// Returns the little-endian bytes for a `u32_le` field.
+pub const fn u32_le_bytes(value: &u32) -> [u8; 4] {
+    return value.to_le_bytes();
+}
+
+/// Returns the big-endian bytes for a `u64_be` field.
+pub const fn u64_be_bytes(value: &u64) -> [u8; 8] {
+    return value.to_be_bytes();
+}
+
+/// Returns the little-endian bytes for a `u64_le` field.
+pub const fn u64_le_bytes(value: &u64) -> [u8; 8] {
+    return value.to_le_bytes();
+}
+
+/// Returns the widened value for an `f16` field.
+#[cfg(feature = "half")]
+pub fn f32_from_f16(value: &half::f16) -> f32 {
+    return value.to_f32();
+}
+
+/// Fixed-capacity buffer holding the widened values for an `f16_slice` field.
+/// A value with more than [`F32Array::CAPACITY`] elements is not converted.
+#[cfg(feature = "half")]
+pub struct F32Array {
+    values: [f32; F32Array::CAPACITY],
+    len: Option<usize>,
+}
+
+#[cfg(feature = "half")]
+impl F32Array {
+    /// Maximum number of elements logged by an `f16_slice` field.
+    pub const CAPACITY: usize = 256;
+
+    /// Widens each value in `value` to an `f32` if `value` has at most `CAPACITY`
+    /// elements.
+    pub fn new(value: &[half::f16]) -> Self {
+        let mut result = Self {
+            values: [0.0; Self::CAPACITY],
+            len: None,
+        };
+        if value.len() <= Self::CAPACITY {
+            for (dest, src) in result.values.iter_mut().zip(value) {
+                *dest = src.to_f32();
+            }
+            result.len = Some(value.len());
+        }
+        return result;
+    }
+
+    /// Returns the widened values, or `None` if the value had too many elements.
+    pub fn as_slice(&self) -> Option<&[f32]> {
+        return self.len.map(|len| &self.values[..len]);
+    }
+}
+
+/// A value accepted by a `raw_pointer` field: a raw pointer or a reference.
+pub trait PointerAddress {
+    /// Returns the address of the pointed-to value.
+    fn address(&self) -> usize;
+}
+
+impl<T: ?Sized> PointerAddress for *const T {
+    fn address(&self) -> usize {
+        return self.cast::<u8>() as usize;
+    }
+}
+
+impl<T: ?Sized> PointerAddress for *mut T {
+    fn address(&self) -> usize {
+        return self.cast::<u8>() as usize;
+    }
+}
+
+impl<T: ?Sized> PointerAddress for &T {
+    fn address(&self) -> usize {
+        return (*self as *const T).cast::<u8>() as usize;
+    }
+}
+
+impl<T: ?Sized> PointerAddress for &mut T {
+    fn address(&self) -> usize {
+        return (&**self as *const T).cast::<u8>() as usize;
+    }
+}
+
+/// Returns the address logged for a `raw_pointer` field.
+pub fn address_from_pointer<P: PointerAddress>(value: P) -> usize {
+    return value.address();
+}
+
+/// A value accepted by a `raw_pointer_slice` field: a thin raw pointer or reference.
+///
+/// # Safety
+///
+/// Implementations must have the same size and alignment as `usize`.
+pub unsafe trait ThinPointer {}
+
+unsafe impl<T> ThinPointer for *const T {}
+unsafe impl<T> ThinPointer for *mut T {}
+unsafe impl<T> ThinPointer for &T {}
+unsafe impl<T> ThinPointer for &mut T {}
+
+/// Returns the addresses logged for a `raw_pointer_slice` field. The pointers are
+/// logged in place, without copying.
+pub fn addresses_from_pointers<P: ThinPointer>(value: &[P]) -> &[usize] {
+    // Safety: a thin pointer has the same size and alignment as usize.
+    return unsafe { slice::from_raw_parts(value.as_ptr().cast::<usize>(), value.len()) };
+}
+
+/// Fixed-capacity UTF-8 buffer holding the formatted value of a `display8` or `debug8`
+/// field. Output beyond [`FmtBuffer::CAPACITY`] bytes is truncated at a character
+/// boundary.
+pub struct FmtBuffer {
+    buf: [u8; FmtBuffer::CAPACITY],
+    len: usize,
+}
+
+impl FmtBuffer {
+    /// Maximum number of bytes logged by a `display8` or `debug8` field.
+    pub const CAPACITY: usize = 512;
+
+    /// Formats `value` using its `Display` implementation.
+    pub fn from_display<T: fmt::Display + ?Sized>(value: &T) -> Self {
+        let mut result = Self::new();
+        let _ = fmt::write(&mut result, format_args!("{}", value));
+        return result;
+    }
+
+    /// Formats `value` using its `Debug` implementation.
+    pub fn from_debug<T: fmt::Debug + ?Sized>(value: &T) -> Self {
+        let mut result = Self::new();
+        let _ = fmt::write(&mut result, format_args!("{:?}", value));
+        return result;
+    }
+
+    /// Returns the formatted UTF-8 bytes.
+    pub fn as_bytes(&self) -> &[u8] {
+        return &self.buf[..self.len];
+    }
+
+    const fn new() -> Self {
+        return Self {
+            buf: [0; Self::CAPACITY],
+            len: 0,
+        };
+    }
+}
+
+impl fmt::Write for FmtBuffer {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let available = Self::CAPACITY - self.len;
+        let mut len = s.len();
+        if len > available {
+            len = available;
+            while !s.is_char_boundary(len) {
+                len -= 1;
+            }
+        }
+
+        self.buf[self.len..self.len + len].copy_from_slice(&s.as_bytes()[..len]);
+        self.len += len;
+
+        // Stop formatting once the buffer is full.
+        return if len == s.len() {
+            Ok(())
+        } else {
+            Err(fmt::Error)
+        };
+    }
+}
+
+/// Fixed-capacity UTF-16 buffer holding the transcoded value of a `str16_from_utf8`
+/// field. A value longer than [`Utf16Buffer::CAPACITY`] UTF-16 code units is not
+/// transcoded.
+pub struct Utf16Buffer {
+    buf: [u16; Utf16Buffer::CAPACITY],
+    len: Option<usize>,
+}
+
+impl Utf16Buffer {
+    /// Maximum number of UTF-16 code units logged by a `str16_from_utf8` field.
+    pub const CAPACITY: usize = 512;
+
+    /// Transcodes `value` from UTF-8 to UTF-16 if the result fits in `CAPACITY` code
+    /// units.
+    pub fn new<T: AsRef<str> + ?Sized>(value: &T) -> Self {
+        let mut result = Self {
+            buf: [0; Self::CAPACITY],
+            len: None,
+        };
+        let mut len = 0;
+        for ch in value.as_ref().chars() {
+            if Self::CAPACITY - len < ch.len_utf16() {
+                return result;
+            }
+
+            len += ch.encode_utf16(&mut result.buf[len..]).len();
+        }
+        result.len = Some(len);
+        return result;
+    }
+
+    /// Returns the UTF-16 code units, or `None` if the value was too long.
+    pub fn as_slice(&self) -> Option<&[u16]> {
+        return self.len.map(|len| &self.buf[..len]);
+    }
+}
+
+/// Fixed-capacity buffer holding the data for a `map_str8` field: a `u16` element count
+/// followed by the counted `Key` and `Value` strings of each element. Elements that do
+/// not fit within [`MapStr8Buffer::CAPACITY`] bytes are dropped and counted in
+/// [`MapStr8Buffer::dropped`], which is logged as the `name_dropped` field.
+pub struct MapStr8Buffer {
+    buf: [u8; MapStr8Buffer::CAPACITY],
+    len: usize,
+    dropped: u16,
+}
+
+impl MapStr8Buffer {
+    /// Maximum number of bytes (including counts) logged by a `map_str8` field.
+    pub const CAPACITY: usize = 1024;
+
+    /// Consumes `items`, copying each key and value until an element does not fit in
+    /// the buffer, and counting the elements from that point on as dropped.
+    pub fn new<I, K, V>(items: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: AsRef<str>,
+        V: AsRef<str>,
+    {
+        let mut result = Self {
+            buf: [0; Self::CAPACITY],
+            len: 2,
+            dropped: 0,
+        };
+        let mut count: u16 = 0;
+        for (key, value) in items {
+            let key = key.as_ref().as_bytes();
+            let value = value.as_ref().as_bytes();
+            if result.dropped != 0
+                || count == u16::MAX
+                || Self::CAPACITY - result.len < 4 + key.len() + value.len()
+            {
+                result.dropped = result.dropped.saturating_add(1);
+                continue;
+            }
+
+            result.push_counted(key);
+            result.push_counted(value);
+            count += 1;
+        }
+
+        result.buf[..2].copy_from_slice(&count.to_le_bytes());
+        return result;
+    }
+
+    /// Returns the element count and elements.
+    pub fn as_bytes(&self) -> &[u8] {
+        return &self.buf[..self.len];
+    }
+
+    /// Returns the number of elements that did not fit (saturating at `u16::MAX`).
+    pub fn dropped(&self) -> &u16 {
+        return &self.dropped;
+    }
+
+    fn push_counted(&mut self, value: &[u8]) {
+        let end = self.len + 2 + value.len();
+        self.buf[self.len..self.len + 2].copy_from_slice(&(value.len() as u16).to_le_bytes());
+        self.buf[self.len + 2..end].copy_from_slice(value);
+        self.len = end;
+    }
+}
+
+/// Returns the JSON serialization of a `json8` field value, or an empty buffer if the
+/// value cannot be serialized (e.g. a map with non-string keys).
+#[cfg(feature = "serde")]
+pub fn to_json_bytes<T: serde::Serialize + ?Sized>(value: &T) -> alloc::vec::Vec<u8> {
+    return serde_json::to_vec(value).unwrap_or_default();
+}
+
 /// Returns the filetime corresponding to an i32 count of seconds since 1970 (time32_t).
 pub const fn filetime_from_time32(time32: &i32) -> i64 {
     let time = *time32;
@@ -87,6 +509,9 @@ pub const fn filetime_from_time64(time64: &i64) -> i64 {
 
 /// Returns the filetime corresponding to a duration returned by a successful call to
 /// `systemtime.duration_since(SystemTime::UNIX_EPOCH)`.
+///
+/// Sub-tick nanoseconds are rounded down (toward earlier times), consistent with
+/// [`filetime_from_duration_before_1970`], so the result is continuous across 1970.
 /// ```
 /// # use tracelogging::_internal as tli;
 /// # use std::time::SystemTime;
@@ -115,6 +540,10 @@ pub const fn filetime_from_duration_after_1970(duration: Duration) -> i64 {
 
 /// Returns the filetime corresponding to a duration returned by a failed call to
 /// `systemtime.duration_since(SystemTime::UNIX_EPOCH)`.
+///
+/// Sub-tick nanoseconds are rounded down (toward earlier times, i.e. away from 1970),
+/// consistent with [`filetime_from_duration_after_1970`], so the result is continuous
+/// across 1970, e.g. 1ns before 1970 is 1 tick before 1970.
 /// ```
 /// # use tracelogging::_internal as tli;
 /// # use std::time::SystemTime;
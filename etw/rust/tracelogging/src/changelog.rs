@@ -3,6 +3,126 @@
 #[allow(unused_imports)]
 use crate::*; // For docs
 
+/// # v1.3.0 (Unreleased)
+/// - Add [`Provider::is_registered`]. Writing an event via an unregistered
+///   provider is now always a no-op that returns 0.
+/// - New provider macro options `default_level` and `default_keyword` set the level
+///   and keyword used by events that do not specify them. The defaults are constants
+///   for events that name the provider by symbol or path, and are read from the
+///   provider for events that use a `&Provider` expression. (Internal change:
+///   `provider_new` now takes `&ProviderDefaults`.)
+/// - New provider macro option `trait` adds a custom provider trait to the
+///   provider's metadata.
+/// - New event macro option `write_flags` sends the event using `EventWriteEx` with
+///   the specified flags.
+/// - **Breaking:** Event macro reports a compile error for duplicate field names
+///   within the same event or struct.
+/// - New event macro field type `str8_chunked` logs strings longer than 65535 bytes
+///   as multiple fields.
+/// - New [`RawStruct`](trait@crate::RawStruct) trait and `#[derive(RawStruct)]` for
+///   `#[repr(C)]` types, logged via the new event macro field type `raw_struct_value`.
+/// - New `chrono` feature and event macro field type `win_systemtime_chrono` for
+///   logging a `chrono::NaiveDateTime` as a Win32 `SYSTEMTIME`.
+/// - New [`keywords!`] macro declares named keyword constants for use with the event
+///   macro `keyword` option.
+/// - New `log` feature adds conversions between [`Level`] and `log::Level`.
+/// - New provider macro option `derive_event_ids` gives events without an
+///   `id_version` option an id derived from the event name via the new
+///   [`event_id_from_name`] function.
+/// - New `disabled` feature compiles providers and events to no-op stubs with no
+///   metadata.
+/// - Guid: Fix `from_u128()` and `to_u128()` on big-endian targets and document
+///   their field order.
+/// - New [`write_event_fields!`] macro returns the fields declared by an event, e.g.
+///   for schema tests.
+/// - Events with more than 16 fields no longer inline their write helper, reducing
+///   the stack frame of the calling function.
+/// - New `bool32_native` and `bool32_native_slice` field types log Rust `bool` values
+///   as `Bool32`. If a `bool32_native_slice` value has more than 256 elements, the
+///   event is not written and `write_event!` returns `ERROR_ARITHMETIC_OVERFLOW`.
+/// - New `display8` and `debug8` field types log a value's `Display` or `Debug`
+///   output as a UTF-8 string.
+/// - New `serde` feature and `json8` field type log a `serde::Serialize` value as a
+///   JSON string.
+/// - Guid: Implement `Display` and `FromStr` (with new `GuidParseError`).
+/// - New `fixed_count(N)` field option logs a `_slice` field as a fixed-length array,
+///   e.g. a `[u8; 6]` MAC address.
+/// - New `struct_tuple` field type logs a tuple of scalar values as a struct.
+/// - New `map_str8` field type logs the key/value pairs of a map (or other iterable)
+///   as an array of struct, followed by a `NAME_dropped` field that counts the pairs
+///   that did not fit in the field's 1024-byte buffer.
+/// - New `raw_pointer` and `raw_pointer_slice` field types log the address of a
+///   reference or raw pointer without an explicit `as usize` cast.
+/// - New `str16_from_utf8` field type transcodes a `&str` value to a UTF-16 `str16`
+///   field. If the value has more than 512 UTF-16 code units, the event is not
+///   written and `write_event!` returns `ERROR_ARITHMETIC_OVERFLOW`.
+/// - **Breaking:** Event macro reports a compile error for `raw_field` and
+///   `raw_field_slice` fields with an `Invalid`, `Struct`, or `Sid` intype.
+/// - New `testing` feature makes providers always enabled in non-ETW builds so that
+///   tests can run the full `write_event!` path.
+/// - Document that event and field tags must be constant expressions.
+/// - Slice and string field types accept owned values (e.g. `Vec<u32>`, `[u32; N]`, or
+///   `String`) as well as references.
+/// - New provider macro option `keyword_name` records keyword names in the
+///   provider's metadata as a custom provider trait.
+/// - New `ascii_names` feature makes the macros report a compile error for non-ASCII
+///   provider, event, and field names.
+/// - New `char8_utf8` and `char8_utf8_slice` field types log `u8` values as UTF-8
+///   code units.
+/// - [`Level`], [`Opcode`], and [`Channel`]: New `ALL` constants list the named
+///   values and new `name()` methods return a value's name.
+/// - **Breaking:** Event macro reports a compile error if fewer logical fields follow a
+///   `raw_struct` or `raw_struct_slice` than its literal FIELD_COUNT.
+/// - New [`decode`] module decodes the metadata and data of a TraceLogging event
+///   without TDH.
+/// - Document the expected layout of `socketaddress` and `socketaddressc` values.
+/// - `write_event!` accepts a `&Provider` expression such as `self.provider` in place
+///   of the provider symbol. The expression is evaluated once, before the enabled check.
+/// - Add `Provider::registration_status()`, which returns the result of the most
+///   recent call to `register`, to help diagnose providers whose events never show up.
+/// - New `f16` and `f16_slice` field types log `half::f16` values by widening them to
+///   `F32`. They require the new `half` feature. If an `f16_slice` value has more than
+///   256 elements, the event is not written and `write_event!` returns
+///   `ERROR_ARITHMETIC_OVERFLOW`.
+/// - New `u32_be`, `u32_le`, `u64_be`, and `u64_le` field types log an integer as
+///   `Binary` bytes in a fixed byte order.
+/// - New `descriptor(&event_descriptor)` event option uses a runtime
+///   `EventDescriptor`, including for the enabled check.
+/// - New [`write_events!`] macro writes one event per item of an iterator with a
+///   single enabled check and shared metadata.
+/// - `format(...)` now reports a compile error when a named OutType does not make
+///   sense for the field's InType, e.g. `u8("x", &0, format(IPv4))`. The `raw_*` types
+///   still accept any format.
+/// - New field types `str8_opt` and `str16_opt` accept an `Option` of a string and log
+///   an empty string for `None`.
+/// - The "event metadata is too large" error now reports the event's estimated metadata
+///   size and no longer stops the size check partway through the event.
+/// - New `windows` feature adds `sid_from_psid` to log a `windows` crate `PSID` as a
+///   `win_sid` field.
+/// - `format(...)` or `tag(...)` on a `raw_data` field now gives an error that points to
+///   the `raw_meta` field instead of "unrecognized option".
+/// - Errors for a slice field whose value has the wrong type (e.g. a `&u32` passed to
+///   `u32_slice`) now point at the value instead of at the whole macro invocation.
+/// - "unrecognized option" errors now name the option and, for a near miss, suggest
+///   the intended option, e.g. `unrecognized option "keywords"; did you mean "keyword"?`.
+/// - Add `binary_u16`, `binary_u32`, and `binary_u64` field types, which log a slice of
+///   integers as a `Binary` blob with a `Hex` format.
+/// - Add `name_style(...)` event option to convert field names to `snake_case`,
+///   `lowerCamelCase`, or `UpperCamelCase` at compile time.
+/// - Add [`Provider::with_activity`], which sets the thread-local activity id for the
+///   duration of a closure and restores the previous value afterwards.
+/// - Add `define_provider!` options `task_name(task, "TaskName")`, `default_task(...)`,
+///   and `default_opcode(...)`. Events can refer to a named task with
+///   `task("TaskName")`, and events that omit `task` or `opcode` use the provider's
+///   defaults. A task name that is not defined by the provider is a compile error.
+/// - Add `guid_bytes` field type for logging a GUID stored as `[u8; 16]` in
+///   `Guid::to_bytes_le` byte order.
+/// - `write_event!` passes the `activity_id`, `related_id`, and `write_flags` options
+///   to its write helper as a single `EventWriteOptions` value. Events without these
+///   options pass no extra arguments. (Internal change: `provider_write_transfer_ex`
+///   now takes `&EventWriteOptions`.)
+pub mod v1_3_0 {}
+
 /// # v1.2.1 (2023-06-29)
 /// - Guid: Fix `as_bytes_raw()` method and `borrow<[u8; 16]>` trait.
 pub mod v1_2_1 {}
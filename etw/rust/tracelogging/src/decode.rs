@@ -0,0 +1,383 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+//! Decoding of TraceLogging-encoded events, e.g. for tests or for tools that need to
+//! display events without using TDH.
+//!
+//! [`decode_event`] takes the provider metadata, event metadata, and event data of an
+//! event (i.e. the content of the blocks that [`write_event!`](crate::write_event)
+//! passes to ETW) and returns a [`DecodedEvent`]. [`DecodedEvent::fields`] returns an
+//! iterator of `(name, InType, OutType, data)` tuples, one for each field in the
+//! event's metadata:
+//!
+//! - For a scalar field, the data is the value's bytes, e.g. 4 bytes for a `u32`.
+//! - For a counted field (e.g. `Str8` or `Binary`), the data is the content, without
+//!   the 2-byte length prefix.
+//! - For a nul-terminated field (e.g. `CStr8`), the data is the content, without the
+//!   terminating nul.
+//! - For an array field (the [InType] includes
+//!   [VariableCountFlag](InType::VariableCountFlag) or
+//!   [ConstantCountFlag](InType::ConstantCountFlag)), the data is the encoded array
+//!   elements, without the 2-byte element count prefix. (For an array of counted
+//!   strings, each element still has its own length prefix.)
+//! - For a struct field, the [InType] is [InType::Struct], the [OutType] holds the
+//!   number of fields in the struct, and the data is the encoded data of all of the
+//!   struct's fields. The struct's fields follow the struct's entry.
+//! - For an array of struct, the data is the encoded elements (without the element
+//!   count). The struct's fields are not listed separately.
+//!
+//! The decoder assumes that the event was written by the current process, e.g. that
+//! pointer-sized fields (e.g. [InType::HexSize]) have the size of `usize`.
+
+use core::fmt;
+use core::mem::size_of;
+use core::str::from_utf8;
+
+use crate::enums::InType;
+use crate::enums::OutType;
+
+/// Limit on the nesting of structs.
+const NESTING_MAX: u32 = 32;
+
+/// Decodes the metadata and data of a TraceLogging event.
+///
+/// - `provider_meta` is the provider's metadata, e.g. from
+///   [`Provider::raw_meta`](crate::Provider::raw_meta).
+/// - `event_meta` is the event's metadata (2-byte size, tag, name, and field
+///   definitions).
+/// - `data` is the event's field data (the concatenated content of the event's data
+///   blocks).
+///
+/// Returns an error if the provider or event metadata is invalid. Errors in the field
+/// definitions or data are returned by the [`DecodedEvent::fields`] iterator.
+pub fn decode_event<'a>(
+    provider_meta: &'a [u8],
+    event_meta: &'a [u8],
+    data: &'a [u8],
+) -> Result<DecodedEvent<'a>, DecodeError> {
+    let provider_meta = sized_meta(provider_meta, "invalid provider metadata size")?;
+    let (provider_name, _) = read_cstr8(provider_meta, 2, "invalid provider name")?;
+
+    let event_meta = sized_meta(event_meta, "invalid event metadata size")?;
+    let (event_tag, pos) = read_tag(event_meta, 2)?;
+    let (event_name, fields_pos) = read_cstr8(event_meta, pos, "invalid event name")?;
+
+    return Ok(DecodedEvent {
+        provider_name,
+        event_name,
+        event_tag,
+        fields_meta: &event_meta[fields_pos..],
+        data,
+    });
+}
+
+/// A TraceLogging event returned by [`decode_event`].
+#[derive(Clone, Copy, Debug)]
+pub struct DecodedEvent<'a> {
+    provider_name: &'a str,
+    event_name: &'a str,
+    event_tag: u32,
+    fields_meta: &'a [u8],
+    data: &'a [u8],
+}
+
+impl<'a> DecodedEvent<'a> {
+    /// Returns the name of the event's provider.
+    pub const fn provider_name(&self) -> &'a str {
+        return self.provider_name;
+    }
+
+    /// Returns the name of the event.
+    pub const fn event_name(&self) -> &'a str {
+        return self.event_name;
+    }
+
+    /// Returns the event's tag, or 0 if none.
+    pub const fn event_tag(&self) -> u32 {
+        return self.event_tag;
+    }
+
+    /// Returns an iterator of the event's fields, in metadata order.
+    pub const fn fields(&self) -> DecodedFields<'a> {
+        return DecodedFields {
+            meta: self.fields_meta,
+            meta_pos: 0,
+            data: self.data,
+            data_pos: 0,
+            failed: false,
+        };
+    }
+}
+
+/// Iterator of the fields of a [`DecodedEvent`]. Each item is `(name, intype,
+/// outtype, data)` or an error. After returning an error, the iterator returns `None`.
+#[derive(Clone, Debug)]
+pub struct DecodedFields<'a> {
+    meta: &'a [u8],
+    meta_pos: usize,
+    data: &'a [u8],
+    data_pos: usize,
+    failed: bool,
+}
+
+impl<'a> DecodedFields<'a> {
+    #[allow(clippy::type_complexity)]
+    fn next_field(&mut self) -> Result<(&'a str, InType, OutType, &'a [u8]), DecodeError> {
+        let (field, members_pos) = read_field_meta(self.meta, self.meta_pos)?;
+        let data = &self.data[self.data_pos..];
+        let (content, size) = field_size(self.meta, &field, members_pos, data, 0)?;
+        let is_struct = field.intype & InType::TypeMask == InType::Struct.as_int();
+        if is_struct && field.intype & InType::FlagMask == 0 {
+            // The struct's fields are decoded next, from the start of the struct's data.
+            self.meta_pos = members_pos;
+        } else {
+            self.meta_pos = if is_struct {
+                skip_members(self.meta, members_pos, field.outtype)?
+            } else {
+                members_pos
+            };
+            self.data_pos += size;
+        }
+
+        return Ok((
+            field.name,
+            InType::from_int(field.intype),
+            OutType::from_int(field.outtype),
+            &data[content.0..content.1],
+        ));
+    }
+}
+
+impl<'a> Iterator for DecodedFields<'a> {
+    type Item = Result<(&'a str, InType, OutType, &'a [u8]), DecodeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed || self.meta_pos >= self.meta.len() {
+            return None;
+        }
+
+        let result = self.next_field();
+        self.failed = result.is_err();
+        return Some(result);
+    }
+}
+
+/// The error returned when an event's metadata or data cannot be decoded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DecodeError {
+    message: &'static str,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return f.write_str(self.message);
+    }
+}
+
+/// Field definition from event metadata. The intype and outtype do not include the
+/// chain flag (0x80).
+struct FieldMeta<'a> {
+    name: &'a str,
+    intype: u8,
+    outtype: u8,
+    constant_count: u16,
+}
+
+const fn error(message: &'static str) -> DecodeError {
+    return DecodeError { message };
+}
+
+/// Returns the metadata, truncated to the size in its 2-byte size prefix.
+fn sized_meta<'a>(meta: &'a [u8], message: &'static str) -> Result<&'a [u8], DecodeError> {
+    let size = read_u16(meta, 0, message)? as usize;
+    return if size < 2 || size > meta.len() {
+        Err(error(message))
+    } else {
+        Ok(&meta[..size])
+    };
+}
+
+fn read_u16(bytes: &[u8], pos: usize, message: &'static str) -> Result<u16, DecodeError> {
+    return match bytes.get(pos..pos + 2) {
+        Some(b) => Ok(u16::from_le_bytes([b[0], b[1]])),
+        None => Err(error(message)),
+    };
+}
+
+/// Reads a nul-terminated UTF-8 string. Returns the string and the position after the nul.
+fn read_cstr8<'a>(
+    bytes: &'a [u8],
+    pos: usize,
+    message: &'static str,
+) -> Result<(&'a str, usize), DecodeError> {
+    let rest = bytes.get(pos..).ok_or(error(message))?;
+    let len = rest.iter().position(|&b| b == 0).ok_or(error(message))?;
+    let value = from_utf8(&rest[..len]).map_err(|_| error(message))?;
+    return Ok((value, pos + len + 1));
+}
+
+/// Reads a 1..4 byte tag, as encoded by [`tag_encode`](crate::_internal::tag_encode).
+fn read_tag(bytes: &[u8], mut pos: usize) -> Result<(u32, usize), DecodeError> {
+    let mut tag = 0u32;
+    let mut shift = 21;
+    for _ in 0..4 {
+        let b = *bytes.get(pos).ok_or(error("invalid tag"))?;
+        pos += 1;
+        tag |= ((b & 0x7F) as u32) << shift;
+        if b & 0x80 == 0 {
+            return Ok((tag, pos));
+        }
+        shift -= 7;
+    }
+    return Err(error("invalid tag"));
+}
+
+/// Reads a field definition. Returns the field and the position after the definition.
+fn read_field_meta(meta: &[u8], pos: usize) -> Result<(FieldMeta<'_>, usize), DecodeError> {
+    const INVALID: &str = "invalid field definition";
+    let (name, mut pos) = read_cstr8(meta, pos, INVALID)?;
+
+    let intype_chain = *meta.get(pos).ok_or(error(INVALID))?;
+    pos += 1;
+
+    let mut outtype_chain = 0;
+    if intype_chain & 0x80 != 0 {
+        outtype_chain = *meta.get(pos).ok_or(error(INVALID))?;
+        pos += 1;
+        if outtype_chain & 0x80 != 0 {
+            (_, pos) = read_tag(meta, pos)?;
+        }
+    }
+
+    let intype = intype_chain & 0x7F;
+    let mut constant_count = 0;
+    match intype & InType::FlagMask {
+        InType::ConstantCountFlag => {
+            constant_count = read_u16(meta, pos, INVALID)?;
+            pos += 2;
+        }
+        InType::CustomFlag => return Err(error("custom fields are not supported")),
+        _ => {}
+    }
+
+    return Ok((
+        FieldMeta {
+            name,
+            intype,
+            outtype: outtype_chain & OutType::TypeMask,
+            constant_count,
+        },
+        pos,
+    ));
+}
+
+/// Returns the position after the definitions of a struct's fields.
+fn skip_members(meta: &[u8], mut pos: usize, count: u8) -> Result<usize, DecodeError> {
+    let mut remaining = count as usize;
+    while remaining != 0 {
+        let field;
+        (field, pos) = read_field_meta(meta, pos)?;
+        remaining -= 1;
+        if field.intype & InType::TypeMask == InType::Struct.as_int() {
+            remaining += field.outtype as usize;
+        }
+    }
+    return Ok(pos);
+}
+
+/// Returns the size of the data of a struct's fields, starting at data[0].
+fn members_size(
+    meta: &[u8],
+    mut pos: usize,
+    count: u8,
+    data: &[u8],
+    nesting: u32,
+) -> Result<usize, DecodeError> {
+    let mut size = 0;
+    for _ in 0..count {
+        let field;
+        (field, pos) = read_field_meta(meta, pos)?;
+        let (_, field_size) = field_size(meta, &field, pos, &data[size..], nesting)?;
+        size += field_size;
+        if field.intype & InType::TypeMask == InType::Struct.as_int() {
+            pos = skip_members(meta, pos, field.outtype)?;
+        }
+    }
+    return Ok(size);
+}
+
+/// Returns the content range and total size of a field's data, starting at data[0].
+/// members_pos is the position after the field's definition.
+fn field_size(
+    meta: &[u8],
+    field: &FieldMeta,
+    members_pos: usize,
+    data: &[u8],
+    nesting: u32,
+) -> Result<((usize, usize), usize), DecodeError> {
+    let (count, mut size) = match field.intype & InType::FlagMask {
+        0 => return element_size(meta, field, members_pos, data, nesting),
+        InType::VariableCountFlag => (read_u16(data, 0, "data too short")?, 2),
+        _ => (field.constant_count, 0),
+    };
+
+    let start = size;
+    for _ in 0..count {
+        let (_, element_size) = element_size(meta, field, members_pos, &data[size..], nesting)?;
+        size += element_size;
+    }
+    return Ok(((start, size), size));
+}
+
+/// Returns the content range and total size of one element of a field's data,
+/// starting at data[0].
+fn element_size(
+    meta: &[u8],
+    field: &FieldMeta,
+    members_pos: usize,
+    data: &[u8],
+    nesting: u32,
+) -> Result<((usize, usize), usize), DecodeError> {
+    const TOO_SHORT: &str = "data too short";
+    let fixed_size = match InType::from_int(field.intype & InType::TypeMask) {
+        InType::I8 | InType::U8 => 1,
+        InType::I16 | InType::U16 => 2,
+        InType::I32 | InType::U32 | InType::F32 | InType::Bool32 | InType::Hex32 => 4,
+        InType::I64 | InType::U64 | InType::F64 | InType::FileTime | InType::Hex64 => 8,
+        InType::Guid | InType::SystemTime => 16,
+        InType::_HexSize_PlatformSpecific => size_of::<usize>(),
+        InType::Sid => 8 + 4 * (*data.get(1).ok_or(error(TOO_SHORT))? as usize),
+        InType::Binary | InType::BinaryC | InType::Str8 | InType::Str16 => {
+            let size = 2 + read_u16(data, 0, TOO_SHORT)? as usize;
+            if size > data.len() {
+                return Err(error(TOO_SHORT));
+            }
+            return Ok(((2, size), size));
+        }
+        InType::CStr8 => {
+            let len = data.iter().position(|&b| b == 0).ok_or(error(TOO_SHORT))?;
+            return Ok(((0, len), len + 1));
+        }
+        InType::CStr16 => {
+            let len = data
+                .chunks_exact(2)
+                .position(|c| c == [0, 0])
+                .ok_or(error(TOO_SHORT))?;
+            return Ok(((0, len * 2), len * 2 + 2));
+        }
+        InType::Struct => {
+            if nesting >= NESTING_MAX {
+                return Err(error("structs nested too deeply"));
+            }
+            let size = members_size(meta, members_pos, field.outtype, data, nesting + 1)?;
+            return Ok(((0, size), size));
+        }
+        _ => return Err(error("invalid field type")),
+    };
+
+    return if fixed_size > data.len() {
+        Err(error(TOO_SHORT))
+    } else {
+        Ok(((0, fixed_size), fixed_size))
+    };
+}
@@ -8,6 +8,10 @@ use crate::enums::Channel;
 use crate::enums::Level;
 use crate::enums::Opcode;
 
+/// The NULL SID (S-1-0-0), returned by [sid_from_psid] for a null or invalid `PSID`.
+#[cfg(feature = "windows")]
+static NULL_SID: [u8; 12] = [1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+
 /// Characteristics of an ETW event: severity level, category bits, etc.
 #[repr(C)]
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
@@ -107,6 +111,79 @@ impl EventDescriptor {
     }
 }
 
+/// Returns the event id that [`write_event!`](crate::write_event) derives from the
+/// event's name when the provider was defined with the `derive_event_ids` option and
+/// the event does not specify an `id_version` option.
+///
+/// The id is computed from the UTF-8 bytes of the name as follows, so tools can
+/// reproduce it:
+///
+/// - Compute the 32-bit FNV-1a hash of the name's bytes (offset basis `0x811C9DC5`,
+///   prime `0x01000193`).
+/// - Fold the hash to 16 bits: `(hash ^ (hash >> 16)) as u16`.
+/// - If the result is 0 (reserved for "no id assigned"), use 1 instead.
+///
+/// The id is stable across builds and platforms, but different names may produce the
+/// same id.
+pub const fn event_id_from_name(event_name: &str) -> u16 {
+    let bytes = event_name.as_bytes();
+    let mut hash = 0x811C9DC5u32;
+    let mut i = 0;
+    while i < bytes.len() {
+        hash ^= bytes[i] as u32;
+        hash = hash.wrapping_mul(0x01000193);
+        i += 1;
+    }
+
+    let id = (hash ^ (hash >> 16)) as u16;
+    return if id == 0 { 1 } else { id };
+}
+
+/// Returns the bytes of the [SID](https://docs.microsoft.com/windows/win32/api/winnt/ns-winnt-sid)
+/// that `psid` points to, for use as the value of a `win_sid` field. Requires the
+/// `windows` feature.
+///
+/// The length of the SID is computed from its `SubAuthorityCount` as
+/// `8 + 4 * SubAuthorityCount`. If `psid` is null, or if the SID's revision is not `1`
+/// or its `SubAuthorityCount` is greater than 15, this returns the bytes of the NULL
+/// SID (S-1-0-0) instead of reading past the SID's header.
+///
+/// ```
+/// # use tracelogging as tlg;
+/// # tlg::define_provider!(MY_PROVIDER, "MyCompany.MyComponent");
+/// # let psid = windows::Win32::Foundation::PSID::default();
+/// tlg::write_event!(
+///     MY_PROVIDER,
+///     "MyEvent",
+///     win_sid("User", unsafe { tlg::sid_from_psid(psid) }),
+/// );
+/// ```
+///
+/// # Safety
+///
+/// If `psid` is not null, it must point to at least 2 readable bytes. If those bytes
+/// are a valid revision and `SubAuthorityCount`, it must point to at least
+/// `8 + 4 * SubAuthorityCount` readable bytes. The bytes must not change for the
+/// lifetime `'a`.
+#[cfg(feature = "windows")]
+pub unsafe fn sid_from_psid<'a>(psid: windows::Win32::Foundation::PSID) -> &'a [u8] {
+    const SID_REVISION: u8 = 1;
+    const SID_MAX_SUB_AUTHORITIES: u8 = 15;
+
+    let ptr = psid.0 as *const u8;
+    if ptr.is_null() {
+        return &NULL_SID;
+    }
+
+    let revision = *ptr;
+    let sub_authority_count = *ptr.add(1);
+    if revision != SID_REVISION || sub_authority_count > SID_MAX_SUB_AUTHORITIES {
+        return &NULL_SID;
+    }
+
+    return core::slice::from_raw_parts(ptr, 8 + 4 * sub_authority_count as usize);
+}
+
 /// Describes a block of data to be sent to ETW via EventWrite.
 #[repr(C)]
 #[derive(Debug, Default)]
@@ -167,7 +244,10 @@ impl<'a> EventDataDescriptor<'a> {
 
     /// Returns an EventDataDescriptor for a nul-terminated string.
     /// Sets the reserved field to 0.
-    /// Returned descriptor does not include the nul-termination.
+    /// Returned descriptor does not include the nul-termination. The value is
+    /// truncated at its first nul (if any), so a value with or without a trailing nul
+    /// produces the same descriptor. `write_event!` follows this descriptor with a
+    /// descriptor for a single nul, so the string is always terminated exactly once.
     pub fn from_cstr<T: Copy + Default + Eq>(mut value: &'a [T]) -> Self {
         let mut value_len = value.len();
 
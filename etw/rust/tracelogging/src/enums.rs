@@ -42,6 +42,25 @@ impl Channel {
 
     /// Channel for events from machine-generated manifests.
     pub const ProviderMetadata: Channel = Channel(12);
+
+    /// The named channels, in numeric order. For example, a tool can use this with
+    /// [Channel::name] to list the available channels.
+    pub const ALL: &'static [Channel] = &[
+        Channel::TraceClassic,
+        Channel::TraceLogging,
+        Channel::ProviderMetadata,
+    ];
+
+    /// Returns the name of this channel (e.g. `"TraceLogging"`), or `None` if the
+    /// channel does not have a name.
+    pub const fn name(self) -> Option<&'static str> {
+        return match self.0 {
+            0 => Some("TraceClassic"),
+            11 => Some("TraceLogging"),
+            12 => Some("ProviderMetadata"),
+            _ => None,
+        };
+    }
 }
 
 impl fmt::Display for Channel {
@@ -63,6 +82,13 @@ impl From<Channel> for u8 {
 }
 
 /// Indicates the severity of an event. Use Verbose if unsure.
+///
+/// Note that ETW levels are ordered by numeric value, so more severe levels compare
+/// as *less than* less severe levels, e.g. `Level::Critical < Level::Verbose`. This
+/// trips people up because "higher level" means "more verbose", not "more important".
+/// An ETW session configured for level `N` receives events with level `0` through `N`,
+/// i.e. the events whose level is `<= N`. [Level::LogAlways] (0) is the lowest value,
+/// so events with this level are not filtered by level.
 #[repr(C)]
 #[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub struct Level(pub(crate) u8);
@@ -92,6 +118,64 @@ impl Level {
     pub const Informational: Level = Level(4);
     /// Verbose event.
     pub const Verbose: Level = Level(5);
+
+    /// The named levels, in numeric order. For example, a tool can use this with
+    /// [Level::name] to list the available levels.
+    pub const ALL: &'static [Level] = &[
+        Level::LogAlways,
+        Level::Critical,
+        Level::Error,
+        Level::Warning,
+        Level::Informational,
+        Level::Verbose,
+    ];
+
+    /// Returns the name of this level (e.g. `"Warning"`), or `None` if the level
+    /// does not have a name.
+    pub const fn name(self) -> Option<&'static str> {
+        return match self.0 {
+            0 => Some("LogAlways"),
+            1 => Some("Critical"),
+            2 => Some("Error"),
+            3 => Some("Warning"),
+            4 => Some("Informational"),
+            5 => Some("Verbose"),
+            _ => None,
+        };
+    }
+
+    /// Returns the ETW level corresponding to the specified `log` crate level.
+    ///
+    /// `Error`, `Warn`, and `Info` map to [Level::Error], [Level::Warning], and
+    /// [Level::Informational]. `Debug` and `Trace` both map to [Level::Verbose].
+    #[cfg(feature = "log")]
+    pub const fn from_log_level(level: log::Level) -> Level {
+        return match level {
+            log::Level::Error => Level::Error,
+            log::Level::Warn => Level::Warning,
+            log::Level::Info => Level::Informational,
+            log::Level::Debug => Level::Verbose,
+            log::Level::Trace => Level::Verbose,
+        };
+    }
+
+    /// Returns the `log` crate level corresponding to this ETW level.
+    ///
+    /// [Level::LogAlways], [Level::Critical], and [Level::Error] map to `Error`,
+    /// [Level::Warning] maps to `Warn`, [Level::Informational] maps to `Info`,
+    /// [Level::Verbose] maps to `Debug`, and levels greater than [Level::Verbose] map to
+    /// `Trace`. Converting a `log` level to ETW and back returns the original level,
+    /// except that `Trace` becomes `Debug`.
+    #[cfg(feature = "log")]
+    pub const fn to_log_level(self) -> log::Level {
+        return match self.0 {
+            0..=2 => log::Level::Error,
+            3 => log::Level::Warn,
+            4 => log::Level::Info,
+            5 => log::Level::Debug,
+            _ => log::Level::Trace,
+        };
+    }
 }
 
 impl fmt::Display for Level {
@@ -235,6 +319,71 @@ impl Opcode {
     /// Alias for `CollectionStop`.
     /// Deprecated - prefer [`CollectionStop`](Opcode::CollectionStop).
     pub const DC_Stop: Opcode = Self::CollectionStop;
+
+    /// The named opcodes (not including deprecated aliases), in numeric order. For
+    /// example, a tool can use this with [Opcode::name] to list the available opcodes.
+    pub const ALL: &'static [Opcode] = &[
+        Opcode::Info,
+        Opcode::ActivityStart,
+        Opcode::ActivityStop,
+        Opcode::CollectionStart,
+        Opcode::CollectionStop,
+        Opcode::Extension,
+        Opcode::Reply,
+        Opcode::Resume,
+        Opcode::Suspend,
+        Opcode::Send,
+        Opcode::Receive,
+        Opcode::ReservedOpcode241,
+        Opcode::ReservedOpcode242,
+        Opcode::ReservedOpcode243,
+        Opcode::ReservedOpcode244,
+        Opcode::ReservedOpcode245,
+        Opcode::ReservedOpcode246,
+        Opcode::ReservedOpcode247,
+        Opcode::ReservedOpcode248,
+        Opcode::ReservedOpcode249,
+        Opcode::ReservedOpcode250,
+        Opcode::ReservedOpcode251,
+        Opcode::ReservedOpcode252,
+        Opcode::ReservedOpcode253,
+        Opcode::ReservedOpcode254,
+        Opcode::ReservedOpcode255,
+    ];
+
+    /// Returns the name of this opcode (e.g. `"ActivityStart"`), or `None` if the
+    /// opcode does not have a name. Deprecated aliases are not returned.
+    pub const fn name(self) -> Option<&'static str> {
+        return match self.0 {
+            0 => Some("Info"),
+            1 => Some("ActivityStart"),
+            2 => Some("ActivityStop"),
+            3 => Some("CollectionStart"),
+            4 => Some("CollectionStop"),
+            5 => Some("Extension"),
+            6 => Some("Reply"),
+            7 => Some("Resume"),
+            8 => Some("Suspend"),
+            9 => Some("Send"),
+            240 => Some("Receive"),
+            241 => Some("ReservedOpcode241"),
+            242 => Some("ReservedOpcode242"),
+            243 => Some("ReservedOpcode243"),
+            244 => Some("ReservedOpcode244"),
+            245 => Some("ReservedOpcode245"),
+            246 => Some("ReservedOpcode246"),
+            247 => Some("ReservedOpcode247"),
+            248 => Some("ReservedOpcode248"),
+            249 => Some("ReservedOpcode249"),
+            250 => Some("ReservedOpcode250"),
+            251 => Some("ReservedOpcode251"),
+            252 => Some("ReservedOpcode252"),
+            253 => Some("ReservedOpcode253"),
+            254 => Some("ReservedOpcode254"),
+            255 => Some("ReservedOpcode255"),
+            _ => None,
+        };
+    }
 }
 
 impl fmt::Display for Opcode {
@@ -5,6 +5,7 @@ use core::borrow;
 use core::convert::TryInto;
 use core::fmt;
 use core::mem;
+use core::str;
 use core::str::from_utf8;
 
 /// [GUID](https://docs.microsoft.com/windows/win32/api/guiddef/ns-guiddef-guid)
@@ -132,6 +133,14 @@ impl Guid {
     }
 
     /// Creates a GUID from a u128 value.
+    ///
+    /// The u128 holds the GUID's fields from most-significant to least-significant:
+    /// `data1` in bits 127..96, `data2` in bits 95..80, `data3` in bits 79..64, and
+    /// `data4[0..8]` in bits 63..0 (`data4[0]` most significant). This matches the
+    /// order of the hex digits in the GUID's string form, the big-endian byte order
+    /// of [`Guid::to_bytes_be`], and the `u128` representation used by the `uuid`
+    /// crate, and is independent of the platform's byte order, so
+    /// `Guid::from_u128(&x).to_u128() == x` for any `x`.
     /// ```
     /// # use tracelogging::Guid;
     /// assert_eq!(
@@ -140,9 +149,9 @@ impl Guid {
     /// ```
     pub const fn from_u128(value: &u128) -> Self {
         return Self {
-            data1: u32::from_le(value.wrapping_shr(96) as u32),
-            data2: u16::from_le(value.wrapping_shr(80) as u16),
-            data3: u16::from_le(value.wrapping_shr(64) as u16),
+            data1: value.wrapping_shr(96) as u32,
+            data2: value.wrapping_shr(80) as u16,
+            data3: value.wrapping_shr(64) as u16,
             data4: (*value as u64).to_be_bytes(),
         };
     }
@@ -246,7 +255,7 @@ impl Guid {
 
     /// Returns this implementation's in-memory byte representation.
     pub const fn as_bytes_raw(&self) -> &[u8; 16] {
-        return unsafe { mem::transmute(self) };
+        return unsafe { mem::transmute::<&Guid, &[u8; 16]>(self) };
     }
 
     /// Returns the bytes of the GUID in big-endian (RFC) byte order.
@@ -307,7 +316,8 @@ impl Guid {
         ];
     }
 
-    /// Returns the GUID as a u128 value.
+    /// Returns the GUID as a u128 value. This is the inverse of [`Guid::from_u128`] and
+    /// uses the same field order.
     /// ```
     /// use tracelogging::Guid;
     /// assert_eq!(
@@ -316,9 +326,9 @@ impl Guid {
     /// ```
     #[allow(clippy::wrong_self_convention)]
     pub const fn to_u128(&self) -> u128 {
-        return (self.data1.to_le() as u128) << 96
-            | (self.data2.to_le() as u128) << 80
-            | (self.data3.to_le() as u128) << 64
+        return (self.data1 as u128) << 96
+            | (self.data2 as u128) << 80
+            | (self.data3 as u128) << 64
             | u64::from_be_bytes(self.data4) as u128;
     }
 
@@ -381,10 +391,44 @@ impl fmt::Debug for Guid {
     }
 }
 
+impl fmt::Display for Guid {
+    /// Format the GUID, e.g. "a3a2a1a0-b1b0-c1c0-d7d6-d5d4d3d2d1d0".
+    /// ```
+    /// # use tracelogging::Guid;
+    /// let guid = Guid::from_fields(0xa3a2a1a0, 0xb1b0, 0xc1c0, [0xd7, 0xd6, 0xd5, 0xd4, 0xd3, 0xd2, 0xd1, 0xd0]);
+    /// assert_eq!(guid.to_string(), "a3a2a1a0-b1b0-c1c0-d7d6-d5d4d3d2d1d0");
+    /// assert_eq!(guid.to_string().parse::<Guid>(), Ok(guid));
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return f.write_str(from_utf8(&self.to_utf8_bytes()).unwrap());
+    }
+}
+
+impl str::FromStr for Guid {
+    type Err = GuidParseError;
+
+    /// Parses a GUID with optional {} and optional '-', as in [`Guid::try_parse`].
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        return Self::try_parse(value).ok_or(GuidParseError { _private: () });
+    }
+}
+
+/// The error returned when a string cannot be parsed as a [`Guid`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GuidParseError {
+    _private: (),
+}
+
+impl fmt::Display for GuidParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return f.write_str("invalid GUID syntax");
+    }
+}
+
 impl borrow::Borrow<[u8; 16]> for Guid {
     /// Returns this implementation's in-memory byte representation.
     fn borrow(&self) -> &[u8; 16] {
-        return unsafe { mem::transmute(self) };
+        return unsafe { mem::transmute::<&Guid, &[u8; 16]>(self) };
     }
 }
 
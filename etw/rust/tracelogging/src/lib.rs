@@ -103,6 +103,9 @@
 //! tracefmt -o MyTraceData.txt MyTraceFile.etl
 //! ```
 
+#[cfg(feature = "serde")]
+extern crate alloc;
+
 /// Creates a static symbol representing an ETW provider.
 ///
 /// `define_provider!(PROVIDER_SYMBOL, "ProviderName", options...);`
@@ -111,6 +114,14 @@
 ///
 /// - `id("ProviderGuid")`
 /// - `group_id("ProviderGroupGuid")`
+/// - `default_level(Verbose)`
+/// - `default_keyword(0x123)`
+/// - `default_opcode(Info)`
+/// - `default_task(24)`
+/// - `derive_event_ids()`
+/// - `trait(TraitType, b"TraitData")`
+/// - `keyword_name(0x10, "KeywordName")`
+/// - `task_name(24, "TaskName")`
 ///
 /// # Overview
 ///
@@ -126,10 +137,17 @@
 /// to code approximately like:
 ///
 /// ```ignore
+/// #[doc(hidden)]
+/// const _TLG_DEFAULTS_MY_PROVIDER: tracelogging::_internal::ProviderDefaults = ...;
 /// static MY_PROVIDER: tracelogging::Provider =
-///     tracelogging::Provider::new("MyProviderName");
+///     tracelogging::Provider::new("MyProviderName", &_TLG_DEFAULTS_MY_PROVIDER);
 /// ```
 ///
+/// The hidden constant holds the event defaults set by the `default_level`,
+/// `default_keyword`, `default_opcode`, `default_task`, `derive_event_ids`, and
+/// `task_name` options. [`write_event!`] uses it when an event does not specify the
+/// corresponding option, so the event's descriptor is still a compile-time constant.
+///
 /// **Note:** The provider starts out unregistered. You must call
 /// `MY_PROVIDER.register();` to open the provider before using it. With the exception
 /// of [`Provider::register`], all operations on an unregistered provider are no-ops
@@ -156,6 +174,40 @@
 /// MY_PROVIDER.unregister();
 /// ```
 ///
+/// # Multiple registrations
+///
+/// Each `PROVIDER_SYMBOL` is exactly one ETW registration with its own registration
+/// handle and its own enablement state. To get several independent registrations of
+/// the same logical provider (e.g. one per tenant, so that each can be enabled and
+/// unregistered separately), define one symbol per registration using the same
+/// provider name and options. Each symbol is registered and unregistered separately,
+/// and [`write_event!`] sends the event via the registration of the symbol it is
+/// given, so a runtime choice of registration is a `match` over the symbols:
+///
+/// ```
+/// use tracelogging as tlg;
+///
+/// tlg::define_provider!(TENANT_A, "MyCompany.MyComponent");
+/// tlg::define_provider!(TENANT_B, "MyCompany.MyComponent");
+///
+/// // Safety: If this is a DLL, you MUST unregister both providers before unload.
+/// unsafe { TENANT_A.register(); }
+/// unsafe { TENANT_B.register(); }
+///
+/// let tenant_b = true;
+/// if tenant_b {
+///     tlg::write_event!(TENANT_B, "MyEvent");
+/// } else {
+///     tlg::write_event!(TENANT_A, "MyEvent");
+/// }
+///
+/// TENANT_A.unregister();
+/// TENANT_B.unregister();
+/// ```
+///
+/// If the number of registrations is not known at compile time, use the
+/// `tracelogging_dynamic` crate, where each `Provider` object is a registration.
+///
 /// # Syntax
 ///
 /// `define_provider!(PROVIDER_SYMBOL, "ProviderName", options...);`
@@ -196,6 +248,91 @@
 ///
 ///   Example: `group_id("f73b8292-f610-4fa7-ba62-708353d162c4")`
 ///
+/// - `trait(trait_type, trait_data)`
+///
+///   *Advanced:* Adds a custom
+///   [provider trait](https://docs.microsoft.com/windows/win32/etw/provider-traits)
+///   to the provider's metadata. Most providers do not need custom traits.
+///
+///   `trait_type` must be an integer literal in the range 2 to 255 (type 1 is the
+///   provider group trait, which is set via `group_id`). `trait_data` must be a byte
+///   string literal like `b"..."` or a byte array literal like `&[1, 2, 3]`. The
+///   trait's 2-byte size and 1-byte type are added automatically. The `trait` option
+///   may be specified more than once. Custom traits are stored after the provider
+///   group trait, in the order they are specified. The total size of the provider's
+///   metadata (name and traits) must be less than 32KB.
+///
+///   Example: `trait(2, b"\x01\x02")`
+///
+/// - `keyword_name(keyword, "KeywordName")`
+///
+///   *Advanced:* Records a friendly name for a keyword in the provider's metadata so
+///   that trace tools can display keyword names instead of bit masks. This does not
+///   affect which events are enabled.
+///
+///   `keyword` must be a non-zero integer literal and the name must be a non-empty
+///   string literal. The `keyword_name` option may be specified more than once, once
+///   per keyword. The names are stored in a custom provider trait of type 128 (so
+///   `trait(128, ...)` may not be used together with `keyword_name`). The trait data
+///   is a sequence of entries, each containing the keyword as a little-endian `u64`
+///   followed by the nul-terminated UTF-8 name, in the order they are specified.
+///
+///   Example: `keyword_name(0x10, "Network")`
+///
+/// - `default_level(event_level)`
+///
+///   Specifies the level that [`write_event!`] will use for events from this provider
+///   that do not specify a `level` option. If not specified, the default level is
+///   [Level::Verbose]. The value must be a constant [Level] value.
+///
+///   Example: `default_level(Informational)`
+///
+/// - `default_keyword(event_keyword)`
+///
+///   Specifies the keyword that [`write_event!`] will use for events from this provider
+///   that do not specify any `keyword` options. If not specified, the default keyword
+///   is `0x1`. The value must be a constant `u64` value.
+///
+///   Example: `default_keyword(0x20)`
+///
+/// - `default_opcode(event_opcode)`
+///
+///   Specifies the opcode that [`write_event!`] will use for events from this provider
+///   that do not specify an `opcode` option. If not specified, the default opcode is
+///   [Opcode::Info]. The value must be a constant [Opcode] value.
+///
+///   Example: `default_opcode(Start)`
+///
+/// - `default_task(event_task)`
+///
+///   Specifies the task that [`write_event!`] will use for events from this provider
+///   that do not specify a `task` option. If not specified, the default task is `0`.
+///   The value must be a constant `u16` value.
+///
+///   Example: `default_task(5)`
+///
+/// - `task_name(task, "TaskName")`
+///
+///   Gives a name to a task value so that events from this provider can use
+///   `task("TaskName")` instead of repeating the number. This does not change the
+///   provider's metadata. The names are resolved when an event that uses
+///   `task("TaskName")` is compiled, so an unknown name is a compile error.
+///
+///   `task` must be an integer literal in the range 1 to 65535 and the name must be a
+///   string literal containing a valid identifier (ASCII letters, digits, and `_`, not
+///   starting with a digit). The `task_name` option may be specified more than once,
+///   but each task and each name may be used only once.
+///
+///   Example: `task_name(47, "PacketSent")`
+///
+/// - `derive_event_ids()`
+///
+///   Events from this provider that do not specify an `id_version` option will use an
+///   id derived from the event name,
+///   [`event_id_from_name("EventName")`](event_id_from_name), instead of 0. The derived id is stable as long as the event name does not change,
+///   so tools can use it for event filtering without manually-assigned ids. An explicit
+///   `id_version` option always takes precedence.
+///
 /// - `debug()`
 ///
 ///   For non-production diagnostics: prints the expanded macro during compilation.
@@ -220,6 +357,9 @@ pub use tracelogging_macros::define_provider;
 /// - `tag(0x123)`
 /// - `id_version(23, 0)`
 /// - `channel(TraceLogging)`
+/// - `descriptor(&event_descriptor)`
+/// - `write_flags(0x2)`
+/// - `name_style(snake_case)`
 /// - `debug()`
 ///
 /// [Fields:](#fields-1)
@@ -260,10 +400,36 @@ pub use tracelogging_macros::define_provider;
 /// logging sessions are listening for the event, `write_event!` immediately returns 0
 /// (`ERROR_SUCCESS`). Otherwise, it returns the value returned by the underlying Windows
 /// [EventWriteTransfer](https://docs.microsoft.com/windows/win32/api/evntprov/nf-evntprov-eventwritetransfer)
-/// API. Since most components treat logging APIs as fire-and-forget, this value should
-/// normally be ignored in production code. It is generally used only for debugging and
+/// API. If a field value is too long for the fixed-size buffer that `write_event!` uses
+/// to convert it (e.g. a `bool32_native_slice` with more than 256 elements),
+/// `write_event!` does not write the event and returns 534 (`ERROR_ARITHMETIC_OVERFLOW`),
+/// the same error that ETW returns for an event that is too large. Since most
+/// components treat logging APIs as fire-and-forget, this value should normally be
+/// ignored in production code. It is generally used only for debugging and
 /// troubleshooting.
 ///
+/// The event's metadata and `EVENT_DESCRIPTOR` are compile-time constants, including
+/// descriptor values that come from the provider's defaults. (If the provider is
+/// specified by an expression instead of a symbol, the defaults are read from the
+/// provider before the enabled check.) The fixed parts of the event (provider metadata,
+/// event metadata, and descriptor) therefore cost the same as in a hand-written write
+/// whose metadata and descriptor are prepared in advance, and there is no need to cache
+/// or pre-build anything between writes of the same event. The `write_cost` example
+/// times `write_event!` against such a prepared write:
+/// `cargo run --release --example write_cost` (with an ETW session listening to the
+/// example's provider, or with `--features testing` on non-Windows platforms). On
+/// x86-64 Linux with `--features testing`, where the ETW write itself is a no-op, both
+/// measured 0.2 ns per write for a 4-field event, i.e. `write_event!` adds no per-write
+/// work of its own. On Windows, the cost of a `write_event!` is dominated by
+/// `EventWriteTransfer` and by the size of the field data.
+///
+/// The functions used to build these constants are `const fn`, so code that needs the
+/// same values in its own `const` items (e.g. for an init routine that runs in a
+/// `const fn`) can compute them the same way: [`Guid::from_fields`],
+/// [`Guid::from_u128`], [`event_id_from_name`], `_internal::EventDescriptor::from_parts`,
+/// `_internal::tag_size`, and `_internal::tag_encode`. The enabled check and the write
+/// itself happen at runtime, so `write_event!` cannot be used in a `const fn`.
+///
 /// # Limitations
 ///
 /// ETW is optimized for efficient handling of small events. ETW events have the
@@ -274,6 +440,65 @@ pub use tracelogging_macros::define_provider;
 ///   delivered to any sessions.
 /// - If the total event size exceeds the buffer size of a logger session, the event will
 ///   not be delivered to that session.
+/// - If the event's metadata (event name string, field name strings, and field type
+///   codes) exceeds 65535 bytes, the TraceLogging encoding will be unable to represent
+///   the event. `write_event!` will generate a compile error that reports the event's
+///   estimated metadata size. The estimate assumes that every tag needs 4 bytes, so it
+///   may be slightly larger than the actual size.
+///
+///   ```compile_fail
+///   # use tracelogging as tlg;
+///   # tlg::define_provider!(MY_PROVIDER, "MyCompany.MyComponent");
+///   # macro_rules! wide_event {
+///   #     ($event:tt, $field:tt, $($struct_name:tt)*) => {
+///   #         tlg::write_event!(
+///   #             MY_PROVIDER,
+///   #             $event,
+///   #             $(struct($struct_name, { raw_meta($field, U8) }),)*
+///   #         )
+///   #     };
+///   # }
+///   // Error: 64 structs with 1015-byte field names need 65536 bytes.
+///   wide_event!(
+///       "EEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEE",
+///   #   "xxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxx",
+///   #   "s00" "s01" "s02" "s03" "s04" "s05" "s06" "s07"
+///   #   "s08" "s09" "s10" "s11" "s12" "s13" "s14" "s15"
+///   #   "s16" "s17" "s18" "s19" "s20" "s21" "s22" "s23"
+///   #   "s24" "s25" "s26" "s27" "s28" "s29" "s30" "s31"
+///   #   "s32" "s33" "s34" "s35" "s36" "s37" "s38" "s39"
+///   #   "s40" "s41" "s42" "s43" "s44" "s45" "s46" "s47"
+///   #   "s48" "s49" "s50" "s51" "s52" "s53" "s54" "s55"
+///   #   "s56" "s57" "s58" "s59" "s60" "s61" "s62" "s63"
+///   );
+///   ```
+///
+///   ```compile_fail
+///   # use tracelogging as tlg;
+///   # tlg::define_provider!(MY_PROVIDER, "MyCompany.MyComponent");
+///   # macro_rules! wide_event {
+///   #     ($event:tt, $field:tt, $($struct_name:tt)*) => {
+///   #         tlg::write_event!(
+///   #             MY_PROVIDER,
+///   #             $event,
+///   #             $(struct($struct_name, { raw_meta($field, U8) }),)*
+///   #         )
+///   #     };
+///   # }
+///   // Error: 64 structs with 1200-byte field names need 77375 bytes.
+///   wide_event!(
+///       "EEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEE",
+///   #   "xxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxx",
+///   #   "s00" "s01" "s02" "s03" "s04" "s05" "s06" "s07"
+///   #   "s08" "s09" "s10" "s11" "s12" "s13" "s14" "s15"
+///   #   "s16" "s17" "s18" "s19" "s20" "s21" "s22" "s23"
+///   #   "s24" "s25" "s26" "s27" "s28" "s29" "s30" "s31"
+///   #   "s32" "s33" "s34" "s35" "s36" "s37" "s38" "s39"
+///   #   "s40" "s41" "s42" "s43" "s44" "s45" "s46" "s47"
+///   #   "s48" "s49" "s50" "s51" "s52" "s53" "s54" "s55"
+///   #   "s56" "s57" "s58" "s59" "s60" "s61" "s62" "s63"
+///   );
+///   ```
 /// - If the event contains more than 128 chunks of data, ETW will not be able to process
 ///   the event. The `write_event!` macro uses one chunk for every simple field and two
 ///   chunks for complex fields (binary, string, and slice fields). `write_event!` will
@@ -290,6 +515,15 @@ pub use tracelogging_macros::define_provider;
 ///   macro will generate a compile error if a struct has more than 127 fields. You might
 ///   be able to work around this limitation by using arrays or by logging a series of
 ///   simpler events instead of a single complex event.
+/// - The thread id and process id in the event header are always set by ETW to the ids
+///   of the thread and process that call `write_event!`. They cannot be overridden. To
+///   record the thread or process on whose behalf an event is written (e.g. when writing
+///   from a completion handler), log the id as a field, e.g. `tid("WorkerTid", &tid)`.
+/// - An event cannot carry caller-supplied `EVENT_HEADER` extended data items, e.g. a
+///   schema GUID. `EventWriteEx` has no parameter for them. The only extended items
+///   that ETW attaches to a `write_event!` event are the event's TraceLogging metadata
+///   and the provider's name and traits (set with the `define_provider!` `group_id`
+///   and `trait` options). To record other data, log it as a field.
 ///
 /// # Example
 ///
@@ -324,7 +558,21 @@ pub use tracelogging_macros::define_provider;
 ///   This is a symbol that was created by [`define_provider!`].
 ///
 ///   This should be the original symbol name created by [`define_provider!`], not a
-///   reference or alias.
+///   reference or alias. The symbol may be written as a path, e.g.
+///   `super::MY_PROVIDER`. The provider's defaults are found next to the symbol, so a
+///   symbol brought into scope with `use` must be written as a path to its
+///   definition or as an expression, e.g. `&MY_PROVIDER`.
+///
+///   Alternatively, this may be an expression that evaluates to a `&Provider`, e.g.
+///   `self.provider`, `get_provider()`, or `&MY_PROVIDER`. The expression is evaluated
+///   exactly once, before the enabled check (or never, if the crate's `disabled`
+///   feature is enabled). A lone identifier or path is always treated as a
+///   provider symbol, so a local `&Provider` variable must be written as an expression,
+///   e.g. `(provider)`. The provider's `default_level`, `default_keyword`,
+///   `default_opcode`, `default_task`, and `derive_event_ids` options also apply to
+///   events written through a provider expression, but they are read from the
+///   provider each time the event is written, before the enabled check, instead of
+///   being compile-time constants.
 ///
 /// - `"EventName"`
 ///
@@ -342,8 +590,10 @@ pub use tracelogging_macros::define_provider;
 ///   Level is important for event filtering so all events should specify a meaningful
 ///   non-zero level.
 ///
-///   If the `level` option is not specified then the event's level will be
-///   [Level::Verbose]. If the level is specified it must be a constant [Level] value.
+///   If the `level` option is not specified then the event's level will be the
+///   provider's `default_level`, which is [Level::Verbose] unless the provider was
+///   defined with a `default_level` option. If the level is specified it must be a
+///   constant [Level] value.
 ///
 /// - `keyword(event_keyword)`
 ///
@@ -358,12 +608,31 @@ pub use tracelogging_macros::define_provider;
 ///   Keyword is important for event filtering so all events should specify a meaningful
 ///   non-zero keyword.
 ///
-///   If no `keyword` options are specified then the event's keyword will be `0x1` to
-///   flag the event as not having any assigned keyword. If the `keyword` option is
+///   If no `keyword` options are specified then the event's keyword will be the
+///   provider's `default_keyword`, which is `0x1` (flagging the event as not having any
+///   assigned keyword) unless the provider was defined with a `default_keyword`
+///   option. If the `keyword` option is
 ///   specified it must be a constant `u64` value. The `keyword` option may be specified
 ///   more than once, in which case all provided keyword values will be OR'ed together in
 ///   the event's keyword.
 ///
+///   A keyword of `0` does not disable the event. ETW treats an event with keyword `0`
+///   as matching every keyword filter, so it is enabled whenever the provider is
+///   enabled at the event's level. To remove an event from some builds, put a
+///   `#[cfg(...)]` attribute on the `write_event!` statement (or use the crate's
+///   `disabled` feature to remove all events).
+///
+///   The keyword may be a literal or a path to a `u64` constant, e.g.
+///   `keyword(MyKeywords::NETWORK)`. Use [`keywords!`] to declare a set of named
+///   keyword constants.
+///
+///   The keyword is evaluated in a `const` context, so it cannot use trait methods such
+///   as `Into<u64>` or the `|` operator of a non-integer type. To use a flags type (e.g.
+///   a type declared with the `bitflags` crate), convert it to `u64` with a `const fn`,
+///   e.g. `keyword(MyFlags::NETWORK.bits())` or
+///   `keyword(MyFlags::NETWORK.union(MyFlags::STORAGE).bits())`. Passing the flags value
+///   itself is a compile error ("expected `u64`").
+///
 /// - `opcode(event_opcode)`
 ///
 ///   Specifies the opcode attribute for the event.
@@ -371,9 +640,10 @@ pub use tracelogging_macros::define_provider;
 ///   The opcode indicates special event semantics such as "activity start" or "activity
 ///   stop" that can be used by the event decoder to group events together.
 ///
-///   If the `opcode` option is not specified the event's opcode will be [Opcode::Info],
-///   indicating no special semantics. If the opcode is specified it must be a constant
-///   [Opcode] value.
+///   If the `opcode` option is not specified the event's opcode will be the provider's
+///   `default_opcode`, which is [Opcode::Info] (indicating no special semantics) unless
+///   the provider was defined with a `default_opcode` option. If the opcode is specified
+///   it must be a constant [Opcode] value.
 ///
 /// - `activity_id(&guid)`
 ///
@@ -382,6 +652,7 @@ pub use tracelogging_macros::define_provider;
 ///   If not specified, the event will use the current thread's thread-local activity id.
 ///   If specified, the value must be a reference to a [Guid] or a reference to a
 ///   `[u8; 16]`.
+///   As with field values, the expression is evaluated only if the event is enabled.
 ///
 /// - `related_id(&guid)`
 ///
@@ -394,6 +665,7 @@ pub use tracelogging_macros::define_provider;
 ///   If not specified, the event will not have any related activity id.
 ///   If specified, the value must be a reference to a [Guid] or a reference to a
 ///   `[u8; 16]`.
+///   As with field values, the expression is evaluated only if the event is enabled.
 ///
 /// - `task(event_task)`
 ///
@@ -405,8 +677,19 @@ pub use tracelogging_macros::define_provider;
 ///   `47` might be assigned semantics "Packet Sent", and then the "IPv4-Packet-Sent" and
 ///   "IPv6-Packet-Sent" events might both be set to use task `47`.
 ///
-///   If the `task` option is not specified then the event's task will be 0. If the task
-///   is specified it must be a constant `u16` value.
+///   If the `task` option is not specified then the event's task will be the provider's
+///   `default_task`, which is 0 unless the provider was defined with a `default_task`
+///   option. If the task is specified it must be a constant `u16` value or a string
+///   literal naming a task defined by the provider's `task_name` option, e.g.
+///   `task("PacketSent")`. A task name requires the provider to be specified by its
+///   symbol (not by an expression), and a name that the provider does not define is a
+///   compile error.
+///
+///   ```compile_fail
+///   use tracelogging as tlg;
+///   tlg::define_provider!(PROV, "MyCompany.MyComponent", task_name(47, "PacketSent"));
+///   tlg::write_event!(PROV, "Sent", task("PacketRecieved")); // Unknown task name.
+///   ```
 ///
 /// - `tag(event_tag)`
 ///
@@ -421,6 +704,23 @@ pub use tracelogging_macros::define_provider;
 ///   If the `tag` option is not specified the event's tag will be 0. If specified, the
 ///   tag must be a constant `u32` value in the range 0 to 0x0FFFFFFF.
 ///
+///   Event and field tags are stored in the event's metadata, which is generated at
+///   compile time, so the tag must be a constant expression, e.g. a literal, a `const`
+///   item, or a call to a `const fn` with constant arguments. A tag computed at runtime
+///   results in a compile error ("attempt to use a non-constant value in a constant")
+///   that points at the tag expression. To record a runtime value, log it as a field.
+///
+///   ```compile_fail
+///   # use tracelogging as tlg;
+///   # tlg::define_provider!(MY_PROVIDER, "MyCompany.MyComponent");
+///   let runtime_tag = 0x1234u32;
+///   tlg::write_event!(
+///       MY_PROVIDER,
+///       "MyEvent",
+///       tag(runtime_tag), // Error: tag must be a constant.
+///   );
+///   ```
+///
 /// - `id_version(event_id, event_version)`
 ///
 ///   Specifies a manually-assigned numeric id for this event, along with a version
@@ -433,9 +733,21 @@ pub use tracelogging_macros::define_provider;
 ///   is made to the event, e.g. when a field is removed or a field changes type.
 ///
 ///   If the `id_version` option is not specified then the event's id and version will be
-///   0, indicating that no id has been assigned to the event. If id and version are
-///   specified, the id must be a constant `u16` value and the version must be a constant
-///   `u8` value.
+///   0, indicating that no id has been assigned to the event. (If the provider was
+///   defined with the `derive_event_ids` option, the event's id will instead be
+///   [`event_id_from_name`] of the event name and the version will be 0.) If id and
+///   version are specified, the id must be a constant `u16` value and the version must
+///   be a constant `u8` value.
+///
+///   ```compile_fail
+///   # use tracelogging as tlg;
+///   # tlg::define_provider!(MY_PROVIDER, "MyCompany.MyComponent");
+///   tlg::write_event!(
+///       MY_PROVIDER,
+///       "MyEvent",
+///       id_version(5), // Error: id_version requires both an id and a version.
+///   );
+///   ```
 ///
 /// - `channel(event_channel)`
 ///
@@ -448,10 +760,95 @@ pub use tracelogging_macros::define_provider;
 ///   [Channel::TraceLogging]. If the channel is specified it must be a constant
 ///   [Channel] value.
 ///
+/// - `descriptor(&event_descriptor)`
+///
+///   *Advanced:* Uses a caller-supplied `&_internal::EventDescriptor` instead of a
+///   descriptor built from constant options. This allows one `write_event!` to log events whose id, version, level,
+///   keyword, etc. are computed at runtime, e.g. from a table.
+///
+///   The expression is evaluated exactly once, before the enabled check (or never, if
+///   the crate's `disabled` feature is enabled), and the enabled check uses the descriptor's `level` and `keyword`. The `descriptor` option
+///   cannot be combined with the `id_version`, `channel`, `level`, `opcode`, `task`, or
+///   `keyword` options.
+///
+///   ```compile_fail
+///   # use tracelogging as tlg;
+///   # tlg::define_provider!(MY_PROVIDER, "MyCompany.MyComponent");
+///   # let desc = tlg::_internal::EventDescriptor::new(tlg::Level::Verbose, 1);
+///   tlg::write_event!(
+///       MY_PROVIDER,
+///       "MyEvent",
+///       descriptor(&desc),
+///       level(Warning), // Error: level comes from the descriptor.
+///   );
+///   ```
+///
+/// - `write_flags(flags)`
+///
+///   *Advanced:* Specifies flags to pass to
+///   [EventWriteEx](https://docs.microsoft.com/windows/win32/api/evntprov/nf-evntprov-eventwriteex),
+///   e.g. `0x1` (`EVENT_WRITE_FLAG_NO_FAULTING`) or `0x2`
+///   (`EVENT_WRITE_FLAG_INPRIVATE`). Most events do not need to specify the
+///   `write_flags` option.
+///
+///   If the `write_flags` option is not specified, the event is sent using
+///   `EventWriteTransfer`. If specified, the event is sent using `EventWriteEx` with
+///   the specified flags. The flags value must be a `u32` expression and is evaluated
+///   only if the event is enabled.
+///
 /// - `debug()`
 ///
 ///   For non-production diagnostics: prints the expanded macro during compilation.
 ///
+/// - `name_style(STYLE)`
+///
+///   Converts the event's field names to `STYLE` at compile time, e.g. for a backend
+///   that requires a particular casing. `STYLE` is `snake_case` (`"http_status"`),
+///   `lowerCamelCase` (`"httpStatus"`), or `UpperCamelCase` (`"HttpStatus"`). Names are
+///   split into words at `_`, `-`, space, and case changes, so `"HTTPStatus"`,
+///   `"http_status"`, and `"http-status"` all become `"httpStatus"` with
+///   `lowerCamelCase`. If not present, field names are used as written.
+///
+///   The style applies to all of the event's field names, including names in structs,
+///   `struct_tuple` element names, and the `Key` and `Value` fields of a `map_str8`
+///   field. It does not change the event name, the provider name, or the names that a
+///   `RawStruct` type provides. The option must come before the event's fields.
+///
+///   ```compile_fail
+///   # use tracelogging as tlg;
+///   # tlg::define_provider!(MY_PROVIDER, "MyCompany.MyComponent");
+///   tlg::write_event!(
+///       MY_PROVIDER,
+///       "MyEvent",
+///       u32("fieldOne", &1),
+///       name_style(snake_case), // Error: name_style must come before the event's fields.
+///   );
+///   ```
+///
+/// An unrecognized event option or field type is a compile error. If the name is close
+/// to a known option or field type, the error suggests it, e.g.
+/// `unrecognized option "keywords"; did you mean "keyword"?`.
+///
+/// ```compile_fail
+/// # use tracelogging as tlg;
+/// # tlg::define_provider!(MY_PROVIDER, "MyCompany.MyComponent");
+/// tlg::write_event!(
+///     MY_PROVIDER,
+///     "MyEvent",
+///     keywords(0x1), // Error: unrecognized option "keywords"; did you mean "keyword"?
+/// );
+/// ```
+///
+/// ```compile_fail
+/// # use tracelogging as tlg;
+/// # tlg::define_provider!(MY_PROVIDER, "MyCompany.MyComponent");
+/// tlg::write_event!(
+///     MY_PROVIDER,
+///     "MyEvent",
+///     frobnicate(1), // Error: unrecognized option "frobnicate"
+/// );
+/// ```
+///
 /// ## Fields
 ///
 /// Event content is provided in fields. Each field is added to the event with a field
@@ -482,13 +879,80 @@ pub use tracelogging_macros::define_provider;
 ///
 /// - `"NAME"` is a string literal that specifies the name of the field.
 ///
+///   Field names must be unique within the event or struct that directly contains them.
+///   Using the same name for two fields at the same nesting level is a compile error.
+///   Fields in different structs may use the same name. (Duplicate names are not checked
+///   after a `raw_struct` or `raw_struct_slice` field since the nesting of subsequent
+///   fields is not known at compile time.)
+///
+///   ```compile_fail
+///   # use tracelogging as tlg;
+///   # tlg::define_provider!(MY_PROVIDER, "MyCompany.MyComponent");
+///   tlg::write_event!(
+///       MY_PROVIDER,
+///       "MyEvent",
+///       u32("count", &1),
+///       u32("count", &2), // Error: duplicate field name.
+///   );
+///   ```
+///
+///   By default, provider, event, and field names may contain any UTF-8 characters
+///   except `'\0'`. Some ETW decoders assume names are ASCII. If the `ascii_names`
+///   feature is enabled, a name with non-ASCII characters is a compile error.
+///
+#[cfg_attr(feature = "ascii_names", doc = "  ```compile_fail")]
+#[cfg_attr(not(feature = "ascii_names"), doc = "  ```")]
+///   # use tracelogging as tlg;
+///   # tlg::define_provider!(MY_PROVIDER, "MyCompany.MyComponent");
+///   tlg::write_event!(
+///       MY_PROVIDER,
+///       "MyEvent",
+///       u32("café", &1), // Error if the ascii_names feature is enabled.
+///   );
+///   ```
+///
 /// - `VALUE_REF` is a Rust expression that provides a reference to the value of the
 ///   field.
 ///
 ///   Field types that expect a slice `&[T]` type will also accept types that implement
 ///   the [`AsRef<[T]>`](AsRef) trait. For example, the `str8` field types expect a
 ///   `&[u8]` but will also accept `&str` or `&String` because those types implement
-///   `AsRef<[u8]>`.
+///   `AsRef<[u8]>`. Such values may be provided either by reference or by value, e.g. a
+///   `u32_slice` field accepts `&[u32]`, `&Vec<u32>`, `Vec<u32>`, `&[u32; N]`, or
+///   `[u32; N]`.
+///
+///   A value of the wrong type is a compile error that points at the value and names
+///   the expected `AsRef<[T]>` bound, e.g. passing a `&u32` to a `u32_slice` field
+///   reports ``the trait `AsRef<[u32]>` is not implemented for `u32` ``. The
+///   [field type tables](#normal-field-types) list the Rust type that each field type
+///   expects.
+///
+///   ```compile_fail
+///   # use tracelogging as tlg;
+///   # tlg::define_provider!(MY_PROVIDER, "MyCompany.MyComponent");
+///   let value = 5u32;
+///   tlg::write_event!(
+///       MY_PROVIDER,
+///       "MyEvent",
+///       u32_slice("Values", &value), // Error: u32_slice expects &[u32], not &u32.
+///   );
+///   ```
+///
+///   ```compile_fail
+///   # use tracelogging as tlg;
+///   # tlg::define_provider!(MY_PROVIDER, "MyCompany.MyComponent");
+///   let values = [1u8, 2, 3];
+///   tlg::write_event!(
+///       MY_PROVIDER,
+///       "MyEvent",
+///       u32_slice("Values", &values), // Error: u32_slice expects &[u32], not &[u8].
+///   );
+///   ```
+///
+///   Likewise, the `binary` and `binaryc` field types accept `&str`, `String`, `&[u8]`,
+///   or `Vec<u8>`. A string value is logged as its UTF-8 bytes with a `Binary` (or
+///   `BinaryC`) intype, so decoders will show the bytes rather than the text. Use
+///   `str8` to log the text.
 ///
 ///   The field value expression will be evaluated only if the event is enabled, i.e.
 ///   only if at least one logging session is listening to the provider and has filtering
@@ -499,7 +963,8 @@ pub use tracelogging_macros::define_provider;
 ///   This is usually omitted because most providers do not use field tags.
 ///
 ///   If not present, the field tag is `0`. If present, the TAG must be a 28-bit constant
-///   `u32` value in the range `0` to `0x0FFFFFFF`.
+///   `u32` value in the range `0` to `0x0FFFFFFF`. As with the event's `tag` option, TAG
+///   must be a constant expression (a runtime value is a compile error).
 ///
 /// - `format(FORMAT)` specifies an [OutType] that overrides the format that would
 ///   normally apply for the given `TYPE`.
@@ -510,7 +975,40 @@ pub use tracelogging_macros::define_provider;
 ///   the same effect.
 ///
 ///   If not present, the field's format depends on the field's `TYPE`. If present, the
-///   FORMAT must be a constant [OutType] value.
+///   FORMAT must be a constant [OutType] value. A named FORMAT must make sense for the
+///   field's [InType], e.g. `IPv4` is allowed for `u32` but not for `u8`, and `Json` is
+///   allowed for `str8` but not for `i32`. The `raw_field` and `raw_meta` types accept
+///   any FORMAT.
+///
+///   ```compile_fail
+///   # use tracelogging as tlg;
+///   # tlg::define_provider!(MY_PROVIDER, "MyCompany.MyComponent");
+///   tlg::write_event!(
+///       MY_PROVIDER,
+///       "MyEvent",
+///       u8("Address", &0, format(IPv4)), // Error: IPv4 requires a 32-bit value.
+///   );
+///   ```
+///
+///   ```compile_fail
+///   # use tracelogging as tlg;
+///   # tlg::define_provider!(MY_PROVIDER, "MyCompany.MyComponent");
+///   tlg::write_event!(
+///       MY_PROVIDER,
+///       "MyEvent",
+///       f32("Value", &1.0, format(OutType::Hex)), // Error: Hex is not valid for f32.
+///   );
+///   ```
+///
+/// - `fixed_count(COUNT)` (only for `_slice` types) logs the value as a fixed-length
+///   array of `COUNT` elements instead of a variable-length array.
+///
+///   The `VALUE_REF` must then be a reference to an array `&[T; COUNT]`, and the field
+///   uses one block of data (no length prefix) with the element count recorded in the
+///   event's metadata. This is useful for small fixed-size values, e.g.
+///   `u8_hex_slice("MacAddress", &mac, fixed_count(6))` for a `[u8; 6]` value.
+///
+///   If present, the COUNT must be an integer literal in the range `1` to `65535`.
 ///
 /// Example:
 ///
@@ -534,13 +1032,20 @@ pub use tracelogging_macros::define_provider;
 /// | Field Type | Rust Type | ETW Type
 /// |------------|-----------|---------
 /// | `binary` | `&[u8]` | [`Binary`](InType::Binary)
+/// | `binary_u16` [^binary_n] | `&[u16]` | [`Binary`](InType::Binary) + [`Hex`](OutType::Hex)
+/// | `binary_u32` [^binary_n] | `&[u32]` | [`Binary`](InType::Binary) + [`Hex`](OutType::Hex)
+/// | `binary_u64` [^binary_n] | `&[u64]` | [`Binary`](InType::Binary) + [`Hex`](OutType::Hex)
 /// | `binaryc` [^binaryc] | `&[u8]` | [`BinaryC`](InType::BinaryC)
 /// | `bool8` | `&bool` | [`U8`](InType::U8) + [`Boolean`](OutType::Boolean)
 /// | `bool8_slice` | `&[bool]` | [`U8`](InType::U8) + [`Boolean`](OutType::Boolean)
 /// | `bool32` | `&i32` | [`Bool32`](InType::Bool32)
 /// | `bool32_slice` | `&[i32]` | [`Bool32`](InType::Bool32)
+/// | `bool32_native` [^bool32_native] | `&bool` | [`Bool32`](InType::Bool32)
+/// | `bool32_native_slice` [^bool32_native] | `&[bool]` | [`Bool32`](InType::Bool32)
 /// | `char8_cp1252` | `&u8` | [`U8`](InType::U8) + [`String`](OutType::String)
 /// | `char8_cp1252_slice` | `&[u8]` | [`U8`](InType::U8) + [`String`](OutType::String)
+/// | `char8_utf8` [^char8_utf8] | `&u8` | [`U8`](InType::U8) + [`Utf8`](OutType::Utf8)
+/// | `char8_utf8_slice` [^char8_utf8] | `&[u8]` | [`U8`](InType::U8) + [`Utf8`](OutType::Utf8)
 /// | `char16` | `&u16` | [`U16`](InType::U16) + [`String`](OutType::String)
 /// | `char16_slice` | `&[u16]` | [`U16`](InType::U16) + [`String`](OutType::String)
 /// | `codepointer` | `&usize` | [`HexSize`](InType::HexSize) + [`CodePointer`](OutType::CodePointer)
@@ -552,13 +1057,18 @@ pub use tracelogging_macros::define_provider;
 /// | `cstr16` [^cstr] | `&[u16]` | [`CStr16`](InType::CStr16)
 /// | `cstr16_json` [^cstr] | `&[u16]` | [`CStr16`](InType::CStr16) + [`Json`](OutType::Json)
 /// | `cstr16_xml` [^cstr] | `&[u16]` | [`CStr16`](InType::CStr16) + [`Xml`](OutType::Xml)
+/// | `debug8` [^fmt] | `&impl Debug` | [`Str8`](InType::Str8) + [`Utf8`](OutType::Utf8)
+/// | `display8` [^fmt] | `&impl Display` | [`Str8`](InType::Str8) + [`Utf8`](OutType::Utf8)
 /// | `errno` [^errno] | `&i32` | [`I32`](InType::I32)
 /// | `errno_slice` [^errno] | `&[i32]` | [`I32`](InType::I32)
+/// | `f16` [^f16] | `&half::f16` | [`F32`](InType::F32)
+/// | `f16_slice` [^f16] | `&[half::f16]` | [`F32`](InType::F32)
 /// | `f32` | `&f32` | [`F32`](InType::F32)
 /// | `f32_slice` | `&[f32]` | [`F32`](InType::F32)
 /// | `f64` | `&f64` | [`F64`](InType::F64)
 /// | `f64_slice` | `&[f64]` | [`F64`](InType::F64)
 /// | `guid` | `&tracelogging::Guid` | [`Guid`](InType::Guid)
+/// | `guid_bytes` [^guid_bytes] | `&[u8; 16]` | [`Guid`](InType::Guid)
 /// | `guid_slice` | `&[tracelogging::Guid]` | [`Guid`](InType::Guid)
 /// | `hresult` | `&i32` | [`I32`](InType::I32) + [`HResult`](OutType::HResult)
 /// | `hresult_slice` | `&[i32]` | [`I32`](InType::I32) + [`HResult`](OutType::HResult)
@@ -586,24 +1096,32 @@ pub use tracelogging_macros::define_provider;
 /// | `isize_slice` | `&[isize]` | [`ISize`](InType::ISize)
 /// | `isize_hex` | `&isize` | [`HexSize`](InType::HexSize)
 /// | `isize_hex_slice` | `&[isize]` | [`HexSize`](InType::HexSize)
-/// | `pid` | `&u32` | [`U32`](InType::U32) + [`Pid`](OutType::Pid)
-/// | `pid_slice` | `&[u32]` | [`U32`](InType::U32) + [`Pid`](OutType::Pid)
+/// | `json8` [^json] | `&impl Serialize` | [`Str8`](InType::Str8) + [`Json`](OutType::Json)
+/// | `map_str8` [^map] | `&impl IntoIterator` | [`Struct`](InType::Struct)
+/// | `pid` [^pid] | `&u32` | [`U32`](InType::U32) + [`Pid`](OutType::Pid)
+/// | `pid_slice` [^pid] | `&[u32]` | [`U32`](InType::U32) + [`Pid`](OutType::Pid)
 /// | `pointer` | `&usize` | [`HexSize`](InType::HexSize)
 /// | `pointer_slice` | `&[usize]` | [`HexSize`](InType::HexSize)
 /// | `port` | `&u16` | [`U16`](InType::U16) + [`Port`](OutType::Port)
 /// | `port_slice` | `&[u16]` | [`U16`](InType::U16) + [`Port`](OutType::Port)
-/// | `socketaddress` | `&[u8]` | [`Binary`](InType::Binary) + [`SocketAddress`](OutType::SocketAddress)
-/// | `socketaddressc` [^binaryc] | `&[u8]` | [`BinaryC`](InType::BinaryC) + [`SocketAddress`](OutType::SocketAddress)
+/// | `raw_pointer` [^raw_pointer] | `&T` | [`HexSize`](InType::HexSize)
+/// | `raw_pointer_slice` [^raw_pointer] | `&[T]` | [`HexSize`](InType::HexSize)
+/// | `socketaddress` [^socketaddress] | `&[u8]` | [`Binary`](InType::Binary) + [`SocketAddress`](OutType::SocketAddress)
+/// | `socketaddressc` [^binaryc] [^socketaddress] | `&[u8]` | [`BinaryC`](InType::BinaryC) + [`SocketAddress`](OutType::SocketAddress)
 /// | `str8` | `&[u8]` | [`Str8`](InType::Str8) + [`Utf8`](OutType::Utf8)
+/// | `str8_chunked` [^chunked] | `&[u8]` | [`Str8`](InType::Str8) + [`Utf8`](OutType::Utf8)
 /// | `str8_cp1252` | `&[u8]` | [`Str8`](InType::Str8)
 /// | `str8_json` | `&[u8]` | [`Str8`](InType::Str8) + [`Json`](OutType::Json)
+/// | `str8_opt` [^opt] | `Option<&[u8]>` | [`Str8`](InType::Str8) + [`Utf8`](OutType::Utf8)
 /// | `str8_xml` | `&[u8]` | [`Str8`](InType::Str8) + [`Xml`](OutType::Xml)
 /// | `str16` | `&[u16]` | [`Str16`](InType::Str16)
+/// | `str16_from_utf8` [^utf16] | `&str` | [`Str16`](InType::Str16)
 /// | `str16_json` | `&[u16]` | [`Str16`](InType::Str16) + [`Json`](OutType::Json)
+/// | `str16_opt` [^opt] | `Option<&[u16]>` | [`Str16`](InType::Str16)
 /// | `str16_xml` | `&[u16]` | [`Str16`](InType::Str16) + [`Xml`](OutType::Xml)
 /// | `systemtime` [^systemtime] | `&std::time::SystemTime` | [`FileTime`](InType::FileTime)
-/// | `tid` | `&u32` | [`U32`](InType::U32) + [`Tid`](OutType::Tid)
-/// | `tid_slice` | `&[u32]` | [`U32`](InType::U32) + [`Tid`](OutType::Tid)
+/// | `tid` [^pid] | `&u32` | [`U32`](InType::U32) + [`Tid`](OutType::Tid)
+/// | `tid_slice` [^pid] | `&[u32]` | [`U32`](InType::U32) + [`Tid`](OutType::Tid)
 /// | `time32` [^time] | `&i32` | [`FileTime`](InType::FileTime)
 /// | `time64` [^time] | `&i64` | [`FileTime`](InType::FileTime)
 /// | `u8` | `&u8` | [`U8`](InType::U8)
@@ -618,10 +1136,14 @@ pub use tracelogging_macros::define_provider;
 /// | `u32_slice` | `&[u32]` | [`U32`](InType::U32)
 /// | `u32_hex` | `&u32` | [`Hex32`](InType::Hex32)
 /// | `u32_hex_slice` | `&[u32]` | [`Hex32`](InType::Hex32)
+/// | `u32_be` [^byte_order] | `&u32` | [`Binary`](InType::Binary)
+/// | `u32_le` [^byte_order] | `&u32` | [`Binary`](InType::Binary)
 /// | `u64` | `&u64` | [`U64`](InType::U64)
 /// | `u64_slice` | `&[u64]` | [`U64`](InType::U64)
 /// | `u64_hex` | `&u64` | [`Hex64`](InType::Hex64)
 /// | `u64_hex_slice` | `&[u64]` | [`Hex64`](InType::Hex64)
+/// | `u64_be` [^byte_order] | `&u64` | [`Binary`](InType::Binary)
+/// | `u64_le` [^byte_order] | `&u64` | [`Binary`](InType::Binary)
 /// | `usize` | `&usize` | [`USize`](InType::USize)
 /// | `usize_slice` | `&[usize]` | [`USize`](InType::USize)
 /// | `usize_hex` | `&usize` | [`HexSize`](InType::HexSize)
@@ -635,15 +1157,66 @@ pub use tracelogging_macros::define_provider;
 /// | `win_sid` [^sid] | `&[u8]` | [`Sid`](InType::Sid)
 /// | `win_systemtime` | `&[u16; 8]` | [`SystemTime`](InType::SystemTime)
 /// | `win_systemtime_slice` | `&[[u16; 8]]` | [`SystemTime`](InType::SystemTime)
+/// | `win_systemtime_chrono` [^chrono] | `&chrono::NaiveDateTime` | [`SystemTime`](InType::SystemTime)
 /// | `win_systemtime_utc` | `&[u16; 8]` | [`SystemTime`](InType::SystemTime) + [`DateTimeUtc`](OutType::DateTimeUtc)
 /// | `win_systemtime_utc_slice` | `&[[u16; 8]]` | [`SystemTime`](InType::SystemTime) + [`DateTimeUtc`](OutType::DateTimeUtc)
 ///
+/// [^binary_n]: The `binary_uN` types log a slice of integers as one `Binary` blob
+/// containing the values' bytes (native byte order), e.g. `binary_u32("Regs", &[1, 2])`
+/// logs 8 bytes. The `Hex` format asks decoders to show the blob as hex. ETW has no way
+/// to record the element size of a `Binary` field, so decoders still see bytes; use a
+/// `_slice` type such as `hex32_slice` if each element should be decoded as a value.
+///
+/// [^guid_bytes]: `guid_bytes` logs the 16 bytes as-is, without converting them to a
+/// [Guid]. The bytes must be in the order that the `guid` type logs, i.e. the order
+/// returned by [`Guid::to_bytes_le`], e.g. `guid_bytes("Id", &guid.to_bytes_le())`
+/// logs the same value as `guid("Id", &guid)`.
+///
 /// [^binaryc]: The `...` and `...c` types are the same except that the `...c` types use
 /// a newer `InType::BinaryC` ETW encoding. The `BinaryC` encoding avoids the extra
 /// `FieldName.Length` field that sometimes shows up for `InType::Binary` fields. This
 /// new encoding requires updated decoder support so it may not work with older ETW
 /// decoding tools.
 ///
+/// [^bool32_native]: The `bool32_native` types accept Rust `bool` values and widen each
+/// value to a 4-byte `Bool32` (`0` or `1`) at the call site, so the logged field has the
+/// same encoding as a `bool32` field. A `bool32_native_slice` value is copied into a
+/// fixed-size buffer on the stack, so it can have at most 256 elements. If the value is
+/// longer, the event is not written and `write_event!` returns 534
+/// (`ERROR_ARITHMETIC_OVERFLOW`). Use `bool8_slice` (1 byte per element) for longer
+/// slices.
+///
+/// [^byte_order]: ETW decoders assume that integer fields use the byte order of the
+/// machine that wrote the event. The `_be` and `_le` types instead convert the value to
+/// big-endian or little-endian bytes at the call site and log the bytes as a `Binary`
+/// field, so the stored byte order does not depend on the emitter's architecture. For
+/// example, `u32_be("x", &0x12345678)` always logs the bytes `12 34 56 78`, and
+/// `u32_le` logs `78 56 34 12`. Decoders show the field as hex bytes, not as a number.
+///
+/// [^char8_utf8]: The `char8_utf8` types log `u8` values with a `Utf8` format so that
+/// decoders can show them as UTF-8 code units (e.g. ASCII characters) rather than as
+/// CP1252 characters. Decoders that do not support the `U8` + `Utf8` combination will
+/// show the values as unsigned integers. To log a UTF-8 string, use `str8` instead.
+///
+/// [^chunked]: A counted string field can hold at most 65535 bytes, so `str8` fields
+/// truncate longer values. The `str8_chunked` type splits the value into up to 4 chunks
+/// of at most 65535 bytes each (splitting at UTF-8 character boundaries) and logs each
+/// chunk as a separate `str8` field. The first chunk uses the field's name and the
+/// others use the name with a `_1`, `_2`, or `_3` suffix, e.g. `str8_chunked("Text", s)`
+/// logs fields `Text`, `Text_1`, `Text_2`, and `Text_3`. All 4 fields are always
+/// present (unused chunks are empty strings), and a value longer than 262140 bytes is
+/// truncated. Note that ETW drops events larger than 64KB (including metadata and all
+/// field values) unless the session is configured with a larger buffer size, so
+/// chunking only helps for sessions with large buffers.
+///
+/// [^chrono]: The `win_systemtime_chrono` type requires the `chrono` feature.
+/// `write_event!` will convert the provided `chrono::NaiveDateTime` value into a Win32
+/// [`SYSTEMTIME`](https://learn.microsoft.com/windows/win32/api/minwinbase/ns-minwinbase-systemtime),
+/// truncating to milliseconds and saturating if the value is out of the range that
+/// `SYSTEMTIME` can represent: if the value is a date before 1601, the logged value
+/// will be the start of 1601, and if the value is a date after 30827, the logged value
+/// will be the end of 30827. The value is logged as-is (no time zone conversion).
+///
 /// [^cstr]: The `cstrN` types use a `0`-terminated `InType::CStrN` string encoding in
 /// the event. If the provided field value contains any `'\0'` characters then the event
 /// will include the value up to the first `'\0'`; otherwise the event will include the
@@ -655,6 +1228,68 @@ pub use tracelogging_macros::define_provider;
 /// [^errno]: The `errno` type is intended for use with C-style `errno` error codes. On
 /// Windows, the `errno` type behaves exactly like the `i32` type.
 ///
+/// [^f16]: The `f16` types require the `half` feature. ETW has no 16-bit float type, so
+/// the `f16` types widen each `half::f16` value to `f32` at the call site and log it as
+/// an `F32` field. Consumers will see a 32-bit float. An `f16_slice` value is widened
+/// into a fixed-size buffer on the stack, so it can have at most 256 elements. If the
+/// value is longer, the event is not written and `write_event!` returns 534
+/// (`ERROR_ARITHMETIC_OVERFLOW`).
+///
+/// [^fmt]: The `display8` and `debug8` types accept a reference to any value that
+/// implements `core::fmt::Display` or `core::fmt::Debug`, respectively, and log the
+/// formatted text as a `str8` field. The value is formatted into a 512-byte buffer on
+/// the stack and longer output is truncated at a UTF-8 character boundary. As with all
+/// field values, the formatting happens only if the event is enabled, but when it is
+/// enabled the cost of running the `Display` or `Debug` implementation is paid on
+/// every write, so prefer a `str8` field if the string is already available.
+///
+/// [^json]: The `json8` type requires the `serde` feature. It accepts a reference to any
+/// value that implements `serde::Serialize`, serializes the value to JSON (in a
+/// heap-allocated buffer), and logs the result as a `str8` field with a
+/// [`Json`](OutType::Json) format. As with all field values, the serialization happens
+/// only if the event is enabled. If serialization fails (e.g. for a map with non-string
+/// keys), the field is logged as an empty string.
+///
+/// [^map]: The `map_str8` type accepts any value that implements `IntoIterator` with
+/// items of type `(K, V)` where `K` and `V` implement `AsRef<str>`, e.g. `&HashMap<String,
+/// String>` or `&BTreeMap<&str, &str>`. It logs a variable-length array of struct, where
+/// each struct has `str8` fields named `Key` and `Value`, followed by a `u16` field
+/// named `NAME_dropped`, e.g. `map_str8("Attrs", &attrs)` logs fields `Attrs` and
+/// `Attrs_dropped`. The items are copied into a 1024-byte buffer on the stack (each item
+/// uses 4 bytes plus the length of its key and value). Items starting with the first
+/// item that does not fit are not logged, and `NAME_dropped` holds the number of items
+/// that were not logged (saturating at 65535), so it is `0` if the whole map was
+/// logged. As with all field values, the iteration happens only if the event is
+/// enabled, and the value is iterated at most once. An empty map logs an empty array.
+///
+/// [^opt]: The `str8_opt` and `str16_opt` types accept an `Option` of a string value,
+/// e.g. `Option<&str>`, `Option<&[u16]>`, or `Option<String>`. The field is always
+/// present in the event: `Some(text)` logs the text and `None` logs an empty string.
+/// The value's type must implement `Default` and `AsRef<[u8]>` (or `AsRef<[u16]>`), so
+/// use `Option::as_deref` to log an `Option<&String>` or `&Option<String>`.
+///
+/// [^pid]: The `pid` and `tid` types log a `u32` process or thread id. The OS APIs that
+/// return these ids already return `u32`, so no cast is needed, e.g.
+/// `pid("Pid", &std::process::id())` or, on Windows, `tid("Tid", &GetCurrentThreadId())`.
+/// A `std::thread::ThreadId` is not an OS thread id and cannot be logged as a `tid`.
+///
+/// [^raw_pointer]: The `raw_pointer` type accepts a reference (`&T` or `&mut T`) or a raw
+/// pointer (`*const T` or `*mut T`) and logs its address, the same as a `pointer` field
+/// with a value of `ptr as usize`. Note that the value is the pointer itself, so
+/// `raw_pointer("Obj", &obj)` logs the address of `obj`, while
+/// `raw_pointer("Ptr", &ptr)` logs the address of the `ptr` variable, not the address
+/// that `ptr` points to. The `raw_pointer_slice` type accepts a slice of such values,
+/// e.g. `&[*const T]` or `&[&T]`, where `T` is a sized type (a slice of fat pointers
+/// such as `&[&str]` is not accepted). The slice is logged in place, without copying.
+///
+/// [^socketaddress]: The `socketaddress` types expect the bytes of a Windows
+/// `SOCKADDR_IN` (family `AF_INET` = 2, 16 bytes) or `SOCKADDR_IN6` (family `AF_INET6` =
+/// 23, 28 bytes), with the family in little-endian byte order and the port in
+/// big-endian (network) byte order. Other layouts, such as a 128-byte
+/// `SOCKADDR_STORAGE`, are also accepted. The value is not checked: `write_event!` logs
+/// the whole slice (up to 65535 bytes), and decoders may show a value in an unexpected
+/// layout as hex bytes.
+///
 /// [^systemtime]: When logging `systemtime` types, `write_event!` will convert the
 /// provided `std::time::SystemTime` value into a Win32
 /// [`FILETIME`](https://docs.microsoft.com/windows/win32/api/minwinbase/ns-minwinbase-filetime),
@@ -664,10 +1299,26 @@ pub use tracelogging_macros::define_provider;
 /// value will be the start of 1601, and if the `SystemTime` value is a date after 30827,
 /// the logged `FILETIME` value will be the end of 30827.
 ///
+/// The logged `FILETIME` is always UTC. By default, decoders may show it in local time;
+/// add `format(DateTimeUtc)` to show it as UTC, e.g.
+/// `systemtime("When", &now, format(DateTimeUtc))`. The same applies to the `time32`,
+/// `time64`, and `win_filetime` types.
+///
+/// [^utf16]: The `str16_from_utf8` type accepts a UTF-8 string (e.g. `&str` or
+/// `&String`) and transcodes it to UTF-16 at the call site, so the logged field has
+/// the same encoding as a `str16` field (characters outside the Basic Multilingual
+/// Plane are logged as surrogate pairs). The value is transcoded into a 512-element
+/// buffer on the stack, so it can have at most 512 UTF-16 code units. If the value is
+/// longer, the event is not written and `write_event!` returns 534
+/// (`ERROR_ARITHMETIC_OVERFLOW`). As with all field values, the transcoding happens
+/// only if the event is enabled.
+///
 /// [^sid]: The `win_sid` type requires an input byte-slice value that is at least
 /// [`GetSidLength(value_bytes)`](https://docs.microsoft.com/windows/win32/api/securitybaseapi/nf-securitybaseapi-getlengthsid)
 /// =  `value_bytes[1] * 4 + 8` bytes long. `write_event!` will panic if the value is
 /// smaller than that size.
+/// To log a `PSID` from the `windows` crate, enable the `windows` feature and use
+/// `sid_from_psid` to get the SID's bytes.
 ///
 /// [^time]: When logging `time32` and `time64` types, `write_event!` assumes that the
 /// provided `i32` or `i64` value is the number of seconds since 1970 (i.e. a `time_t`)
@@ -677,7 +1328,8 @@ pub use tracelogging_macros::define_provider;
 /// [`FileTimeToSystemTime`](https://docs.microsoft.com/windows/win32/api/timezoneapi/nf-timezoneapi-filetimetosystemtime)
 /// can handle: if an `i64` value is a date before 1601, the logged `FILETIME`
 /// value will be the start of 1601, and if the `i64` value is a date after 30827,
-/// the logged `FILETIME` value will be the end of 30827.
+/// the logged `FILETIME` value will be the end of 30827. As with `systemtime`, add
+/// `format(DateTimeUtc)` to have decoders show the time as UTC.
 ///
 /// ### Struct fields
 ///
@@ -726,6 +1378,34 @@ pub use tracelogging_macros::define_provider;
 /// );
 /// ```
 ///
+/// A tuple of scalar values can be logged as a struct with the `struct_tuple` field
+/// type. Each element of the tuple becomes a field of the struct, with the field's type
+/// determined by the element's Rust type.
+///
+/// **Tuple struct field syntax:** `struct_tuple("NAME", (TYPES...), VALUE_REF, tag(TAG))`
+///
+/// - `(TYPES...)` is the tuple's type, e.g. `(i32, i32, f64)`. Element types may be
+///   `bool`, `f32`, `f64`, `i8`, `i16`, `i32`, `i64`, `isize`, `u8`, `u16`, `u32`, `u64`,
+///   `usize`, or [`Guid`] (the same types supported by [`RawStruct`]). The tuple may have
+///   1 to 12 elements. The member fields are named `item0`, `item1`, etc. unless the
+///   elements are given names, e.g. `(x: i32, y: i32, z: f64)`.
+///
+/// - `VALUE_REF` is a reference to a value of the tuple type, e.g. `&(x, y, z)`.
+///
+/// Example:
+///
+/// ```
+/// # use tracelogging as tlg;
+/// # tlg::define_provider!(MY_PROVIDER, "MyCompany.MyComponent");
+/// let (x, y, z) = (1, 2, 0.5);
+/// tlg::write_event!(
+///     MY_PROVIDER,
+///     "MyPointEvent",
+///     struct_tuple("Point", (i32, i32, f64), &(x, y, z)), // item0, item1, item2
+///     struct_tuple("Named", (x: i32, y: i32, z: f64), &(x, y, z)), // x, y, z
+/// );
+/// ```
+///
 /// ### Raw fields
 ///
 /// *Advanced:* In certain cases, you may need capabilities not directly exposed by the
@@ -759,13 +1439,36 @@ pub use tracelogging_macros::define_provider;
 /// - `raw_field("NAME", INTYPE, VALUE_BYTES, format(FORMAT), tag(TAG))`
 ///
 ///   The `raw_field` type allows you to add a field with direct control over the field's
-///   contents. VALUE_BYTES is specified as `&[u8]` and you can specify any [InType].
+///   contents. VALUE_BYTES is specified as `&[u8]` and you can specify any [InType]
+///   except [`Invalid`](InType::Invalid), [`Struct`](InType::Struct) (use `raw_struct`),
+///   and [`Sid`](InType::Sid) (use `win_sid`). Using one of these is a compile error
+///   when INTYPE is written as a name, e.g. `Struct` or `InType::Struct`:
+///
+///   ```compile_fail
+///   # use tracelogging as tlg;
+///   # tlg::define_provider!(MY_PROVIDER, "MyCompany.MyComponent");
+///   tlg::write_event!(
+///       MY_PROVIDER,
+///       "MyEvent",
+///       raw_field("Struct", Struct, &[0u8]), // Error: InType::Struct is not allowed.
+///   );
+///   ```
+///
+///   ```compile_fail
+///   # use tracelogging as tlg;
+///   # tlg::define_provider!(MY_PROVIDER, "MyCompany.MyComponent");
+///   tlg::write_event!(
+///       MY_PROVIDER,
+///       "MyEvent",
+///       raw_field_slice("Sids", tlg::InType::Sid, &[0u8, 0]), // Error: InType::Sid is not allowed.
+///   );
+///   ```
 ///
 /// - `raw_field_slice("NAME", INTYPE, VALUE_BYTES, format(FORMAT), tag(TAG))`
 ///
 ///   The `raw_field` type allows you to add a variable-sized array field with direct
 ///   control over the field's contents. VALUE_BYTES is specified as `&[u8]` and you can
-///   specify any [InType]. Note that the provided VALUE_BYTES must include the entire
+///   specify any [InType] except those disallowed for `raw_field`. Note that the provided VALUE_BYTES must include the entire
 ///   array, including the array element count, which is a `u16` element count
 ///   immediately before the field values.
 ///
@@ -795,6 +1498,21 @@ pub use tracelogging_macros::define_provider;
 ///   part of the struct. In cases of nested structs, a struct and its fields count as a
 ///   single logical field.
 ///
+///   If FIELD_COUNT is an integer literal, it is a compile error for the event to end
+///   before FIELD_COUNT logical fields follow the `raw_struct`. (Other FIELD_COUNT
+///   expressions are not checked.)
+///
+///   ```compile_fail
+///   # use tracelogging as tlg;
+///   # tlg::define_provider!(MY_PROVIDER, "MyCompany.MyComponent");
+///   tlg::write_event!(
+///       MY_PROVIDER,
+///       "MyEvent",
+///       raw_struct("Point", 2), // Error: only 1 field follows.
+///       i32("x", &1),
+///   );
+///   ```
+///
 /// - `raw_struct_slice("NAME", FIELD_COUNT, tag(TAG))`
 ///
 ///   The `raw_struct_slice` type allows you to begin a variable-length array-of-struct
@@ -807,7 +1525,15 @@ pub use tracelogging_macros::define_provider;
 ///   Note that the FIELD_COUNT must be a constant `u8` value in the range 0 to 127. It
 ///   indicates the number of subsequent logical fields that will be considered to be
 ///   part of the struct. In cases of nested structs, a struct and its fields count as a
-///   single logical field.
+///   single logical field. As with `raw_struct`, a literal FIELD_COUNT is checked.
+///
+/// - `raw_struct_value("NAME", TYPE, VALUE, tag(TAG))`
+///
+///   The `raw_struct_value` type adds a struct field containing the members of a
+///   `#[repr(C)]` type that implements [`RawStruct`](trait@RawStruct) (usually via
+///   `#[derive(RawStruct)]`). TYPE is the type of the value, e.g. `MyPoint`. The VALUE
+///   must be a `&TYPE`. The struct's member fields are determined by
+///   [`RawStruct::RAW_META`] and the struct's data is the bytes of the value.
 ///
 /// - `raw_data(VALUE_BYTES)`
 ///
@@ -824,6 +1550,21 @@ pub use tracelogging_macros::define_provider;
 ///   In addition, you can use one `raw_data` to supply the data for any number of
 ///   fields or you can use multiple `raw_data` fields to supply the data for one field.
 ///
+///   Because `raw_data` adds no metadata and is not tied to any one field, it does not
+///   accept the `format` or `tag` options. Put them on the `raw_meta` or
+///   `raw_meta_slice` field that declares the field's type instead:
+///
+///   ```compile_fail
+///   # use tracelogging as tlg;
+///   # tlg::define_provider!(MY_PROVIDER, "MyCompany.MyComponent");
+///   tlg::write_event!(
+///       MY_PROVIDER,
+///       "MyEvent",
+///       raw_meta("RawHex32", U32),
+///       raw_data(&[255, 0, 0, 0], format(Hex)), // Error: use raw_meta(..., format(Hex)).
+///   );
+///   ```
+///
 /// Example:
 ///
 /// ```
@@ -886,18 +1627,126 @@ pub use tracelogging_macros::define_provider;
 #[cfg(feature = "macros")]
 pub use tracelogging_macros::write_event;
 
+/// Writes one event per item of an iterator, with a single enabled check for the
+/// whole batch.
+///
+/// `write_events!(PROVIDER_SYMBOL, "EventName", for PATTERN in ITERATOR, options and fields...)`
+///
+/// This accepts the same provider, options, and fields as [`write_event!`], plus a
+/// `for PATTERN in ITERATOR` argument after the event name. The provider's enabled
+/// check is done once, and the event's metadata and descriptor are shared by all of
+/// the writes. If the event is enabled, the iterator is consumed and one event is
+/// written per item, with the field values (and any `activity_id` and `related_id`)
+/// evaluated for each item. If the event is not enabled, the iterator expression is
+/// not evaluated.
+///
+/// Returns 0 if the event was not enabled or all writes succeeded. Otherwise, returns
+/// the error code of the most recent failed write.
+///
+/// Since the enabled check is done only once, events in the batch may still be
+/// written if all sessions stop listening to the provider while the batch is in
+/// progress. Use [`write_event!`] in a loop if each write needs its own check.
+///
+/// Batching saves only the per-event enabled check. Each event is still a separate
+/// `EventWriteTransfer` call. The `write_cost` example times `write_events!` against a
+/// [`write_event!`] loop that writes the same events. On x86-64 Linux with
+/// `--features testing`, where the ETW write itself is a no-op, both measured under
+/// 0.1 ns per event, i.e. no measurable difference.
+///
+/// ```
+/// use tracelogging as tlg;
+///
+/// tlg::define_provider!(MY_PROVIDER, "MyCompany.MyComponent");
+/// unsafe { MY_PROVIDER.register(); }
+///
+/// let samples = [(1u32, 2.5f64), (2, 3.5), (3, 4.5)];
+/// tlg::write_events!(
+///     MY_PROVIDER,
+///     "Sample",
+///     for (id, value) in samples.iter(),
+///     level(Verbose),
+///     u32("Id", id),
+///     f64("Value", value),
+/// );
+///
+/// MY_PROVIDER.unregister();
+/// ```
+#[cfg(feature = "macros")]
+pub use tracelogging_macros::write_events;
+
+/// Returns the fields that a [`write_event!`] with the same arguments would declare
+/// in its metadata, as a `&'static [(&'static str, InType, OutType)]`.
+///
+/// `write_event_fields!(PROVIDER_SYMBOL, "EventName", options and fields...)`
+///
+/// This is intended for tests that verify an event's schema. It accepts the same
+/// arguments as [`write_event!`], but it does not write an event and does not
+/// evaluate the field values.
+///
+/// Each entry in the result has the field's name, [InType], and [OutType], in the
+/// same order as the event's metadata:
+///
+/// - For slice fields, the [InType] includes the
+///   [VariableCountFlag](InType::VariableCountFlag).
+/// - For `struct` and `raw_struct_value` fields, the entry's [InType] is
+///   [InType::Struct] and the entry's [OutType] holds the number of fields in the
+///   struct, e.g. `OutType::from_int(2)`. The struct's fields follow the struct's entry.
+///   (The fields of a `raw_struct_value` are not listed.)
+/// - A `str8_chunked` field has one entry per chunk, e.g. `"Name"`, `"Name_1"`, ...
+/// - If the field type and options do not set a format, the entry's [OutType] is
+///   [OutType::Default]. (Some field types set a format, e.g. `bool8` uses
+///   [OutType::Boolean].)
+///
+/// ```
+/// use tracelogging as tlg;
+///
+/// tlg::define_provider!(MY_PROVIDER, "MyCompany.MyComponent");
+/// let fields = tlg::write_event_fields!(
+///     MY_PROVIDER,
+///     "MyEvent",
+///     u32("Count", &1),
+///     str8("Name", "abc", format(Xml)),
+///     i32_slice("Values", &[1, 2]),
+/// );
+/// assert_eq!(
+///     fields,
+///     &[
+///         ("Count", tlg::InType::U32, tlg::OutType::Default),
+///         ("Name", tlg::InType::Str8, tlg::OutType::Xml),
+///         (
+///             "Values",
+///             tlg::InType::from_int(tlg::InType::I32.as_int() | tlg::InType::VariableCountFlag),
+///             tlg::OutType::Default,
+///         ),
+///     ]
+/// );
+/// ```
+#[cfg(feature = "macros")]
+pub use tracelogging_macros::write_event_fields;
+
+/// Implements [`RawStruct`](trait@RawStruct) for a `#[repr(C)]` struct.
+/// See the trait documentation for details.
+#[cfg(feature = "macros")]
+pub use tracelogging_macros::RawStruct;
+
+pub use descriptors::event_id_from_name;
+#[cfg(feature = "windows")]
+pub use descriptors::sid_from_psid;
 pub use enums::Channel;
 pub use enums::InType;
 pub use enums::Level;
 pub use enums::Opcode;
 pub use enums::OutType;
 pub use guid::Guid;
+pub use guid::GuidParseError;
 pub use native::NativeImplementation;
 pub use native::ProviderEnableCallback;
 pub use native::NATIVE_IMPLEMENTATION;
 pub use provider::Provider;
+pub use raw_struct::RawStruct;
 pub mod _internal;
 pub mod changelog;
+pub mod decode;
 
 /// Converts a
 /// [`std::time::SystemTime`](https://doc.rust-lang.org/std/time/struct.SystemTime.html)
@@ -965,8 +1814,60 @@ macro_rules! provider_enabled {
     }};
 }
 
+/// Declares a set of named keyword constants for use with the `keyword` option of
+/// [`write_event!`] or with [`provider_enabled!`].
+///
+/// Usage:
+///
+/// ```
+/// use tracelogging as tlg;
+///
+/// tlg::keywords! {
+///     /// Keywords used by MyComponent.
+///     pub struct MyKeywords {
+///         NETWORK, // 0x1
+///         STORAGE, // 0x2
+///         UI,      // 0x4
+///     }
+/// }
+///
+/// tlg::define_provider!(MY_PROVIDER, "MyCompany.MyComponent");
+/// tlg::write_event!(
+///     MY_PROVIDER,
+///     "MyEvent",
+///     keyword(MyKeywords::NETWORK),
+///     keyword(MyKeywords::STORAGE), // Event keyword is 0x3.
+/// );
+/// ```
+///
+/// This declares a unit struct with one associated `u64` constant per name. Each
+/// constant has a single keyword bit set, assigned in order starting with `0x1`, so the
+/// constants are mutually exclusive. At most 64 names may be declared.
+#[macro_export]
+macro_rules! keywords {
+    (
+        $(#[$attr:meta])*
+        $vis:vis struct $name:ident {
+            $($(#[$keyword_attr:meta])* $keyword:ident),* $(,)?
+        }
+    ) => {
+        $(#[$attr])*
+        $vis struct $name;
+        impl $name {
+            ::tracelogging::keywords!(@consts ($vis) 0; $($(#[$keyword_attr])* $keyword,)*);
+        }
+    };
+    (@consts ($vis:vis) $bit:expr; $(#[$keyword_attr:meta])* $keyword:ident, $($rest:tt)*) => {
+        $(#[$keyword_attr])*
+        $vis const $keyword: ::core::primitive::u64 = 1 << ($bit);
+        ::tracelogging::keywords!(@consts ($vis) $bit + 1; $($rest)*);
+    };
+    (@consts ($vis:vis) $bit:expr;) => {};
+}
+
 mod descriptors;
 mod enums;
 mod guid;
 mod native;
 mod provider;
+mod raw_struct;
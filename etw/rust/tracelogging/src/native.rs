@@ -3,9 +3,8 @@
 
 use core::marker::PhantomPinned;
 
-#[cfg(all(windows, feature = "etw"))]
+#[cfg(all(windows, feature = "etw", not(feature = "disabled")))]
 use core::cell::UnsafeCell;
-#[cfg(all(windows, feature = "etw"))]
 use core::sync::atomic;
 
 use crate::descriptors::EventDataDescriptor;
@@ -23,11 +22,12 @@ pub enum NativeImplementation {
 }
 
 /// The configuration under which this crate was compiled: `Windows` or `Other`.
-pub const NATIVE_IMPLEMENTATION: NativeImplementation = if cfg!(all(windows, feature = "etw")) {
-    NativeImplementation::Windows
-} else {
-    NativeImplementation::Other
-};
+pub const NATIVE_IMPLEMENTATION: NativeImplementation =
+    if cfg!(all(windows, feature = "etw", not(feature = "disabled"))) {
+        NativeImplementation::Windows
+    } else {
+        NativeImplementation::Other
+    };
 
 /// Signature for a custom
 /// [provider enable callback](https://docs.microsoft.com/windows/win32/api/evntprov/nc-evntprov-penablecallback).
@@ -41,7 +41,7 @@ pub type ProviderEnableCallback = fn(
     callback_context: usize,
 );
 
-#[cfg(all(windows, feature = "etw"))]
+#[cfg(all(windows, feature = "etw", not(feature = "disabled")))]
 type OuterEnableCallback = unsafe extern "system" fn(
     source_id: &Guid,
     event_control_code: u32,
@@ -55,8 +55,9 @@ type OuterEnableCallback = unsafe extern "system" fn(
 /// Data needed to manage an ETW registration with callback.
 pub struct ProviderContext {
     _pinned: PhantomPinned,
+    registration_status: atomic::AtomicU32,
 
-    #[cfg(all(windows, feature = "etw"))]
+    #[cfg(all(windows, feature = "etw", not(feature = "disabled")))]
     cell: UnsafeCell<ProviderContextInner>,
 }
 
@@ -65,11 +66,11 @@ impl ProviderContext {
     /// Other: return ERROR_NOT_SUPPORTED;
     pub fn activity_id_control(_control_code: u32, _activity_id: &mut Guid) -> u32 {
         let result;
-        #[cfg(not(all(windows, feature = "etw")))]
+        #[cfg(not(all(windows, feature = "etw", not(feature = "disabled"))))]
         {
             result = 50; // ERROR_NOT_SUPPORTED
         }
-        #[cfg(all(windows, feature = "etw"))]
+        #[cfg(all(windows, feature = "etw", not(feature = "disabled")))]
         {
             result = unsafe { EventActivityIdControl(_control_code, _activity_id) };
         }
@@ -80,8 +81,9 @@ impl ProviderContext {
     pub const fn new() -> ProviderContext {
         return ProviderContext {
             _pinned: PhantomPinned,
+            registration_status: atomic::AtomicU32::new(0),
 
-            #[cfg(all(windows, feature = "etw"))]
+            #[cfg(all(windows, feature = "etw", not(feature = "disabled")))]
             cell: UnsafeCell::new(ProviderContextInner::new()),
         };
     }
@@ -89,11 +91,11 @@ impl ProviderContext {
     /// Returns the registration handle. For diagnostic purposes only.
     pub const fn reg_handle(&self) -> u64 {
         let result;
-        #[cfg(not(all(windows, feature = "etw")))]
+        #[cfg(not(all(windows, feature = "etw", not(feature = "disabled"))))]
         {
             result = 0;
         }
-        #[cfg(all(windows, feature = "etw"))]
+        #[cfg(all(windows, feature = "etw", not(feature = "disabled")))]
         {
             let inner_ptr: *const ProviderContextInner = self.cell.get();
             let inner = unsafe { &*inner_ptr };
@@ -102,15 +104,27 @@ impl ProviderContext {
         return result;
     }
 
+    /// Returns the result of the most recent call to register, or 0 if register has
+    /// not been called.
+    pub fn registration_status(&self) -> u32 {
+        return self.registration_status.load(atomic::Ordering::Relaxed);
+    }
+
+    /// Returns true if the provider currently has a valid registration handle.
+    pub const fn is_registered(&self) -> bool {
+        return self.reg_handle() != 0;
+    }
+
     /// Returns true if the provider is enabled at the specified level and keyword.
+    /// Other: returns true if the `testing` feature is enabled, false otherwise.
     #[inline(always)]
     pub const fn enabled(&self, _level: Level, _keyword: u64) -> bool {
         let result;
-        #[cfg(not(all(windows, feature = "etw")))]
+        #[cfg(not(all(windows, feature = "etw", not(feature = "disabled"))))]
         {
-            result = false;
+            result = cfg!(all(feature = "testing", not(feature = "disabled")));
         }
-        #[cfg(all(windows, feature = "etw"))]
+        #[cfg(all(windows, feature = "etw", not(feature = "disabled")))]
         {
             let inner_ptr: *const ProviderContextInner = self.cell.get();
             let inner = unsafe { &*inner_ptr };
@@ -126,11 +140,11 @@ impl ProviderContext {
     ///   register or unregister.
     pub fn unregister(&self) -> u32 {
         let result;
-        #[cfg(not(all(windows, feature = "etw")))]
+        #[cfg(not(all(windows, feature = "etw", not(feature = "disabled"))))]
         {
             result = 0;
         }
-        #[cfg(all(windows, feature = "etw"))]
+        #[cfg(all(windows, feature = "etw", not(feature = "disabled")))]
         {
             let inner_ptr: *mut ProviderContextInner = self.cell.get();
             let inner_mut = unsafe { &mut *inner_ptr };
@@ -139,7 +153,7 @@ impl ProviderContext {
         return result;
     }
 
-    /// Calls EventRegister.
+    /// Calls EventRegister and records the result for registration_status.
     ///
     /// # Preconditions
     /// - This will panic if provider is currently registered.
@@ -157,28 +171,30 @@ impl ProviderContext {
         _callback_context: usize,
     ) -> u32 {
         let result;
-        #[cfg(not(all(windows, feature = "etw")))]
+        #[cfg(not(all(windows, feature = "etw", not(feature = "disabled"))))]
         {
             result = 0;
         }
-        #[cfg(all(windows, feature = "etw"))]
+        #[cfg(all(windows, feature = "etw", not(feature = "disabled")))]
         {
             result = /* unsafe */ { &mut *self.cell.get() }.register(
                 _provider_id,
                 _callback_fn,
                 _callback_context);
         }
+        self.registration_status
+            .store(result, atomic::Ordering::Relaxed);
         return result;
     }
 
     /// Calls EventSetInformation.
     pub fn set_information(&self, _information_class: u32, _information: &[u8]) -> u32 {
         let result;
-        #[cfg(not(all(windows, feature = "etw")))]
+        #[cfg(not(all(windows, feature = "etw", not(feature = "disabled"))))]
         {
             result = 0;
         }
-        #[cfg(all(windows, feature = "etw"))]
+        #[cfg(all(windows, feature = "etw", not(feature = "disabled")))]
         {
             result = unsafe {
                 EventSetInformation(
@@ -192,7 +208,7 @@ impl ProviderContext {
         return result;
     }
 
-    /// Calls EventWriteTransfer.
+    /// Calls EventWriteTransfer. Returns 0 without writing if not registered.
     pub fn write_transfer(
         &self,
         _descriptor: &EventDescriptor,
@@ -201,21 +217,64 @@ impl ProviderContext {
         _data: &[EventDataDescriptor],
     ) -> u32 {
         let result;
-        #[cfg(not(all(windows, feature = "etw")))]
+        #[cfg(not(all(windows, feature = "etw", not(feature = "disabled"))))]
         {
             result = 0;
         }
-        #[cfg(all(windows, feature = "etw"))]
+        #[cfg(all(windows, feature = "etw", not(feature = "disabled")))]
         {
-            result = unsafe {
-                EventWriteTransfer(
-                    self.reg_handle(),
-                    _descriptor,
-                    _activity_id,
-                    _related_id,
-                    _data.len() as u32,
-                    _data.as_ptr(),
-                )
+            let reg_handle = self.reg_handle();
+            result = if reg_handle == 0 {
+                0
+            } else {
+                unsafe {
+                    EventWriteTransfer(
+                        reg_handle,
+                        _descriptor,
+                        _activity_id,
+                        _related_id,
+                        _data.len() as u32,
+                        _data.as_ptr(),
+                    )
+                }
+            };
+        }
+        return result;
+    }
+
+    /// Calls EventWriteEx. Returns 0 without writing if not registered.
+    pub fn write_ex(
+        &self,
+        _descriptor: &EventDescriptor,
+        _filter: u64,
+        _flags: u32,
+        _activity_id: Option<&[u8; 16]>,
+        _related_id: Option<&[u8; 16]>,
+        _data: &[EventDataDescriptor],
+    ) -> u32 {
+        let result;
+        #[cfg(not(all(windows, feature = "etw", not(feature = "disabled"))))]
+        {
+            result = 0;
+        }
+        #[cfg(all(windows, feature = "etw", not(feature = "disabled")))]
+        {
+            let reg_handle = self.reg_handle();
+            result = if reg_handle == 0 {
+                0
+            } else {
+                unsafe {
+                    EventWriteEx(
+                        reg_handle,
+                        _descriptor,
+                        _filter,
+                        _flags,
+                        _activity_id,
+                        _related_id,
+                        _data.len() as u32,
+                        _data.as_ptr(),
+                    )
+                }
             };
         }
         return result;
@@ -237,7 +296,7 @@ impl Drop for ProviderContext {
     }
 }
 
-#[cfg(all(windows, feature = "etw"))]
+#[cfg(all(windows, feature = "etw", not(feature = "disabled")))]
 struct ProviderContextInner {
     level: i32, // -1 means not enabled by anybody.
     busy: atomic::AtomicBool,
@@ -248,7 +307,7 @@ struct ProviderContextInner {
     callback_context: usize,
 }
 
-#[cfg(all(windows, feature = "etw"))]
+#[cfg(all(windows, feature = "etw", not(feature = "disabled")))]
 impl ProviderContextInner {
     const fn new() -> Self {
         return Self {
@@ -290,6 +349,10 @@ impl ProviderContextInner {
         return result;
     }
 
+    /// Memory ordering: register and unregister take the busy flag with an Acquire
+    /// swap and release it with a Release swap, so each call observes the reg_handle,
+    /// level, and callback values written by the previous call. A call that finds the
+    /// flag already set does not modify any state.
     fn register(
         &mut self,
         provider_id: &Guid,
@@ -324,7 +387,7 @@ impl ProviderContextInner {
         return result;
     }
 
-    #[cfg(all(windows, feature = "etw"))]
+    #[cfg(all(windows, feature = "etw", not(feature = "disabled")))]
     fn outer_callback_impl(
         &mut self,
         source_id: &Guid,
@@ -380,7 +443,7 @@ impl ProviderContextInner {
     }
 }
 
-#[cfg(all(windows, feature = "etw"))]
+#[cfg(all(windows, feature = "etw", not(feature = "disabled")))]
 extern "system" {
     fn EventUnregister(reg_handle: u64) -> u32;
     fn EventRegister(
@@ -403,5 +466,15 @@ extern "system" {
         data_count: u32,
         data: *const EventDataDescriptor,
     ) -> u32;
+    fn EventWriteEx(
+        reg_handle: u64,
+        descriptor: &EventDescriptor,
+        filter: u64,
+        flags: u32,
+        activity_id: Option<&[u8; 16]>,
+        related_id: Option<&[u8; 16]>,
+        data_count: u32,
+        data: *const EventDataDescriptor,
+    ) -> u32;
     fn EventActivityIdControl(control_code: u32, activity_id: &mut Guid) -> u32;
 }
@@ -7,6 +7,7 @@ use core::str::from_utf8;
 use crate::descriptors::EventDataDescriptor;
 use crate::descriptors::EventDescriptor;
 use crate::enums::Level;
+use crate::enums::Opcode;
 use crate::guid::Guid;
 use crate::native::ProviderContext;
 use crate::native::ProviderEnableCallback;
@@ -33,6 +34,7 @@ pub struct Provider {
     context: ProviderContext,
     meta: &'static [u8], // provider metadata
     id: Guid,
+    defaults: ProviderDefaults,
 }
 
 impl Provider {
@@ -72,6 +74,33 @@ impl Provider {
         return activity_id;
     }
 
+    /// Sets the current thread's thread-local activity id to `activity_id`, calls
+    /// `f(activity_id)`, then restores the previous thread-local activity id. The
+    /// previous value is restored even if `f` panics. Returns the result of `f`.
+    ///
+    /// Events written inside `f` by [`write_event!`] with no `activity_id` option use
+    /// `activity_id` as their activity id, so a group of events can share an activity
+    /// without passing `activity_id(...)` to each one.
+    ///
+    /// ```
+    /// # use tracelogging as tlg;
+    /// let activity_id = tlg::Provider::create_activity_id();
+    /// tlg::Provider::with_activity(&activity_id, |_activity_id| {
+    ///     // Events written here use activity_id unless they specify another one.
+    /// });
+    /// ```
+    pub fn with_activity<R>(activity_id: &Guid, f: impl FnOnce(&Guid) -> R) -> R {
+        struct RestoreActivityId(Guid);
+        impl Drop for RestoreActivityId {
+            fn drop(&mut self) {
+                Provider::set_current_thread_activity_id(&self.0);
+            }
+        }
+
+        let _restore = RestoreActivityId(Provider::set_current_thread_activity_id(activity_id));
+        return f(activity_id);
+    }
+
     /// Generates and returns a new 128-bit value suitable for use as an activity id.
     /// (Calls
     /// [EventActivityIdControl](https://docs.microsoft.com/windows/win32/api/evntprov/nf-evntprov-eventactivityidcontrol)
@@ -141,11 +170,40 @@ impl Provider {
     ///
     /// Note: [`write_event!`] already checks `enabled()`. You only need to make your own
     /// call to `enabled()` if you want to skip something other than [`write_event!`].
+    ///
+    /// If the crate was not compiled for Windows ETW and the `testing` feature is
+    /// enabled, always returns true (even if the provider is not registered) so that
+    /// tests can exercise the code that [`write_event!`] runs for enabled events.
     #[inline(always)]
     pub const fn enabled(&self, level: Level, keyword: u64) -> bool {
         return self.context.enabled(level, keyword);
     }
 
+    /// Returns true if this provider is currently registered, i.e. if the most recent
+    /// call to [`Provider::register()`] succeeded and has not been followed by a call to
+    /// [`Provider::unregister()`].
+    ///
+    /// An unregistered provider is never enabled: [`write_event!`] will be a no-op and
+    /// will return 0. A provider may be registered again after it is unregistered, e.g.
+    /// for plugin load/unload. Each registration gets a new ETW registration handle.
+    ///
+    /// Always returns false if the crate was not compiled for Windows ETW (see
+    /// [`NATIVE_IMPLEMENTATION`](crate::NATIVE_IMPLEMENTATION)).
+    pub const fn is_registered(&self) -> bool {
+        return self.context.is_registered();
+    }
+
+    /// Returns the result of the most recent call to [`Provider::register()`] or
+    /// [`Provider::register_with_callback()`]: 0
+    /// for success or a Win32 error from `EventRegister` for failure. Returns 0 if the
+    /// provider has never been registered.
+    ///
+    /// If registration fails, the provider is never enabled and events are silently
+    /// dropped. Use this to diagnose a provider whose events never show up.
+    pub fn registration_status(&self) -> u32 {
+        return self.context.registration_status();
+    }
+
     /// If this provider is not registered, does nothing and returns 0.
     /// Otherwise, unregisters the provider.
     ///
@@ -183,6 +241,21 @@ impl Provider {
     ///
     ///   You'll typically register the provider during `DLL_PROCESS_ATTACH` and
     ///   unregister during `DLL_PROCESS_DETACH`.
+    ///
+    /// # Thread safety
+    ///
+    /// Registration only happens via an explicit call to `register` or
+    /// [`Provider::register_with_callback()`]. [`write_event!`] and
+    /// [`Provider::enabled()`] never register the provider, so concurrent writes cannot
+    /// cause a registration.
+    ///
+    /// `register` and `unregister` take an internal busy flag via an atomic swap before
+    /// touching the registration state, so a provider has at most one ETW registration
+    /// handle at a time: if several threads race to call `register`, exactly one of
+    /// them registers the provider and the others panic (either because the provider
+    /// is busy or because it is already registered) without calling `EventRegister`.
+    /// Events written by other threads while `register` is running are either dropped
+    /// (returning 0) or written using the new registration handle.
     pub unsafe fn register(&self) -> u32 {
         return self.register_impl(None, 0);
     }
@@ -265,20 +338,73 @@ impl fmt::Debug for Provider {
     }
 }
 
+/// For use by the write_event macro: the event attributes set by the define_provider
+/// macro's `default_level`, `default_keyword`, `default_opcode`, `default_task`,
+/// `derive_event_ids`, and `task_name` options.
+#[derive(Clone, Copy, Debug)]
+pub struct ProviderDefaults {
+    /// Level for events that do not specify a level.
+    pub level: Level,
+    /// Keyword for events that do not specify a keyword.
+    pub keyword: u64,
+    /// Opcode for events that do not specify an opcode.
+    pub opcode: Opcode,
+    /// Task for events that do not specify a task.
+    pub task: u16,
+    /// If true, events that do not specify an id use `event_id_from_name(event_name)`.
+    pub derive_event_ids: bool,
+    /// (task, name) for each `task_name` option.
+    pub task_names: &'static [(u16, &'static str)],
+}
+
+impl ProviderDefaults {
+    /// Returns the task with the specified name. Used in a constant expression, so a
+    /// name that is not defined by the provider's `task_name` option is a compile error.
+    pub const fn task_from_name(&self, name: &str) -> u16 {
+        let name = name.as_bytes();
+        let mut i = 0;
+        while i != self.task_names.len() {
+            let (task, task_name) = self.task_names[i];
+            let task_name = task_name.as_bytes();
+            if task_name.len() == name.len() {
+                let mut j = 0;
+                while j != name.len() && task_name[j] == name[j] {
+                    j += 1;
+                }
+                if j == name.len() {
+                    return task;
+                }
+            }
+            i += 1;
+        }
+        panic!("task name is not defined by the provider's task_name option");
+    }
+}
+
 /// For use by the define_provider macro: creates a new provider.
 ///
 /// # Safety
 ///
 /// - Must not move-out of a provider while it is registered. `define_provider` enforces
 ///   this by storing the result in an immutable variable.
-pub const unsafe fn provider_new(meta: &'static [u8], id: &Guid) -> Provider {
+pub const unsafe fn provider_new(
+    meta: &'static [u8],
+    id: &Guid,
+    defaults: &ProviderDefaults,
+) -> Provider {
     return Provider {
         context: ProviderContext::new(),
         meta,
         id: *id,
+        defaults: *defaults,
     };
 }
 
+/// For use by the write_event macro: returns the provider's event defaults.
+pub fn provider_defaults(provider: &Provider) -> &ProviderDefaults {
+    return &provider.defaults;
+}
+
 /// For use by the write_event macro: Calls EventWriteTransfer.
 pub fn provider_write_transfer(
     provider: &Provider,
@@ -291,3 +417,33 @@ pub fn provider_write_transfer(
         .context
         .write_transfer(descriptor, activity_id, related_id, dd);
 }
+
+/// For use by the write_event macro: the optional parameters of an event write. The
+/// default value (no activity ids, no flags) is equivalent to a
+/// [`provider_write_transfer`] with `None` activity ids.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct EventWriteOptions<'a> {
+    /// Activity id, or `None` to use the thread's current activity id.
+    pub activity_id: Option<&'a [u8; 16]>,
+    /// Related activity id, or `None` for no related activity id.
+    pub related_id: Option<&'a [u8; 16]>,
+    /// `EventWriteEx` flags, e.g. `EVENT_WRITE_FLAG_NO_FAULTING` (`0x1`).
+    pub flags: u32,
+}
+
+/// For use by the write_event macro: Calls EventWriteEx.
+pub fn provider_write_transfer_ex(
+    provider: &Provider,
+    descriptor: &EventDescriptor,
+    options: &EventWriteOptions,
+    dd: &[EventDataDescriptor],
+) -> u32 {
+    return provider.context.write_ex(
+        descriptor,
+        0,
+        options.flags,
+        options.activity_id,
+        options.related_id,
+        dd,
+    );
+}
@@ -0,0 +1,61 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+use crate::_internal::meta_as_bytes;
+
+/// A `#[repr(C)]` type that can be logged as a struct field by copying its bytes
+/// directly into the event, i.e. via the `raw_struct_value` field type of
+/// [`write_event!`](crate::write_event).
+///
+/// This trait is normally implemented via `#[derive(RawStruct)]`. The derive
+/// requires a non-generic `#[repr(C)]` struct with 1 to 127 named fields, each of type
+/// `bool`, `f32`, `f64`, `i8`, `i16`, `i32`, `i64`, `isize`, `u8`, `u16`, `u32`, `u64`,
+/// `usize`, or [`Guid`](crate::Guid), and no padding between fields. Each member of the
+/// Rust struct becomes a field of the same name within the logged struct.
+///
+/// ```
+/// use tracelogging as tlg;
+///
+/// #[repr(C)]
+/// #[derive(tlg::RawStruct)]
+/// struct Point {
+///     x: i32,
+///     y: i32,
+///     visible: u32,
+/// }
+///
+/// tlg::define_provider!(MY_PROVIDER, "MyCompany.MyComponent");
+/// let point = Point { x: 1, y: 2, visible: 1 };
+/// tlg::write_event!(MY_PROVIDER, "PointEvent", raw_struct_value("Point", Point, &point));
+/// ```
+///
+/// The derive reports an error for members of other types, e.g. arrays or nested
+/// structs:
+///
+/// ```compile_fail
+/// # use tracelogging as tlg;
+/// #[repr(C)]
+/// #[derive(tlg::RawStruct)]
+/// struct Unsupported {
+///     bytes: [u8; 4], // error: unsupported RawStruct field type
+/// }
+/// ```
+///
+/// # Safety
+///
+/// [`RawStruct::RAW_META`] must describe exactly [`RawStruct::FIELD_COUNT`] fields
+/// whose total size is `size_of::<Self>()`, and `Self` must not contain padding or any
+/// other uninitialized bytes.
+pub unsafe trait RawStruct: Sized {
+    /// The number of fields described by [`RawStruct::RAW_META`] (1 to 127).
+    const FIELD_COUNT: u8;
+
+    /// The TraceLogging metadata for the struct's fields, i.e. the same bytes that
+    /// `raw_meta` fields would generate for each member of the struct.
+    const RAW_META: &'static [u8];
+
+    /// Returns the bytes of the value to be logged.
+    fn raw_data(&self) -> &[u8] {
+        return meta_as_bytes(self);
+    }
+}
@@ -0,0 +1,74 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+//! Tests that check that writes do not allocate. These are in their own test binary
+//! because they replace the global allocator.
+
+#![allow(clippy::needless_return)]
+
+use tracelogging as tlg;
+
+/// Counts heap allocations made by each thread, so a test can check that a write
+/// does not allocate even while other tests run in parallel.
+struct CountingAllocator;
+
+std::thread_local! {
+    static ALLOCATIONS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+}
+
+/// Returns the number of heap allocations made by the current thread so far.
+fn thread_allocations() -> usize {
+    return ALLOCATIONS.with(|count| count.get());
+}
+
+unsafe impl std::alloc::GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: std::alloc::Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+        return std::alloc::System.alloc(layout);
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: std::alloc::Layout) {
+        std::alloc::System.dealloc(ptr, layout);
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+struct Unregister(&'static tlg::Provider);
+
+impl Drop for Unregister {
+    fn drop(&mut self) {
+        self.0.unregister();
+    }
+}
+
+#[test]
+fn utf16_no_alloc() {
+    tlg::define_provider!(PROV, "TraceLoggingDynamicTest");
+    let _u = Unregister(&PROV);
+    unsafe { PROV.register() };
+
+    // str16_from_utf8 transcodes into a stack buffer, so repeated writes do not
+    // allocate (even when the provider is enabled, as with the testing feature).
+    let before = thread_allocations();
+    let count = unsafe { core::ptr::read_volatile(&1) }; // Not a constant.
+    let name = "short name".repeat(count);
+    let wide: Vec<u16> = name.encode_utf16().collect();
+    assert!(
+        thread_allocations() > before,
+        "allocation counter is not working"
+    );
+
+    let before = thread_allocations();
+    for i in 0..1000u32 {
+        tlg::write_event!(
+            PROV,
+            "Utf16",
+            u32("i", &i),
+            str16_from_utf8("name", &name),
+            str16("wide", &wide),
+        );
+    }
+    assert_eq!(0, thread_allocations() - before);
+}
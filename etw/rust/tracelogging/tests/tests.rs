@@ -126,13 +126,148 @@ fn guid() {
     }
 }
 
-#[cfg(all(windows))]
+#[test]
+fn guid_u128() {
+    use tlg::Guid;
+
+    // IID_IUnknown, {00000000-0000-0000-C000-000000000046}.
+    let iunknown = Guid::from_u128(&0x00000000_0000_0000_c000_000000000046);
+    assert_eq!(
+        iunknown.to_fields(),
+        (0, 0, 0, [0xC0, 0, 0, 0, 0, 0, 0, 0x46])
+    );
+    assert_eq!(
+        iunknown,
+        Guid::try_parse("{00000000-0000-0000-C000-000000000046}").unwrap()
+    );
+    assert_eq!(
+        iunknown.to_bytes_le(),
+        [0, 0, 0, 0, 0, 0, 0, 0, 0xC0, 0, 0, 0, 0, 0, 0, 0x46]
+    );
+
+    // Field order matches from_fields/to_fields, most-significant field first.
+    let fields = Guid::from_fields(0x01234567, 0x89AB, 0xCDEF, [1, 2, 3, 4, 5, 6, 7, 8]);
+    assert_eq!(fields.to_u128(), 0x01234567_89AB_CDEF_0102_030405060708);
+    assert_eq!(fields, Guid::from_u128(&fields.to_u128()));
+    assert_eq!(fields.to_u128().to_be_bytes(), fields.to_bytes_be());
+
+    // Round-trip.
+    for value in [
+        0u128,
+        1,
+        u128::MAX,
+        1 << 64,
+        1 << 80,
+        1 << 96,
+        1 << 127,
+        0x0123456789ABCDEF_FEDCBA9876543210,
+    ] {
+        assert_eq!(value, Guid::from_u128(&value).to_u128());
+        assert_eq!(
+            Guid::from_bytes_be(&value.to_be_bytes()),
+            Guid::from_u128(&value)
+        );
+    }
+
+    const CONST_GUID: Guid = Guid::from_u128(&0xb3864c38_4273_58c5_545b_8b3608343471);
+    const CONST_U128: u128 = CONST_GUID.to_u128();
+    assert_eq!(CONST_U128, 0xb3864c38_4273_58c5_545b_8b3608343471);
+    assert_eq!(CONST_GUID, Guid::from_name("MyProvider"));
+}
+
+#[test]
+fn guid_display() {
+    use tlg::Guid;
+
+    assert_eq!(
+        Guid::zero().to_string(),
+        "00000000-0000-0000-0000-000000000000"
+    );
+    assert_eq!(
+        Guid::from_u128(&u128::MAX).to_string(),
+        "ffffffff-ffff-ffff-ffff-ffffffffffff"
+    );
+    assert_eq!(
+        format!("{}", Guid::from_name("MyProvider")),
+        format!("{:?}", Guid::from_name("MyProvider"))
+    );
+
+    // Round-trip, including values with every hex digit in every position.
+    let mut value = 0x0123456789ABCDEF_FEDCBA9876543210u128;
+    for _ in 0..1000 {
+        let guid = Guid::from_u128(&value);
+        assert_eq!(Ok(guid), guid.to_string().parse::<Guid>());
+        assert_eq!(Ok(guid), guid.to_string().to_uppercase().parse::<Guid>());
+        assert_eq!(Ok(guid), format!("{{{}}}", guid).parse::<Guid>());
+        value = value
+            .wrapping_mul(0x2360ED051FC65DA44385DF649FCCF645)
+            .wrapping_add(0x5851F42D4C957F2D);
+    }
+    assert_eq!(
+        Ok(Guid::zero()),
+        "{00000000-0000-0000-0000-000000000000}".parse()
+    );
+    assert_eq!(Ok(Guid::zero()), "00000000000000000000000000000000".parse());
+
+    for bad in [
+        "",
+        "0",
+        "{}",
+        "00000000-0000-0000-0000-00000000000",
+        "00000000-0000-0000-0000-0000000000000",
+        "00000000-0000-0000-0000-00000000000g",
+        "00000000+0000-0000-0000-000000000000",
+        "{00000000-0000-0000-0000-000000000000",
+        " 00000000-0000-0000-0000-000000000000",
+    ] {
+        let err = bad.parse::<Guid>().unwrap_err();
+        assert_eq!("invalid GUID syntax", err.to_string());
+    }
+}
+
+#[cfg(windows)]
 #[test]
 fn guid_new() {
     use tlg::Guid;
     assert_ne!(Guid::new(), Guid::zero());
 }
 
+#[test]
+fn const_constructors() {
+    // The values that write_event! computes at compile time can also be built in
+    // const items.
+    const GUID: tlg::Guid = tlg::Guid::from_fields(
+        0x12345678,
+        0x1234,
+        0x5678,
+        *b"\x9A\xBC\xDE\xF0\x12\x34\x56\x78",
+    );
+    const ID: u16 = tlg::event_id_from_name("MyEvent");
+    const DESC: tli::EventDescriptor = tli::EventDescriptor::from_parts(
+        ID,
+        1,
+        tlg::Channel::TraceLogging,
+        tlg::Level::Warning,
+        tlg::Opcode::ActivityStart,
+        7,
+        0x23,
+    );
+    const TAG: [u8; tli::tag_size(0x1234)] = tli::tag_encode(0x1234);
+
+    assert_eq!(
+        GUID,
+        tlg::Guid::from_u128(&0x12345678_1234_5678_9abc_def012345678)
+    );
+    assert_eq!(ID, DESC.id);
+    assert_eq!(1, DESC.version);
+    assert_eq!(tlg::Channel::TraceLogging, DESC.channel);
+    assert_eq!(tlg::Level::Warning, DESC.level);
+    assert_eq!(tlg::Opcode::ActivityStart, DESC.opcode);
+    assert_eq!(7, DESC.task);
+    assert_eq!(0x23, DESC.keyword);
+    assert_eq!([0x80, 0x80, 0xA4, 0x34], TAG);
+}
+
 #[test]
 fn meta_as_bytes() {
     let x = 47i32;
@@ -141,6 +276,38 @@ fn meta_as_bytes() {
     assert_eq!(&x as *const i32 as *const u8, b.as_ptr());
 }
 
+#[test]
+fn cstr_trailing_nul() {
+    // from_cstr stops at the first NUL, so a source that already ends with a NUL
+    // produces the same descriptor as the same text without it. The event then
+    // gets exactly one terminating NUL from the descriptor that follows.
+    let buf8 = b"ab\0";
+    assert_eq!(
+        format!("{:?}", tli::EventDataDescriptor::from_cstr(&buf8[..2])),
+        format!("{:?}", tli::EventDataDescriptor::from_cstr(&buf8[..])),
+    );
+    let buf16 = [97u16, 98, 0];
+    assert_eq!(
+        format!("{:?}", tli::EventDataDescriptor::from_cstr(&buf16[..2])),
+        format!("{:?}", tli::EventDataDescriptor::from_cstr(&buf16[..])),
+    );
+
+    tlg::define_provider!(PROV, "TraceLoggingDynamicTest");
+    let _u = Unregister(&PROV);
+    unsafe { PROV.register() };
+    assert_eq!(
+        0,
+        tlg::write_event!(
+            PROV,
+            "CStrTrailingNul",
+            cstr8("cstr8", "ab"),
+            cstr8("cstr8-0", "ab\0"),
+            cstr16("cstr16", &buf16[..2]),
+            cstr16("cstr16-0", &buf16),
+        )
+    );
+}
+
 #[test]
 fn tag_size() {
     assert_eq!(1, tli::tag_size(0));
@@ -158,212 +325,2904 @@ fn tag_encode() {
 }
 
 #[test]
-fn win_filetime_from_systemtime() {
-    let epoch = std::time::SystemTime::UNIX_EPOCH;
-    let d100 = std::time::Duration::from_secs(100);
+fn raw_struct_derive() {
+    use tlg::RawStruct;
+
+    #[repr(C)]
+    #[derive(tlg::RawStruct)]
+    struct Point {
+        x: i32,
+        y: i32,
+        visible: u32,
+    }
+
+    assert_eq!(3, Point::FIELD_COUNT);
+    assert_eq!(
+        &[
+            b'x', 0, 7, // x: I32
+            b'y', 0, 7, // y: I32
+            b'v', b'i', b's', b'i', b'b', b'l', b'e', 0, 8, // visible: U32
+        ],
+        Point::RAW_META
+    );
+
+    // Data is the struct's bytes, in field order.
+    let point = Point {
+        x: -1,
+        y: 2,
+        visible: 1,
+    };
+    let data = point.raw_data();
+    assert_eq!(12, data.len());
+    let fields: Vec<u32> = data
+        .chunks(4)
+        .map(|c| u32::from_ne_bytes(c.try_into().unwrap()))
+        .collect();
+    assert_eq!(vec![point.x as u32, point.y as u32, point.visible], fields);
+
+    tlg::define_provider!(PROV, "TraceLoggingDynamicTest");
+    let _u = Unregister(&PROV);
+    unsafe { PROV.register() };
+
+    tlg::write_event!(
+        PROV,
+        "RawStructValue",
+        raw_struct_value("Point", Point, &point, tag(0x1234)),
+        raw_struct("Nested", 1),
+        raw_struct_value("Point", Point, &point),
+        u8("after", &1),
+    );
+}
+
+#[test]
+fn write_event_fields() {
+    use tlg::InType;
+    use tlg::OutType;
+
+    #[repr(C)]
+    #[derive(tlg::RawStruct)]
+    struct Pair {
+        a: u16,
+        b: u16,
+    }
+
+    const fn slice(intype: InType) -> InType {
+        return InType::from_int(intype.as_int() | InType::VariableCountFlag);
+    }
+
+    tlg::define_provider!(PROV, "TraceLoggingDynamicTest");
+    let fields = tlg::write_event_fields!(
+        PROV,
+        "Mixed",
+        level(Warning),
+        u32("scalar", &1),
+        u8("tagged", &1, tag(0x1234)),
+        u32_hex("hex", &1),
+        str8("str", "abc", format(Json)),
+        i64_slice("slice", &[1, 2]),
+        struct("outer", {
+            bool8("flag", &true),
+            struct("inner", {
+                guid("guid", &tlg::Guid::zero()),
+            }),
+            f64_slice("floats", &[1.0]),
+        }),
+        raw_field("raw", Str16, &[b'a', 0], format(Xml)),
+        raw_struct_value("pair", Pair, &Pair { a: 1, b: 2 }),
+        str8_chunked("big", "x"),
+    );
+
+    assert_eq!(
+        fields,
+        &[
+            ("scalar", InType::U32, OutType::Default),
+            ("tagged", InType::U8, OutType::Default),
+            ("hex", InType::Hex32, OutType::Default),
+            ("str", InType::Str8, OutType::Json),
+            ("slice", slice(InType::I64), OutType::Default),
+            ("outer", InType::Struct, OutType::from_int(3)),
+            ("flag", InType::U8, OutType::Boolean),
+            ("inner", InType::Struct, OutType::from_int(1)),
+            ("guid", InType::Guid, OutType::Default),
+            ("floats", slice(InType::F64), OutType::Default),
+            ("raw", InType::Str16, OutType::Xml),
+            ("pair", InType::Struct, OutType::from_int(2)),
+            ("big", InType::Str8, OutType::Utf8),
+            ("big_1", InType::Str8, OutType::Utf8),
+            ("big_2", InType::Str8, OutType::Utf8),
+            ("big_3", InType::Str8, OutType::Utf8),
+        ]
+    );
+
+    // Usable as a constant.
+    const EMPTY: &[(&str, InType, OutType)] = tlg::write_event_fields!(PROV, "Empty");
+    assert!(EMPTY.is_empty());
+}
+
+#[test]
+fn pid_tid() {
+    use tlg::InType;
+    use tlg::OutType;
+
+    tlg::define_provider!(PROV, "TraceLoggingDynamicTest");
+    let _u = Unregister(&PROV);
+    unsafe { PROV.register() };
+
+    // Ids from OS APIs are already u32, e.g. std::process::id().
+    let pid = std::process::id();
+    let pids = vec![pid, 4];
+    assert_eq!(
+        0,
+        tlg::write_event!(
+            PROV,
+            "PidTid",
+            pid("Pid", &std::process::id()),
+            pid("PidVar", &pid),
+            pid_slice("Pids", &pids),
+            tid("Tid", &0),
+            tid_slice("Tids", &[1, 2]),
+        )
+    );
+    assert_eq!(
+        tlg::write_event_fields!(
+            PROV,
+            "PidTid",
+            pid("Pid", &std::process::id()),
+            pid_slice("Pids", &pids),
+            tid("Tid", &0),
+            tid_slice("Tids", &[1, 2]),
+        ),
+        &[
+            ("Pid", InType::U32, OutType::Pid),
+            ("Pids", slice(InType::U32), OutType::Pid),
+            ("Tid", InType::U32, OutType::Tid),
+            ("Tids", slice(InType::U32), OutType::Tid),
+        ]
+    );
+
+    const fn slice(intype: InType) -> InType {
+        return InType::from_int(intype.as_int() | InType::VariableCountFlag);
+    }
+}
+
+#[test]
+fn metadata_only_event() {
+    use tlg::InType;
+    use tlg::OutType;
+
+    tlg::define_provider!(PROV, "TraceLoggingDynamicTest");
+    let _u = Unregister(&PROV);
+    unsafe { PROV.register() };
+
+    // A raw_meta field adds metadata but no data descriptor, so the event is written
+    // with only the provider metadata and event metadata descriptors.
+    assert_eq!(
+        0,
+        tlg::write_event!(PROV, "MetaOnly", raw_meta("m", U32, format(Hex)))
+    );
+    assert_eq!(
+        tlg::write_event_fields!(PROV, "MetaOnly", raw_meta("m", U32, format(Hex))),
+        &[("m", InType::U32, OutType::Hex)]
+    );
+}
+
+// With the ascii_names feature, these names are compile errors (see the doc tests).
+#[cfg(not(any(feature = "ascii_names", feature = "disabled")))]
+#[test]
+fn non_ascii_names() {
+    use tlg::InType;
+    use tlg::OutType;
+
+    tlg::define_provider!(PROV, "Prov.Café", keyword_name(1, "Réseau"));
+    assert_eq!(PROV.name(), "Prov.Café");
+
+    let fields = tlg::write_event_fields!(PROV, "Événement", u32("café", &1));
+    assert_eq!([("café", InType::U32, OutType::Default)], fields);
+
+    let _u = Unregister(&PROV);
+    unsafe { PROV.register() };
+    tlg::write_event!(PROV, "Événement", u32("café", &1));
+}
+
+#[test]
+fn char8_utf8() {
+    use tlg::InType;
+    use tlg::OutType;
+
+    tlg::define_provider!(PROV, "TraceLoggingDynamicTest");
+    let _u = Unregister(&PROV);
+    unsafe { PROV.register() };
+
+    tlg::write_event!(
+        PROV,
+        "Char8Utf8",
+        char8_utf8("char", &b'A'),
+        char8_utf8_slice("slice", b"abc"),
+    );
+
+    // Same InType as char8_cp1252, but decoded as UTF-8 instead of CP1252.
+    const U8_SLICE: InType = InType::from_int(InType::U8.as_int() | InType::VariableCountFlag);
+    let fields = tlg::write_event_fields!(
+        PROV,
+        "Char8",
+        char8_cp1252("cp1252", &b'A'),
+        char8_utf8("utf8", &b'A'),
+        char8_cp1252_slice("cp1252_slice", b"abc"),
+        char8_utf8_slice("utf8_slice", b"abc"),
+    );
+    assert_eq!(
+        [
+            ("cp1252", InType::U8, OutType::String),
+            ("utf8", InType::U8, OutType::Utf8),
+            ("cp1252_slice", U8_SLICE, OutType::String),
+            ("utf8_slice", U8_SLICE, OutType::Utf8),
+        ],
+        fields
+    );
+}
+
+#[test]
+fn struct_tuple() {
+    use tlg::InType;
+    use tlg::OutType;
+
+    tlg::define_provider!(PROV, "TraceLoggingDynamicTest");
+    let (x, y, visible) = (-1i32, 2.5f64, true);
+    let fields = tlg::write_event_fields!(
+        PROV,
+        "Tuple",
+        struct_tuple("point", (i32, f64, bool), &(x, y, visible)),
+        struct_tuple("named", (id: u16, guid: tlg::Guid), &(7, tlg::Guid::zero()), tag(5)),
+        u8("after", &1),
+    );
+    assert_eq!(
+        [
+            ("point", InType::Struct, OutType::from_int(3)),
+            ("item0", InType::I32, OutType::Default),
+            ("item1", InType::F64, OutType::Default),
+            ("item2", InType::U8, OutType::Boolean),
+            ("named", InType::Struct, OutType::from_int(2)),
+            ("id", InType::U16, OutType::Default),
+            ("guid", InType::Guid, OutType::Default),
+            ("after", InType::U8, OutType::Default),
+        ],
+        fields
+    );
+
+    // The tuple value is evaluated once.
+    let mut evaluated = 0;
+    let mut point = || {
+        evaluated += 1;
+        return (x, y, visible);
+    };
+    tlg::write_event!(
+        PROV,
+        "Tuple",
+        struct_tuple("point", (i32, f64, bool), &point()),
+        struct_tuple("single", (u64,), &(1,)),
+    );
+    assert!(evaluated <= 1);
+}
+
+#[test]
+fn map_str8() {
+    use std::collections::BTreeMap;
+
+    fn next_u16(data: &mut &[u8]) -> usize {
+        let value = u16::from_le_bytes([data[0], data[1]]);
+        *data = &data[2..];
+        return value as usize;
+    }
+
+    // Decodes the element count and (Key, Value) pairs from the field data.
+    fn decode(mut data: &[u8]) -> Vec<(String, String)> {
+        let count = next_u16(&mut data);
+        let mut pairs = Vec::new();
+        for _ in 0..count {
+            let mut item = [String::new(), String::new()];
+            for s in &mut item {
+                let len = next_u16(&mut data);
+                *s = String::from_utf8(data[..len].to_vec()).unwrap();
+                data = &data[len..];
+            }
+            let [key, value] = item;
+            pairs.push((key, value));
+        }
+        assert!(data.is_empty());
+        return pairs;
+    }
+
+    let mut map = BTreeMap::new();
+    map.insert("b".to_string(), "two".to_string());
+    map.insert("a".to_string(), "one".to_string());
+    map.insert("c".to_string(), String::new());
+    assert_eq!(
+        [("a", "one"), ("b", "two"), ("c", "")]
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .to_vec(),
+        decode(tli::MapStr8Buffer::new(&map).as_bytes())
+    );
+    assert_eq!(0, *tli::MapStr8Buffer::new(&map).dropped());
+
+    // Empty map.
+    let empty: BTreeMap<String, String> = BTreeMap::new();
+    assert_eq!(&[0, 0], tli::MapStr8Buffer::new(&empty).as_bytes());
+    assert_eq!(0, *tli::MapStr8Buffer::new(&empty).dropped());
+
+    // Too many: elements that don't fit are dropped and counted.
+    let value = "x".repeat(100);
+    let pairs = tli::MapStr8Buffer::new((0..100).map(|_| ("k", value.as_str())));
+    let decoded = decode(pairs.as_bytes());
+    assert_eq!(tli::MapStr8Buffer::CAPACITY / 105, decoded.len());
+    assert_eq!(100 - decoded.len(), *pairs.dropped() as usize);
+
+    // Elements after the first one that doesn't fit are dropped, even if they would fit.
+    let big = "x".repeat(tli::MapStr8Buffer::CAPACITY);
+    let pairs = tli::MapStr8Buffer::new([("a", "1"), ("big", big.as_str()), ("b", "2")]);
+    assert_eq!(
+        vec![("a".to_string(), "1".to_string())],
+        decode(pairs.as_bytes())
+    );
+    assert_eq!(2, *pairs.dropped());
+
+    // The dropped count saturates.
+    let pairs = tli::MapStr8Buffer::new((0..70000).map(|_| ("k", value.as_str())));
+    assert_eq!(u16::MAX, *pairs.dropped());
+
+    tlg::define_provider!(PROV, "TraceLoggingDynamicTest");
+    let fields = tlg::write_event_fields!(
+        PROV,
+        "Map",
+        map_str8("attrs", &map, tag(3)),
+        u8("after", &1),
+    );
+    assert_eq!(
+        [
+            (
+                "attrs",
+                tlg::InType::from_int(
+                    tlg::InType::Struct.as_int() | tlg::InType::VariableCountFlag
+                ),
+                tlg::OutType::from_int(2)
+            ),
+            ("Key", tlg::InType::Str8, tlg::OutType::Utf8),
+            ("Value", tlg::InType::Str8, tlg::OutType::Utf8),
+            ("attrs_dropped", tlg::InType::U16, tlg::OutType::Default),
+            ("after", tlg::InType::U8, tlg::OutType::Default),
+        ],
+        fields
+    );
+
+    // The value is iterated once if the event is enabled, and not at all otherwise.
+    let mut iterated = 0;
+    tlg::write_event!(
+        PROV,
+        "Map",
+        map_str8("attrs", map.iter().inspect(|_| iterated += 1)),
+        map_str8("empty", &empty),
+        map_str8("too_many", (0..100).map(|_| ("k", value.as_str()))),
+    );
+    assert_eq!(if always_enabled() { map.len() } else { 0 }, iterated);
+}
+
+#[test]
+fn raw_pointer() {
+    let thing = 5u64;
+    let mut other = [1u8, 2];
+    let address = &thing as *const u64 as usize;
+
+    // A reference, a raw pointer, and the equivalent usize produce identical data.
+    assert_eq!(address, tli::address_from_pointer(&thing));
+    assert_eq!(address, tli::address_from_pointer(&thing as *const u64));
+    assert_eq!(
+        other.as_ptr() as usize,
+        tli::address_from_pointer(other.as_mut_ptr())
+    );
+    assert_eq!(
+        other.as_ptr() as usize,
+        tli::address_from_pointer(&other[..]) // Fat pointer.
+    );
+    assert_eq!(
+        &[address, 0],
+        tli::addresses_from_pointers(&[&thing as *const u64, core::ptr::null()])
+    );
+    assert_eq!(&[address], tli::addresses_from_pointers(&[&thing]));
+    assert!(tli::addresses_from_pointers::<&u8>(&[]).is_empty());
+
+    // Slices longer than any stack buffer are logged in full.
+    let many = vec![&thing; 1000];
+    assert_eq!(1000, tli::addresses_from_pointers(&many).len());
+    assert!(tli::addresses_from_pointers(&many)
+        .iter()
+        .all(|a| *a == address));
+
+    tlg::define_provider!(PROV, "TraceLoggingDynamicTest");
+    let fields = tlg::write_event_fields!(
+        PROV,
+        "Pointer",
+        raw_pointer("ref", &thing),
+        pointer("usize", &address),
+        raw_pointer_slice("refs", &[&thing]),
+    );
+    assert_eq!(
+        [
+            ("ref", tlg::InType::HexSize, tlg::OutType::Default),
+            ("usize", tlg::InType::HexSize, tlg::OutType::Default),
+            (
+                "refs",
+                tlg::InType::from_int(
+                    tlg::InType::HexSize.as_int() | tlg::InType::VariableCountFlag
+                ),
+                tlg::OutType::Default
+            ),
+        ],
+        fields
+    );
+
+    tlg::write_event!(
+        PROV,
+        "Pointer",
+        raw_pointer("ref", &thing),
+        raw_pointer("mut", &mut other),
+        raw_pointer("const", &thing as *const u64),
+        raw_pointer_slice("refs", &[&thing, &thing]),
+        raw_pointer_slice("ptrs", &[core::ptr::null::<u8>()]),
+        raw_pointer_slice("many", &many),
+    );
+}
+
+#[test]
+fn utf16_buffer() {
+    let counted = |value: &[u16]| tli::counted_size(value);
+
+    let emoji = "a\u{1F600}b"; // Non-BMP: surrogate pair.
+    let buffer = tli::Utf16Buffer::new(emoji);
+    let value = buffer.as_slice().unwrap();
+    assert_eq!(&[0x61, 0xD83D, 0xDE00, 0x62], value);
+    assert_eq!(8, counted(value)); // Length in bytes.
+
+    let cjk = String::from("\u{65E5}\u{672C}\u{8A9E}");
+    let buffer = tli::Utf16Buffer::new(&cjk);
+    assert_eq!(Some(&[0x65E5, 0x672C, 0x8A9E][..]), buffer.as_slice());
+    assert_eq!(6, counted(buffer.as_slice().unwrap()));
+    assert_eq!(Some(&[][..]), tli::Utf16Buffer::new("").as_slice());
+
+    // Exactly CAPACITY code units, ending with a surrogate pair.
+    let full = "x".repeat(tli::Utf16Buffer::CAPACITY - 2) + "\u{1F600}";
+    let buffer = tli::Utf16Buffer::new(&full);
+    assert_eq!(
+        full,
+        String::from_utf16(buffer.as_slice().unwrap()).unwrap()
+    );
+
+    // Too long (the surrogate pair doesn't fit): not transcoded, and the event is not
+    // written.
+    let long = "x".repeat(tli::Utf16Buffer::CAPACITY - 1) + "\u{1F600}";
+    assert_eq!(None, tli::Utf16Buffer::new(&long).as_slice());
+
+    tlg::define_provider!(PROV, "TraceLoggingDynamicTest");
+    let fields = tlg::write_event_fields!(
+        PROV,
+        "Utf16",
+        str16_from_utf8("emoji", emoji),
+        str16("str16", &[0x61u16]),
+    );
+    assert_eq!(
+        [
+            ("emoji", tlg::InType::Str16, tlg::OutType::Default),
+            ("str16", tlg::InType::Str16, tlg::OutType::Default),
+        ],
+        fields
+    );
+
+    let _u = Unregister(&PROV);
+    unsafe { PROV.register() };
+    assert_eq!(
+        0,
+        tlg::write_event!(
+            PROV,
+            "Utf16",
+            str16_from_utf8("emoji", emoji),
+            str16_from_utf8("cjk", &cjk),
+            str16_from_utf8("full", &full),
+        )
+    );
+    assert_eq!(
+        if always_enabled() {
+            tli::ERROR_ARITHMETIC_OVERFLOW
+        } else {
+            0
+        },
+        tlg::write_event!(PROV, "Utf16Long", str16_from_utf8("long", &long))
+    );
+}
+
+#[test]
+fn slice_value_forms() {
+    tlg::define_provider!(PROV, "TraceLoggingDynamicTest");
+    let vec: Vec<u32> = vec![1, 2, 3];
+    let array: [u32; 3] = [1, 2, 3];
+    let string = String::from("abc");
+    let bytes: Vec<u8> = b"abc".to_vec();
+
+    // References and owned values of any AsRef<[T]> type are accepted.
+    tlg::write_event!(
+        PROV,
+        "SliceForms",
+        u32_slice("slice", &vec[..]),
+        u32_slice("vec_ref", &vec),
+        u32_slice("vec", vec.clone()),
+        u32_slice("array_ref", &array),
+        u32_slice("array", array),
+        u32_slice("literal", &[1, 2, 3]),
+        u32_slice("literal_array", [1, 2, 3]),
+        str8("str", "abc"),
+        str8("string_ref", &string),
+        str8("string", string.clone()),
+        str8("bytes_ref", &bytes),
+        str8("bytes", bytes.clone()),
+        str8("byte_array_ref", b"abc"),
+        str8("byte_array", *b"abc"),
+        str8_chunked("chunked", string.clone()),
+        bool32_native_slice("bools", vec![true, false]),
+        binary("binary", [1u8, 2]),
+    );
+}
+
+#[test]
+fn guid_bytes() {
+    use tlg::InType;
+    use tlg::OutType;
+
+    tlg::define_provider!(PROV, "TraceLoggingDynamicTest");
+    let _u = Unregister(&PROV);
+    unsafe { PROV.register() };
+
+    let guid = tlg::Guid::from_fields(
+        0xa3a2a1a0,
+        0xb1b0,
+        0xc1c0,
+        [0xd7, 0xd6, 0xd5, 0xd4, 0xd3, 0xd2, 0xd1, 0xd0],
+    );
+    let bytes = guid.to_bytes_le();
+
+    // guid logs the Guid's in-memory bytes; guid_bytes logs the array as-is. On ETW
+    // platforms (little-endian) they are the same bytes.
+    #[cfg(target_endian = "little")]
+    assert_eq!(guid.as_bytes_raw(), &bytes);
+
+    assert_eq!(
+        0,
+        tlg::write_event!(
+            PROV,
+            "GuidBytes",
+            guid("guid", &guid),
+            guid_bytes("guid_bytes", &bytes),
+            guid_bytes("literal", &[0; 16]),
+        )
+    );
+    assert_eq!(
+        tlg::write_event_fields!(
+            PROV,
+            "GuidBytes",
+            guid("guid", &guid),
+            guid_bytes("guid_bytes", &bytes),
+        ),
+        &[
+            ("guid", InType::Guid, OutType::Default),
+            ("guid_bytes", InType::Guid, OutType::Default),
+        ]
+    );
+}
+
+#[test]
+fn binary_typed() {
+    use tlg::InType;
+    use tlg::OutType;
+
+    tlg::define_provider!(PROV, "TraceLoggingDynamicTest");
+    let _u = Unregister(&PROV);
+    unsafe { PROV.register() };
+
+    let values = vec![1u32, 2];
+    assert_eq!(
+        0,
+        tlg::write_event!(
+            PROV,
+            "BinaryTyped",
+            binary_u16("u16", &[1, 2, 3]),
+            binary_u32("u32", &[1u32, 2]),
+            binary_u32("vec", &values),
+            binary_u64("u64", &[1]),
+            binary_u32("hex_override", &[1], format(Default)),
+        )
+    );
+    assert_eq!(
+        tlg::write_event_fields!(
+            PROV,
+            "BinaryTyped",
+            binary_u16("u16", &[1, 2, 3]),
+            binary_u32("u32", &[1u32, 2]),
+            binary_u64("u64", &[1]),
+        ),
+        &[
+            ("u16", InType::Binary, OutType::Hex),
+            ("u32", InType::Binary, OutType::Hex),
+            ("u64", InType::Binary, OutType::Hex),
+        ]
+    );
+
+    // The blob's size prefix is in bytes.
+    assert_eq!(8, tli::counted_size(&[1u32, 2]));
+    assert_eq!(6, tli::counted_size(&[1u16, 2, 3]));
+}
+
+#[test]
+fn binary_value_forms() {
+    use tlg::InType;
+    use tlg::OutType;
+
+    tlg::define_provider!(PROV, "TraceLoggingDynamicTest");
+    let _u = Unregister(&PROV);
+    unsafe { PROV.register() };
+
+    let string = String::from("abc");
+    let bytes: Vec<u8> = b"abc".to_vec();
+
+    // binary and binaryc accept any AsRef<[u8]>, so a string is logged as its bytes.
+    tlg::write_event!(
+        PROV,
+        "BinaryForms",
+        binary("str", "abc"),
+        binary("string_ref", &string),
+        binary("string", string.clone()),
+        binary("slice", &bytes[..]),
+        binary("vec_ref", &bytes),
+        binary("vec", bytes.clone()),
+        binaryc("str_c", "abc"),
+        binaryc("string_ref_c", &string),
+        binaryc("string_c", string.clone()),
+        binaryc("slice_c", &bytes[..]),
+        binaryc("vec_ref_c", &bytes),
+        binaryc("vec_c", bytes.clone()),
+    );
+
+    let fields = tlg::write_event_fields!(
+        PROV,
+        "BinaryForms",
+        binary("binary", &string),
+        binaryc("binaryc", &string),
+        str8("str8", &string),
+    );
+    assert_eq!(
+        [
+            ("binary", InType::Binary, OutType::Default),
+            ("binaryc", InType::BinaryC, OutType::Default),
+            ("str8", InType::Str8, OutType::Utf8),
+        ],
+        fields
+    );
+}
+
+#[cfg(not(feature = "disabled"))] // Checks provider metadata.
+#[test]
+fn decode_event() {
+    use tlg::decode;
+    use tlg::InType;
+    use tlg::OutType;
+
+    const fn array(intype: InType, flag: u8) -> InType {
+        return InType::from_int(intype.as_int() | flag);
+    }
+
+    tlg::define_provider!(PROV, "Prov", trait(2, b"x"));
+
+    // Event metadata as encoded by write_event!, with tags, a struct, and arrays.
+    const TAG: [u8; tli::tag_size(0x1234)] = tli::tag_encode(0x1234);
+    let mut meta = vec![0, 0]; // size
+    meta.extend_from_slice(&TAG); // event tag
+    meta.extend_from_slice(b"Event\0");
+    meta.extend_from_slice(b"u32\0\x08");
+    meta.extend_from_slice(b"str\0\x97\x23"); // Str8 + Utf8
+    meta.extend_from_slice(b"cstr\0\x82\x23"); // CStr8 + Utf8
+    meta.extend_from_slice(b"arr\0\x48"); // U32 | VariableCountFlag
+    meta.extend_from_slice(b"fixed\0\x24\x03\0"); // U8 | ConstantCountFlag, count 3
+    meta.extend_from_slice(b"s\0\x98\x82"); // Struct with 2 fields and a tag
+    meta.extend_from_slice(&TAG);
+    meta.extend_from_slice(b"a\0\x04");
+    meta.extend_from_slice(b"b\0\x0E"); // Binary
+    meta.extend_from_slice(b"sa\0\xD8\x02"); // Struct | VariableCountFlag, 2 fields
+    meta.extend_from_slice(b"x\0\x06");
+    meta.extend_from_slice(b"y\0\x17"); // Str8
+    meta.extend_from_slice(b"after\0\x03");
+    meta[0] = meta.len() as u8;
+
+    let mut data = Vec::new();
+    data.extend_from_slice(&7u32.to_le_bytes()); // u32
+    data.extend_from_slice(b"\x03\0abc"); // str
+    data.extend_from_slice(b"cs\0"); // cstr
+    data.extend_from_slice(b"\x02\0\x01\0\0\0\x02\0\0\0"); // arr
+    data.extend_from_slice(b"xyz"); // fixed
+    data.extend_from_slice(b"\x05\x02\0\xAA\xBB"); // s.a, s.b
+    data.extend_from_slice(b"\x02\0\x01\0\x01\0p\x02\0\0\0"); // sa
+    data.extend_from_slice(b"\xFF"); // after
+
+    let event = decode::decode_event(PROV.raw_meta(), &meta, &data).unwrap();
+    assert_eq!("Prov", event.provider_name());
+    assert_eq!("Event", event.event_name());
+    assert_eq!(0x1234, event.event_tag());
+
+    let fields: Vec<_> = event.fields().map(|f| f.unwrap()).collect();
+    assert_eq!(
+        vec![
+            (
+                "u32",
+                InType::U32,
+                OutType::Default,
+                &7u32.to_le_bytes()[..]
+            ),
+            ("str", InType::Str8, OutType::Utf8, b"abc"),
+            ("cstr", InType::CStr8, OutType::Utf8, b"cs"),
+            (
+                "arr",
+                array(InType::U32, InType::VariableCountFlag),
+                OutType::Default,
+                b"\x01\0\0\0\x02\0\0\0"
+            ),
+            (
+                "fixed",
+                array(InType::U8, InType::ConstantCountFlag),
+                OutType::Default,
+                b"xyz"
+            ),
+            (
+                "s",
+                InType::Struct,
+                OutType::from_int(2),
+                b"\x05\x02\0\xAA\xBB"
+            ),
+            ("a", InType::U8, OutType::Default, b"\x05"),
+            ("b", InType::Binary, OutType::Default, b"\xAA\xBB"),
+            (
+                "sa",
+                array(InType::Struct, InType::VariableCountFlag),
+                OutType::from_int(2),
+                b"\x01\0\x01\0p\x02\0\0\0"
+            ),
+            ("after", InType::I8, OutType::Default, b"\xFF"),
+        ],
+        fields
+    );
+
+    // The decoded field types match the fields declared by write_event!.
+    let declared = tlg::write_event_fields!(
+        PROV,
+        "Event",
+        u32("u32", &7),
+        str8("str", "abc"),
+        cstr8("cstr", "cs"),
+        u32_slice("arr", &[1, 2]),
+        u8_slice("fixed", b"xyz", fixed_count(3)),
+        struct("s", tag(0x1234), {
+            u8("a", &5),
+            binary("b", &[0xAA, 0xBB]),
+        }),
+    );
+    for (field, declared) in fields.iter().zip(declared) {
+        assert_eq!(
+            (declared.0, declared.1, declared.2),
+            (field.0, field.1, field.2)
+        );
+    }
+
+    // Data that is too short is an error, after which the iterator ends.
+    let mut fields = decode::decode_event(PROV.raw_meta(), &meta, &data[..5])
+        .unwrap()
+        .fields();
+    assert!(fields.next().unwrap().is_ok());
+    assert!(fields.next().unwrap().is_err());
+    assert!(fields.next().is_none());
+
+    // Invalid metadata size.
+    assert!(decode::decode_event(PROV.raw_meta(), &meta[..4], &data).is_err());
+    assert!(decode::decode_event(&[1, 0], &meta, &data).is_err());
+}
+
+#[test]
+fn socket_address() {
+    tlg::define_provider!(PROV, "TraceLoggingDynamicTest");
+    let _u = Unregister(&PROV);
+    unsafe { PROV.register() };
+
+    // AF_INET (2), port 80, 127.0.0.1.
+    let mut inet = [0u8; 16];
+    inet[..8].copy_from_slice(&[2, 0, 0, 80, 127, 0, 0, 1]);
+    assert_eq!(16, tli::counted_size(&inet));
+
+    // AF_INET6 (23), port 443, ::1.
+    let mut inet6 = [0u8; 28];
+    inet6[..4].copy_from_slice(&[23, 0, 0x01, 0xBB]);
+    inet6[23] = 1;
+    assert_eq!(28, tli::counted_size(&inet6));
+
+    // SOCKADDR_STORAGE, and AF_INET6 with the Linux family value (10).
+    let storage = [0u8; 128];
+    let mut linux_inet6 = [0u8; 28];
+    linux_inet6[0] = 10;
+    assert_eq!(128, tli::counted_size(&storage));
+
+    tlg::write_event!(
+        PROV,
+        "SocketAddress",
+        socketaddress("inet", &inet),
+        socketaddressc("inet6", &inet6),
+        socketaddress("storage", &storage),
+        socketaddressc("linux_inet6", &linux_inet6),
+    );
+}
+
+#[test]
+fn write_event_large() {
+    // Events with more than 16 fields use a non-inlined helper function.
+    tlg::define_provider!(PROV, "TraceLoggingDynamicTest");
+    let _u = Unregister(&PROV);
+    unsafe { PROV.register() };
+
+    let values: Vec<u32> = (0..20).collect();
+    tlg::write_event!(
+        PROV,
+        "Large",
+        u32("f0", &values[0]),
+        u32("f1", &values[1]),
+        u32("f2", &values[2]),
+        u32("f3", &values[3]),
+        u32("f4", &values[4]),
+        u32("f5", &values[5]),
+        u32("f6", &values[6]),
+        u32("f7", &values[7]),
+        u32("f8", &values[8]),
+        u32("f9", &values[9]),
+        u32("f10", &values[10]),
+        u32("f11", &values[11]),
+        u32("f12", &values[12]),
+        u32("f13", &values[13]),
+        u32("f14", &values[14]),
+        u32("f15", &values[15]),
+        u32("f16", &values[16]),
+        u32("f17", &values[17]),
+        u32("f18", &values[18]),
+        u32("f19", &values[19]),
+    );
+}
+
+#[test]
+fn str8_chunks() {
+    let empty: [&[u8]; 4] = tli::str8_chunks(b"");
+    assert_eq!([b"" as &[u8]; 4], empty);
+
+    let small: [&[u8]; 4] = tli::str8_chunks(b"abc");
+    assert_eq!([b"abc" as &[u8], b"", b"", b""], small);
+
+    // 200KB: 3 full chunks plus a partial chunk.
+    let big = "0123456789".repeat(20 * 1024);
+    let chunks: [&[u8]; 4] = tli::str8_chunks(big.as_bytes());
+    assert_eq!([65535, 65535, 65535, 8195], chunks.map(|c| c.len()));
+    assert_eq!(big.as_bytes(), chunks.concat());
+
+    // Too long: truncated after the last chunk.
+    let huge = "x".repeat(300000);
+    let chunks: [&[u8]; 4] = tli::str8_chunks(huge.as_bytes());
+    assert_eq!([65535; 4], chunks.map(|c| c.len()));
+
+    // Chunks end on UTF-8 character boundaries.
+    let utf8 = "\u{20AC}".repeat(50000); // 3 bytes per char.
+    let chunks: [&[u8]; 4] = tli::str8_chunks(utf8.as_bytes());
+    assert_eq!([65535, 65535, 18930, 0], chunks.map(|c| c.len()));
+    let utf8 = format!("x{}", utf8);
+    let chunks: [&[u8]; 4] = tli::str8_chunks(utf8.as_bytes());
+    assert_eq!([65533, 65535, 18933, 0], chunks.map(|c| c.len()));
+    for chunk in chunks {
+        assert!(core::str::from_utf8(chunk).is_ok());
+    }
+}
+
+#[test]
+fn bool32_native() {
+    // true is logged as a 4-byte Bool32 with value 1.
+    assert_eq!(
+        1i32.to_ne_bytes(),
+        tli::meta_as_bytes(&tli::bool32_from_bool(&true))
+    );
+    assert_eq!(
+        0i32.to_ne_bytes(),
+        tli::meta_as_bytes(&tli::bool32_from_bool(&false))
+    );
+
+    let values = tli::Bool32Array::new(&[true, false, true]);
+    assert_eq!(Some(&[1, 0, 1][..]), values.as_slice());
+    assert_eq!(Some(&[][..]), tli::Bool32Array::new(&[]).as_slice());
+
+    let full = vec![true; tli::Bool32Array::CAPACITY];
+    let values = tli::Bool32Array::new(&full);
+    assert!(values.as_slice().unwrap().iter().all(|&v| v == 1));
+    assert_eq!(tli::Bool32Array::CAPACITY, values.as_slice().unwrap().len());
+
+    // Too long: not converted, and the event is not written.
+    let long = vec![true; tli::Bool32Array::CAPACITY + 1];
+    assert_eq!(None, tli::Bool32Array::new(&long).as_slice());
+
+    tlg::define_provider!(PROV, "TraceLoggingDynamicTest");
+    let _u = Unregister(&PROV);
+    unsafe { PROV.register() };
+    assert_eq!(
+        0,
+        tlg::write_event!(PROV, "Bool32Full", bool32_native_slice("s", &full))
+    );
+    assert_eq!(
+        if always_enabled() {
+            tli::ERROR_ARITHMETIC_OVERFLOW
+        } else {
+            0
+        },
+        tlg::write_event!(PROV, "Bool32Long", bool32_native_slice("s", &long))
+    );
+
+    let fields = tlg::write_event_fields!(
+        PROV,
+        "Bool32Native",
+        bool32_native("b", &true),
+        bool32_native_slice("s", &[true, false]),
+    );
+    assert_eq!(
+        [
+            ("b", tlg::InType::Bool32, tlg::OutType::Default),
+            (
+                "s",
+                tlg::InType::from_int(
+                    tlg::InType::Bool32.as_int() | tlg::InType::VariableCountFlag
+                ),
+                tlg::OutType::Default
+            ),
+        ],
+        fields
+    );
+}
+
+#[cfg(feature = "half")]
+#[test]
+fn f16() {
+    use half::f16;
+
+    let one = f16::ONE;
+    let minus_two_and_a_half = f16::from_f32(-2.5);
+    let smallest = f16::MIN_POSITIVE_SUBNORMAL;
+    assert_eq!(1.0f32.to_bits(), tli::f32_from_f16(&one).to_bits());
+    assert_eq!(
+        (-2.5f32).to_bits(),
+        tli::f32_from_f16(&minus_two_and_a_half).to_bits()
+    );
+    assert_eq!(2.0f32.powi(-24), tli::f32_from_f16(&smallest));
+    assert!(tli::f32_from_f16(&f16::INFINITY).is_infinite());
+    assert!(tli::f32_from_f16(&f16::NAN).is_nan());
+
+    let values = tli::F32Array::new(&[one, minus_two_and_a_half, f16::MAX]);
+    assert_eq!(Some(&[1.0, -2.5, 65504.0][..]), values.as_slice());
+    assert_eq!(Some(&[][..]), tli::F32Array::new(&[]).as_slice());
+
+    let full = vec![one; tli::F32Array::CAPACITY];
+    assert_eq!(
+        tli::F32Array::CAPACITY,
+        tli::F32Array::new(&full).as_slice().unwrap().len()
+    );
+
+    // Too long: not converted, and the event is not written.
+    let long = vec![one; tli::F32Array::CAPACITY + 1];
+    assert_eq!(None, tli::F32Array::new(&long).as_slice());
+
+    tlg::define_provider!(PROV, "TraceLoggingDynamicTest");
+    let fields = tlg::write_event_fields!(
+        PROV,
+        "F16",
+        f16("h", &one),
+        f16_slice("s", &[one, minus_two_and_a_half]),
+    );
+    assert_eq!(
+        [
+            ("h", tlg::InType::F32, tlg::OutType::Default),
+            (
+                "s",
+                tlg::InType::from_int(tlg::InType::F32.as_int() | tlg::InType::VariableCountFlag),
+                tlg::OutType::Default
+            ),
+        ],
+        fields
+    );
+
+    let _u = Unregister(&PROV);
+    unsafe { PROV.register() };
+    let slice = vec![one, minus_two_and_a_half];
+    assert_eq!(
+        0,
+        tlg::write_event!(
+            PROV,
+            "F16",
+            f16("h", &minus_two_and_a_half),
+            f16_slice("s", &slice),
+            f16_slice("a", &[one; 3]),
+            f16_slice("full", &full),
+        )
+    );
+    assert_eq!(
+        if always_enabled() {
+            tli::ERROR_ARITHMETIC_OVERFLOW
+        } else {
+            0
+        },
+        tlg::write_event!(PROV, "F16Long", f16_slice("s", &long))
+    );
+}
+
+#[test]
+fn byte_order() {
+    assert_eq!([0x12, 0x34, 0x56, 0x78], tli::u32_be_bytes(&0x12345678));
+    assert_eq!([0x78, 0x56, 0x34, 0x12], tli::u32_le_bytes(&0x12345678));
+    assert_eq!(
+        [0x01, 0x23, 0x45, 0x67, 0x89, 0xAB, 0xCD, 0xEF],
+        tli::u64_be_bytes(&0x0123456789ABCDEF)
+    );
+    assert_eq!(
+        [0xEF, 0xCD, 0xAB, 0x89, 0x67, 0x45, 0x23, 0x01],
+        tli::u64_le_bytes(&0x0123456789ABCDEF)
+    );
+
+    tlg::define_provider!(PROV, "TraceLoggingDynamicTest");
+    let fields = tlg::write_event_fields!(
+        PROV,
+        "ByteOrder",
+        u32_be("u32_be", &1),
+        u32_le("u32_le", &1),
+        u64_be("u64_be", &1),
+        u64_le("u64_le", &1),
+    );
+    assert_eq!(
+        [
+            ("u32_be", tlg::InType::Binary, tlg::OutType::Default),
+            ("u32_le", tlg::InType::Binary, tlg::OutType::Default),
+            ("u64_be", tlg::InType::Binary, tlg::OutType::Default),
+            ("u64_le", tlg::InType::Binary, tlg::OutType::Default),
+        ],
+        fields
+    );
+
+    let _u = Unregister(&PROV);
+    unsafe { PROV.register() };
+    let value = 0x12345678u32;
+    assert_eq!(
+        0,
+        tlg::write_event!(
+            PROV,
+            "ByteOrder",
+            u32_be("u32_be", &value),
+            u32_le("u32_le", &value),
+            u64_be("u64_be", &(value as u64)),
+            u64_le("u64_le", &(value as u64)),
+        )
+    );
+}
+
+#[test]
+fn str_opt() {
+    use tlg::InType;
+    use tlg::OutType;
+
+    tlg::define_provider!(PROV, "TraceLoggingDynamicTest");
+    let _u = Unregister(&PROV);
+    unsafe { PROV.register() };
+
+    let some_name: Option<&str> = Some("name");
+    let none_name: Option<&str> = None;
+    let owned: Option<String> = Some(String::from("owned"));
+    let wide: Option<&[u16]> = Some(&[0x68, 0x69]);
+
+    let fields = tlg::write_event_fields!(
+        PROV,
+        "StrOpt",
+        str8_opt("some", some_name),
+        str8_opt("none", none_name),
+        str16_opt("wide", wide),
+    );
+    assert_eq!(
+        [
+            ("some", InType::Str8, OutType::Utf8),
+            ("none", InType::Str8, OutType::Utf8),
+            ("wide", InType::Str16, OutType::Default),
+        ],
+        fields
+    );
+
+    // The field is always present: None logs an empty string.
+    assert_eq!(
+        0,
+        tlg::write_event!(
+            PROV,
+            "StrOpt",
+            str8_opt("some", some_name),
+            str8_opt("none", none_name),
+            str8_opt("owned", owned.as_deref()),
+            str8_opt("by_value", owned.clone()),
+            str8_opt("bytes", Some(&b"bytes"[..])),
+            str8_opt("literal_none", None::<&str>, tag(0x1234)),
+            str16_opt("wide", wide),
+            str16_opt("wide_none", None::<&[u16]>),
+        )
+    );
+}
+
+#[cfg(feature = "windows")]
+#[test]
+fn sid_from_psid() {
+    use windows::Win32::Foundation::PSID;
+
+    const NULL_SID: [u8; 12] = [1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+
+    // S-1-5-18 (LocalSystem), followed by bytes that are not part of the SID.
+    let local_system: [u8; 16] = [1, 1, 0, 0, 0, 0, 0, 5, 18, 0, 0, 0, 0xFF, 0xFF, 0xFF, 0xFF];
+    let psid = PSID(local_system.as_ptr() as *mut core::ffi::c_void);
+    let sid = unsafe { tlg::sid_from_psid(psid) };
+    assert_eq!(&local_system[..12], sid);
+
+    // Null and invalid SIDs log the NULL SID instead of reading past the header.
+    assert_eq!(NULL_SID, unsafe { tlg::sid_from_psid(PSID::default()) });
+    let bad_revision = [2u8, 1];
+    let psid_bad_revision = PSID(bad_revision.as_ptr() as *mut core::ffi::c_void);
+    assert_eq!(NULL_SID, unsafe { tlg::sid_from_psid(psid_bad_revision) });
+    let bad_count = [1u8, 16];
+    let psid_bad_count = PSID(bad_count.as_ptr() as *mut core::ffi::c_void);
+    assert_eq!(NULL_SID, unsafe { tlg::sid_from_psid(psid_bad_count) });
+
+    tlg::define_provider!(PROV, "TraceLoggingDynamicTest");
+    let _u = Unregister(&PROV);
+    unsafe { PROV.register() };
+    tlg::write_event!(
+        PROV,
+        "Sid",
+        win_sid("system", unsafe { tlg::sid_from_psid(psid) }),
+        win_sid("null", unsafe { tlg::sid_from_psid(PSID::default()) }),
+    );
+}
+
+#[test]
+fn raw_data_format() {
+    use tlg::InType;
+    use tlg::OutType;
+
+    // The format for raw_data comes from the raw_meta field that declares its type.
+    tlg::define_provider!(PROV, "TraceLoggingDynamicTest");
+    let fields = tlg::write_event_fields!(
+        PROV,
+        "RawData",
+        raw_meta("hex", U32, format(Hex)),
+        raw_meta("ip", U32, format(IPv4), tag(0x1234)),
+        raw_data(&[255, 0, 0, 0, 127, 0, 0, 1]),
+    );
+    assert_eq!(
+        [
+            ("hex", InType::U32, OutType::Hex),
+            ("ip", InType::U32, OutType::IPv4),
+        ],
+        fields
+    );
+
+    let _u = Unregister(&PROV);
+    unsafe { PROV.register() };
+    tlg::write_event!(
+        PROV,
+        "RawData",
+        raw_meta("hex", U32, format(Hex)),
+        raw_meta("ip", U32, format(IPv4), tag(0x1234)),
+        raw_data(&[255, 0, 0, 0, 127, 0, 0, 1]),
+    );
+}
+
+#[test]
+fn fmt_buffer() {
+    #[derive(Debug)]
+    #[allow(dead_code)] // Fields are only read by Debug.
+    struct Custom {
+        id: u32,
+        name: &'static str,
+    }
+
+    let err = std::io::Error::new(std::io::ErrorKind::NotFound, "no such file");
+    assert_eq!(
+        b"no such file",
+        tli::FmtBuffer::from_display(&err).as_bytes()
+    );
+    let custom = Custom { id: 7, name: "x" };
+    assert_eq!(
+        br#"Custom { id: 7, name: "x" }"#,
+        tli::FmtBuffer::from_debug(&custom).as_bytes()
+    );
+    assert_eq!(b"abc", tli::FmtBuffer::from_display("abc").as_bytes());
+    assert!(tli::FmtBuffer::from_display("").as_bytes().is_empty());
+
+    // Too long: truncated at a character boundary.
+    let long = "x".repeat(tli::FmtBuffer::CAPACITY + 1);
+    let buffer = tli::FmtBuffer::from_display(&long);
+    assert_eq!(
+        &long.as_bytes()[..tli::FmtBuffer::CAPACITY],
+        buffer.as_bytes()
+    );
+    let utf8 = "\u{20AC}".repeat(tli::FmtBuffer::CAPACITY); // 3 bytes per char.
+    let buffer = tli::FmtBuffer::from_display(&utf8);
+    assert_eq!(tli::FmtBuffer::CAPACITY / 3 * 3, buffer.as_bytes().len());
+    assert!(core::str::from_utf8(buffer.as_bytes()).is_ok());
+
+    tlg::define_provider!(PROV, "TraceLoggingDynamicTest");
+    let fields = tlg::write_event_fields!(
+        PROV,
+        "Fmt",
+        display8("error", &err),
+        debug8("custom", &custom),
+    );
+    assert_eq!(
+        [
+            ("error", tlg::InType::Str8, tlg::OutType::Utf8),
+            ("custom", tlg::InType::Str8, tlg::OutType::Utf8),
+        ],
+        fields
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn json8() {
+    #[derive(serde::Serialize)]
+    struct Request {
+        id: u32,
+        path: &'static str,
+        tags: [&'static str; 2],
+    }
+
+    let value = Request {
+        id: 7,
+        path: "/index.html",
+        tags: ["a", "\"b\""],
+    };
+    let bytes = tli::to_json_bytes(&value);
+    let parsed: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+    assert_eq!(
+        serde_json::json!({"id": 7, "path": "/index.html", "tags": ["a", "\"b\""]}),
+        parsed
+    );
+
+    // Serialization errors produce an empty value.
+    let bad = std::collections::HashMap::from([((1, 2), 3)]);
+    assert!(tli::to_json_bytes(&bad).is_empty());
+
+    tlg::define_provider!(PROV, "TraceLoggingDynamicTest");
+    let fields = tlg::write_event_fields!(PROV, "Json", json8("request", &value));
+    assert_eq!([("request", tlg::InType::Str8, tlg::OutType::Json)], fields);
+    tlg::write_event!(PROV, "Json", json8("request", &value), json8("str", "abc"));
+}
+
+#[test]
+fn fixed_count() {
+    use tlg::InType;
+    use tlg::OutType;
+
+    const fn fixed(intype: InType) -> InType {
+        return InType::from_int(intype.as_int() | InType::ConstantCountFlag);
+    }
+
+    tlg::define_provider!(PROV, "TraceLoggingDynamicTest");
+    let mac = [0x00u8, 0x15, 0x5D, 0x01, 0x02, 0x03];
+    let fields = tlg::write_event_fields!(
+        PROV,
+        "FixedCount",
+        u8_hex_slice("mac", &mac, fixed_count(6)),
+        u16_slice("pair", &[1, 2], fixed_count(2), tag(0x1234)),
+        u8_slice("variable", &mac),
+    );
+    assert_eq!(
+        [
+            ("mac", fixed(InType::U8), OutType::Hex),
+            ("pair", fixed(InType::U16), OutType::Default),
+            (
+                "variable",
+                InType::from_int(InType::U8.as_int() | InType::VariableCountFlag),
+                OutType::Default
+            ),
+        ],
+        fields
+    );
+
+    tlg::write_event!(
+        PROV,
+        "FixedCount",
+        u8_hex_slice("mac", &mac, fixed_count(6)),
+        u16_slice("pair", &[1, 2], fixed_count(2), tag(0x1234)),
+    );
+}
+
+#[test]
+fn format_outtypes() {
+    use tlg::InType;
+    use tlg::OutType;
+
+    // Legal pairings, in each of the accepted spellings.
+    tlg::define_provider!(PROV, "TraceLoggingDynamicTest");
+    let fields = tlg::write_event_fields!(
+        PROV,
+        "Formats",
+        u32("ip", &0x0100007F, format(IPv4)),
+        u16("port", &80, format(OutType::Port)),
+        i32("hr", &0, format(tlg::OutType::HResult)),
+        u64("fn", &0, format(CodePointer)),
+        binary("addr", &[0u8; 16], format(IPv6)),
+        str16("xml", &[0x3Cu16], format(Xml)),
+        win_filetime("time", &0, format(DateTimeUtc)),
+        f64("hidden", &1.0, format(NoPrint)),
+        u8("computed", &1, format(OutType::from_int(3))),
+        raw_field("raw", I8, &[0], format(IPv6)), // raw_field is not checked.
+    );
+    assert_eq!(
+        [
+            ("ip", InType::U32, OutType::IPv4),
+            ("port", InType::U16, OutType::Port),
+            ("hr", InType::I32, OutType::HResult),
+            ("fn", InType::U64, OutType::CodePointer),
+            ("addr", InType::Binary, OutType::IPv6),
+            ("xml", InType::Str16, OutType::Xml),
+            ("time", InType::FileTime, OutType::DateTimeUtc),
+            ("hidden", InType::F64, OutType::NoPrint),
+            ("computed", InType::U8, OutType::Boolean),
+            ("raw", InType::I8, OutType::IPv6),
+        ],
+        fields
+    );
+}
+
+#[test]
+fn name_style() {
+    use tlg::InType;
+    use tlg::OutType;
+
+    tlg::define_provider!(PROV, "TraceLoggingDynamicTest");
+    let _u = Unregister(&PROV);
+    unsafe { PROV.register() };
+
+    macro_rules! fields {
+        ($($style:tt)*) => {
+            tlg::write_event_fields!(
+                PROV,
+                "NameStyleEvent",
+                $($style)*
+                u32("fieldOne", &1),
+                u32("HTTPStatus", &200),
+                u32("retry_count2", &0),
+                struct("inner-struct", {
+                    str8("user name", "x"),
+                }),
+                struct_tuple("Point", (xValue: i32, y_value: i32), &(1, 2)),
+                map_str8("Attrs", &[("a", "b")]),
+                str8_chunked("bigText", "x"),
+            )
+        };
+    }
+
+    let names = |fields: &[(&'static str, InType, OutType)]| -> Vec<&'static str> {
+        return fields.iter().map(|f| f.0).collect();
+    };
+
+    assert_eq!(
+        names(fields!()),
+        [
+            "fieldOne",
+            "HTTPStatus",
+            "retry_count2",
+            "inner-struct",
+            "user name",
+            "Point",
+            "xValue",
+            "y_value",
+            "Attrs",
+            "Key",
+            "Value",
+            "Attrs_dropped",
+            "bigText",
+            "bigText_1",
+            "bigText_2",
+            "bigText_3",
+        ]
+    );
+    assert_eq!(
+        names(fields!(name_style(snake_case),)),
+        [
+            "field_one",
+            "http_status",
+            "retry_count2",
+            "inner_struct",
+            "user_name",
+            "point",
+            "x_value",
+            "y_value",
+            "attrs",
+            "key",
+            "value",
+            "attrs_dropped",
+            "big_text",
+            "big_text_1",
+            "big_text_2",
+            "big_text_3",
+        ]
+    );
+    assert_eq!(
+        names(fields!(name_style(lowerCamelCase),)),
+        [
+            "fieldOne",
+            "httpStatus",
+            "retryCount2",
+            "innerStruct",
+            "userName",
+            "point",
+            "xValue",
+            "yValue",
+            "attrs",
+            "key",
+            "value",
+            "attrs_dropped",
+            "bigText",
+            "bigText_1",
+            "bigText_2",
+            "bigText_3",
+        ]
+    );
+    assert_eq!(
+        names(fields!(name_style(UpperCamelCase),)),
+        [
+            "FieldOne",
+            "HttpStatus",
+            "RetryCount2",
+            "InnerStruct",
+            "UserName",
+            "Point",
+            "XValue",
+            "YValue",
+            "Attrs",
+            "Key",
+            "Value",
+            "Attrs_dropped",
+            "BigText",
+            "BigText_1",
+            "BigText_2",
+            "BigText_3",
+        ]
+    );
+
+    assert_eq!(
+        0,
+        tlg::write_event!(
+            PROV,
+            "NameStyleEvent",
+            name_style(snake_case),
+            u32("fieldOne", &1),
+        )
+    );
+}
+
+#[test]
+fn time_formats() {
+    use tlg::InType;
+    use tlg::OutType;
+
+    tlg::define_provider!(PROV, "TraceLoggingDynamicTest");
+    let _u = Unregister(&PROV);
+    unsafe { PROV.register() };
+
+    let now = std::time::SystemTime::now();
+    assert_eq!(
+        0,
+        tlg::write_event!(
+            PROV,
+            "TimeFormats",
+            time32("time32", &0, format(DateTimeUtc)),
+            time64("time64", &0, format(DateTimeUtc)),
+            systemtime("systemtime", &now, format(DateTimeUtc)),
+            win_filetime("filetime", &0, format(DateTime)),
+        )
+    );
+    assert_eq!(
+        tlg::write_event_fields!(
+            PROV,
+            "TimeFormats",
+            time32("time32", &0, format(DateTimeUtc)),
+            time64("time64", &0, format(DateTimeUtc)),
+            time64("local", &0, format(DateTime)),
+            time64("default", &0),
+            systemtime("systemtime", &now, format(DateTimeUtc)),
+            win_filetime("filetime", &0, format(DateTimeUtc)),
+        ),
+        &[
+            ("time32", InType::FileTime, OutType::DateTimeUtc),
+            ("time64", InType::FileTime, OutType::DateTimeUtc),
+            ("local", InType::FileTime, OutType::DateTime),
+            ("default", InType::FileTime, OutType::Default),
+            ("systemtime", InType::FileTime, OutType::DateTimeUtc),
+            ("filetime", InType::FileTime, OutType::DateTimeUtc),
+        ]
+    );
+}
+
+#[test]
+fn metadata_size_limit() {
+    // Gets the fields of an event with a struct for each $struct_name, each containing
+    // a raw_meta field named $field (no data blocks). The estimated metadata size is
+    // 2 (size) + 4 (tag) + (event name + 1) + per struct: (struct name + 1 + 2) +
+    // (field name + 1 + 1).
+    macro_rules! wide_event_fields {
+        ($prov:ident, $event:tt, $field:tt, $($struct_name:tt)*) => {
+            tlg::write_event_fields!(
+                $prov,
+                $event,
+                $(struct($struct_name, { raw_meta($field, U8) }),)*
+            )
+        };
+    }
+
+    // 6 + (56 + 1) + 64 * (3 + 3) + 64 * (1015 + 2) = 65535 bytes, exactly at the
+    // limit. The just-over and far-over cases are compile errors (see the doc tests).
+    tlg::define_provider!(PROV, "TraceLoggingDynamicTest");
+    let fields = wide_event_fields!(
+        PROV,
+        "EEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEEE",
+        "xxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxx",
+        "s00" "s01" "s02" "s03" "s04" "s05" "s06" "s07"
+        "s08" "s09" "s10" "s11" "s12" "s13" "s14" "s15"
+        "s16" "s17" "s18" "s19" "s20" "s21" "s22" "s23"
+        "s24" "s25" "s26" "s27" "s28" "s29" "s30" "s31"
+        "s32" "s33" "s34" "s35" "s36" "s37" "s38" "s39"
+        "s40" "s41" "s42" "s43" "s44" "s45" "s46" "s47"
+        "s48" "s49" "s50" "s51" "s52" "s53" "s54" "s55"
+        "s56" "s57" "s58" "s59" "s60" "s61" "s62" "s63"
+    );
+    assert_eq!(128, fields.len());
+    assert_eq!(1015, fields[1].0.len());
+}
+
+#[test]
+fn level_ordering() {
+    // ETW convention: more severe levels have lower values.
+    assert!(tlg::Level::LogAlways < tlg::Level::Critical);
+    assert!(tlg::Level::Critical < tlg::Level::Error);
+    assert!(tlg::Level::Error < tlg::Level::Warning);
+    assert!(tlg::Level::Warning < tlg::Level::Informational);
+    assert!(tlg::Level::Informational < tlg::Level::Verbose);
+    assert!(tlg::Level::Critical < tlg::Level::Verbose);
+    assert!(tlg::Level::Verbose < tlg::Level::from_int(6));
+    assert_eq!(
+        tlg::Level::Critical,
+        tlg::Level::Verbose.min(tlg::Level::Critical)
+    );
+}
+
+#[test]
+fn enum_names() {
+    use tlg::Channel;
+    use tlg::Level;
+    use tlg::Opcode;
+
+    // Any u8 value converts to a Level (via From or TryFrom), named or not.
+    assert_eq!(Level::Warning, Level::from(3));
+    assert_eq!(Level::Informational, Level::from(4));
+    assert_eq!(Level::from_int(200), Level::from(200));
+
+    assert_eq!(6, Level::ALL.len());
+    for (i, level) in Level::ALL.iter().enumerate() {
+        assert_eq!(i as u8, level.as_int());
+    }
+    let level_names: Vec<_> = Level::ALL.iter().map(|l| l.name().unwrap()).collect();
+    assert_eq!(
+        [
+            "LogAlways",
+            "Critical",
+            "Error",
+            "Warning",
+            "Informational",
+            "Verbose"
+        ],
+        level_names[..]
+    );
+    assert_eq!(None, Level::from_int(6).name());
+
+    assert_eq!(26, Opcode::ALL.len());
+    for pair in Opcode::ALL.windows(2) {
+        assert!(pair[0] < pair[1]);
+    }
+    assert!(Opcode::ALL.iter().all(|o| o.name().is_some()));
+    assert_eq!(Some("ActivityStart"), Opcode::Start.name());
+    assert_eq!(Some("Receive"), Opcode::Receive.name());
+    assert_eq!(Some("ReservedOpcode255"), Opcode::ReservedOpcode255.name());
+    assert_eq!(None, Opcode::from_int(10).name());
+
+    assert_eq!(
+        [
+            Channel::TraceClassic,
+            Channel::TraceLogging,
+            Channel::ProviderMetadata
+        ],
+        Channel::ALL
+    );
+    assert_eq!(Some("TraceLogging"), Channel::TraceLogging.name());
+    assert_eq!(None, Channel::from_int(1).name());
+}
+
+#[cfg(feature = "log")]
+#[test]
+fn level_log() {
+    assert_eq!(
+        tlg::Level::Error,
+        tlg::Level::from_log_level(log::Level::Error)
+    );
+    assert_eq!(
+        tlg::Level::Warning,
+        tlg::Level::from_log_level(log::Level::Warn)
+    );
+    assert_eq!(
+        tlg::Level::Informational,
+        tlg::Level::from_log_level(log::Level::Info)
+    );
+    assert_eq!(
+        tlg::Level::Verbose,
+        tlg::Level::from_log_level(log::Level::Debug)
+    );
+    assert_eq!(
+        tlg::Level::Verbose,
+        tlg::Level::from_log_level(log::Level::Trace)
+    );
+
+    assert_eq!(log::Level::Error, tlg::Level::LogAlways.to_log_level());
+    assert_eq!(log::Level::Error, tlg::Level::Critical.to_log_level());
+    assert_eq!(log::Level::Error, tlg::Level::Error.to_log_level());
+    assert_eq!(log::Level::Warn, tlg::Level::Warning.to_log_level());
+    assert_eq!(log::Level::Info, tlg::Level::Informational.to_log_level());
+    assert_eq!(log::Level::Debug, tlg::Level::Verbose.to_log_level());
+    assert_eq!(log::Level::Trace, tlg::Level::from_int(6).to_log_level());
+    assert_eq!(log::Level::Trace, tlg::Level::from_int(255).to_log_level());
+
+    // Round-trip (Trace is lossy since ETW has no level below Verbose).
+    for level in log::Level::iter() {
+        let expected = if level == log::Level::Trace {
+            log::Level::Debug
+        } else {
+            level
+        };
+        assert_eq!(expected, tlg::Level::from_log_level(level).to_log_level());
+    }
+
+    // Severity order is preserved: log::Level::Error < log::Level::Warn, and
+    // tlg::Level::Error < tlg::Level::Warning.
+    for a in log::Level::iter() {
+        for b in log::Level::iter() {
+            if a < b && b != log::Level::Trace {
+                assert!(tlg::Level::from_log_level(a) < tlg::Level::from_log_level(b));
+            }
+        }
+    }
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn systemtime_words_from_naive() {
+    use chrono::NaiveDate;
+
+    // Thursday, June 29, 2023, 12:34:56.789
+    let value = NaiveDate::from_ymd_opt(2023, 6, 29)
+        .unwrap()
+        .and_hms_milli_opt(12, 34, 56, 789)
+        .unwrap();
+    let expected: [u16; 8] = [2023, 6, 4, 29, 12, 34, 56, 789];
+    assert_eq!(expected, tli::systemtime_words_from_naive(&value));
+
+    // Sunday = 0.
+    let sunday = NaiveDate::from_ymd_opt(2023, 7, 2)
+        .unwrap()
+        .and_hms_opt(0, 0, 0)
+        .unwrap();
+    assert_eq!(
+        [2023, 7, 0, 2, 0, 0, 0, 0],
+        tli::systemtime_words_from_naive(&sunday)
+    );
+
+    // Leap second.
+    let leap = NaiveDate::from_ymd_opt(2016, 12, 31)
+        .unwrap()
+        .and_hms_milli_opt(23, 59, 59, 1500)
+        .unwrap();
+    assert_eq!(
+        [2016, 12, 6, 31, 23, 59, 59, 999],
+        tli::systemtime_words_from_naive(&leap)
+    );
+
+    // Saturation.
+    let early = NaiveDate::from_ymd_opt(1000, 1, 1)
+        .unwrap()
+        .and_hms_opt(0, 0, 0)
+        .unwrap();
+    assert_eq!(
+        [1601, 1, 1, 1, 0, 0, 0, 0],
+        tli::systemtime_words_from_naive(&early)
+    );
+    let late = NaiveDate::from_ymd_opt(40000, 1, 1)
+        .unwrap()
+        .and_hms_opt(0, 0, 0)
+        .unwrap();
+    assert_eq!(
+        [30827, 12, 5, 31, 23, 59, 59, 999],
+        tli::systemtime_words_from_naive(&late)
+    );
+
+    tlg::define_provider!(PROV, "TraceLoggingDynamicTest");
+    let _u = Unregister(&PROV);
+    unsafe { PROV.register() };
+    tlg::write_event!(PROV, "Chrono", win_systemtime_chrono("time", &value));
+}
+
+#[test]
+fn win_filetime_from_systemtime() {
+    let epoch = std::time::SystemTime::UNIX_EPOCH;
+    let d100 = std::time::Duration::from_secs(100);
+    assert_eq!(
+        tlg::win_filetime_from_systemtime!(epoch + d100),
+        tli::filetime_from_duration_after_1970(d100)
+    );
+    assert_eq!(
+        tlg::win_filetime_from_systemtime!(epoch - d100),
+        tli::filetime_from_duration_before_1970(d100)
+    );
+}
+
+#[test]
+fn win_filetime_from_systemtime_near_1970() {
+    use std::time::{Duration, SystemTime};
+    const FT_1970: i64 = 0x19DB1DED53E8000;
+    let epoch = SystemTime::UNIX_EPOCH;
+
+    // Conversion rounds down (toward earlier times) on both sides of the epoch, so the
+    // result is floor(nanos / 100) ticks from the epoch with no discontinuity at 1970.
+    for nanos in -100_000i64..=100_000 {
+        let time = if nanos < 0 {
+            epoch - Duration::from_nanos(nanos.unsigned_abs())
+        } else {
+            epoch + Duration::from_nanos(nanos as u64)
+        };
+        assert_eq!(
+            FT_1970 + nanos.div_euclid(100),
+            tlg::win_filetime_from_systemtime!(time),
+            "nanos = {}",
+            nanos
+        );
+    }
+
+    // Same at whole-second offsets, where the Err branch carries a seconds component.
+    for seconds in [1i64, 59, 3600, 86400 * 365] {
+        for nanos in [-101i64, -100, -99, -1, 0, 1, 99, 100, 101] {
+            let offset = seconds * 1_000_000_000 + nanos;
+            let time_before = epoch - Duration::from_nanos(offset as u64);
+            let time_after = epoch + Duration::from_nanos(offset as u64);
+            assert_eq!(
+                FT_1970 + (-offset).div_euclid(100),
+                tlg::win_filetime_from_systemtime!(time_before),
+                "-{}ns",
+                offset
+            );
+            assert_eq!(
+                FT_1970 + offset.div_euclid(100),
+                tlg::win_filetime_from_systemtime!(time_after),
+                "+{}ns",
+                offset
+            );
+        }
+    }
+
+    // 1ns before the epoch is in the tick before the epoch; 1ns after is in the tick
+    // starting at the epoch.
+    assert_eq!(
+        1,
+        tlg::win_filetime_from_systemtime!(epoch + Duration::from_nanos(1))
+            - tlg::win_filetime_from_systemtime!(epoch - Duration::from_nanos(1))
+    );
+}
+
+#[test]
+fn filetime_from_duration_after_1970() {
+    use core::time::Duration;
+    use tli::filetime_from_duration_after_1970 as ft_from_dur_after;
+    const FT_PER_SEC: i64 = 10000000;
+    let ft_1970 = 0x19DB1DED53E8000;
+    let ft_30828_2 = 0x7FFF35F4F06C8000 - FT_PER_SEC * 2;
+    let n000 = Duration::ZERO;
+    let n001 = Duration::from_nanos(1);
+    let n099 = Duration::from_nanos(99);
+    let pos_max_2 = Duration::from_secs(0xD408314C00 - 2); // time between 1970 and 30828-2s
+
+    // Adding to 1970
+
+    // Test each second starting 2 seconds before boundary to 2 seconds after.
+    for seconds in 0..4 {
+        let dur_s = Duration::from_secs(seconds);
+        let ft_s = (10000000 * seconds) as i64;
+
+        // Test each tick starting 2 ticks before second to 2 ticks after.
+        for ticks in 0..4 {
+            let dur_ticks = Duration::from_nanos(ticks * 100);
+            let ft_ticks = ticks as i64;
+
+            assert_eq!(
+                ft_clamp(ft_30828_2 + ft_s + ft_ticks),
+                ft_from_dur_after(pos_max_2 + dur_s + dur_ticks + n099)
+            );
+            assert_eq!(
+                ft_clamp(ft_30828_2 + ft_s + ft_ticks),
+                ft_from_dur_after(pos_max_2 + dur_s + dur_ticks + n001)
+            );
+            assert_eq!(
+                ft_clamp(ft_30828_2 + ft_s + ft_ticks),
+                ft_from_dur_after(pos_max_2 + dur_s + dur_ticks + n000)
+            );
+
+            assert_eq!(
+                ft_clamp(ft_1970 + ft_s + ft_ticks),
+                ft_from_dur_after(dur_s + dur_ticks + n099)
+            );
+            assert_eq!(
+                ft_clamp(ft_1970 + ft_s + ft_ticks),
+                ft_from_dur_after(dur_s + dur_ticks + n001)
+            );
+            assert_eq!(
+                ft_clamp(ft_1970 + ft_s + ft_ticks),
+                ft_from_dur_after(dur_s + dur_ticks + n000)
+            );
+        }
+    }
+}
+
+#[test]
+fn filetime_from_duration_before_1970() {
+    use core::time::Duration;
+    use tli::filetime_from_duration_before_1970 as ft_from_dur_before;
+    const FT_PER_SEC: i64 = 10000000;
+    let ft_1601_2 = FT_PER_SEC * 2;
+    let ft_1970 = 0x19DB1DED53E8000;
+    let n000 = Duration::ZERO;
+    let n001 = Duration::from_nanos(1);
+    let n099 = Duration::from_nanos(99);
+    let neg_max_2 = Duration::from_secs(0x2B6109100 - 2); // time between 1601+2s and 1970
+
+    // Subtracting from 1970
+
+    // Test each second starting 2 seconds before boundary to 2 seconds after.
+    for seconds in 0..4 {
+        let dur_s = Duration::from_secs(seconds);
+        let ft_s = (10000000 * seconds) as i64;
+
+        // Test each tick starting 2 ticks before second to 2 ticks after.
+        for ticks in 0..4 {
+            let dur_ticks = Duration::from_nanos(ticks * 100);
+            let ft_ticks = ticks as i64;
+
+            // Test rounding down at T-0.99, T-0.01, and T-0.00.
+            assert_eq!(
+                ft_clamp(ft_1601_2 - ft_s - ft_ticks - 1),
+                ft_from_dur_before(neg_max_2 + dur_s + dur_ticks + n099)
+            );
+            assert_eq!(
+                ft_clamp(ft_1601_2 - ft_s - ft_ticks - 1),
+                ft_from_dur_before(neg_max_2 + dur_s + dur_ticks + n001)
+            );
+            assert_eq!(
+                ft_clamp(ft_1601_2 - ft_s - ft_ticks),
+                ft_from_dur_before(neg_max_2 + dur_s + dur_ticks + n000)
+            );
+
+            assert_eq!(
+                ft_clamp(ft_1970 - ft_s - ft_ticks - 1),
+                ft_from_dur_before(dur_s + dur_ticks + n099)
+            );
+            assert_eq!(
+                ft_clamp(ft_1970 - ft_s - ft_ticks - 1),
+                ft_from_dur_before(dur_s + dur_ticks + n001)
+            );
+            assert_eq!(
+                ft_clamp(ft_1970 - ft_s - ft_ticks),
+                ft_from_dur_before(dur_s + dur_ticks + n000)
+            );
+        }
+    }
+}
+
+fn ft_clamp(ft: i64) -> i64 {
+    let ft_1601 = 0;
+    let ft_30828 = 0x7FFF35F4F06C8000;
+    return if ft < ft_1601 {
+        0
+    } else if ft >= ft_30828 {
+        ft_30828 - 1
+    } else {
+        ft
+    };
+}
+
+struct Unregister(&'static tlg::Provider);
+
+/// True if providers are always enabled (`testing` feature without ETW).
+fn always_enabled() -> bool {
+    return cfg!(all(feature = "testing", not(feature = "disabled")))
+        && matches!(tlg::NATIVE_IMPLEMENTATION, tlg::NativeImplementation::Other);
+}
+
+impl Drop for Unregister {
+    fn drop(&mut self) {
+        self.0.unregister();
+    }
+}
+
+#[cfg(not(feature = "disabled"))] // Checks provider metadata.
+#[test]
+fn define_provider() {
+    tlg::define_provider!(PROV, "TraceLoggingDynamicTest");
+
+    fn callback(
+        _s: &tlg::Guid,
+        _c: u32,
+        _l: tlg::Level,
+        _anyk: u64,
+        _allk: u64,
+        _flt: usize,
+        ctx: usize,
+    ) {
+        assert_eq!(12345usize, ctx);
+    }
+
+    let _u = Unregister(&PROV);
+    assert_eq!(always_enabled(), PROV.enabled(tlg::Level::LogAlways, 0));
+    PROV.unregister();
+    assert_eq!(always_enabled(), PROV.enabled(tlg::Level::LogAlways, 0));
+    unsafe { PROV.register() };
+    PROV.unregister();
+    assert_eq!(always_enabled(), PROV.enabled(tlg::Level::LogAlways, 0));
+    PROV.unregister();
+    assert_eq!(always_enabled(), PROV.enabled(tlg::Level::LogAlways, 0));
+    unsafe { PROV.register_with_callback(callback, 12345usize) };
+    PROV.unregister();
+    assert_eq!(always_enabled(), PROV.enabled(tlg::Level::LogAlways, 0));
+    PROV.unregister();
+    assert_eq!(always_enabled(), PROV.enabled(tlg::Level::LogAlways, 0));
+    PROV.raw_meta();
+
+    tlg::define_provider!(PROV1, "TestProvider1");
+    assert_eq!("TestProvider1", PROV1.name());
+    assert_eq!(&tlg::Guid::from_name("TestProvider1"), PROV1.id());
+
+    tlg::define_provider!(
+        PROV2,
+        "TestProvider2",
+        group_id("e3c21ff5-164e-4cbb-b08c-d47e2dcf9197")
+    );
+    assert_eq!("TestProvider2", PROV2.name());
+    assert_eq!(&tlg::Guid::from_name("TestProvider2"), PROV2.id());
+
+    tlg::define_provider!(
+        PROV3,
+        "TestProvider3",
+        id("d93270c9-8144-4209-99a6-3acd39aaae71")
+    );
+    assert_eq!("TestProvider3", PROV3.name());
+    assert_eq!(
+        &tlg::Guid::from_u128(&0xd93270c9_8144_4209_99a6_3acd39aaae71),
+        PROV3.id()
+    );
+
+    tlg::define_provider!(
+        PROV4,
+        "TestProvider4",
+        id("632a8743-6a0d-456f-9ae8-a26febe2dbc3"),
+        group_id("e3c21ff5-164e-4cbb-b08c-d47e2dcf9197")
+    );
+    assert_eq!("TestProvider4", PROV4.name());
+    assert_eq!(
+        &tlg::Guid::from_u128(&0x632a8743_6a0d_456f_9ae8_a26febe2dbc3),
+        PROV4.id()
+    );
+}
+
+#[test]
+fn with_activity() {
+    tlg::define_provider!(PROV, "TraceLoggingDynamicTest");
+    let _u = Unregister(&PROV);
+    unsafe { PROV.register() };
+
+    // Without ETW, the thread-local activity id is not supported and is always zero.
+    let etw = matches!(
+        tlg::NATIVE_IMPLEMENTATION,
+        tlg::NativeImplementation::Windows
+    );
+    let outer = tlg::Provider::current_thread_activity_id();
+    let activity = tlg::Guid::from_name("with_activity");
+    let expected = if etw { activity } else { outer };
+
+    // Events inside the closure use the thread-local activity id.
+    let result = tlg::Provider::with_activity(&activity, |id| {
+        assert_eq!(&activity, id);
+        assert_eq!(expected, tlg::Provider::current_thread_activity_id());
+        assert_eq!(0, tlg::write_event!(PROV, "First"));
+        assert_eq!(expected, tlg::Provider::current_thread_activity_id());
+        assert_eq!(0, tlg::write_event!(PROV, "Second"));
+        return 5;
+    });
+    assert_eq!(5, result);
+    assert_eq!(outer, tlg::Provider::current_thread_activity_id());
+
+    // The previous activity id is restored even if the closure panics.
+    let panicked = std::panic::catch_unwind(|| {
+        tlg::Provider::with_activity(&activity, |_| {
+            assert_eq!(expected, tlg::Provider::current_thread_activity_id());
+            panic!("inside with_activity");
+        });
+    });
+    assert!(panicked.is_err());
+    assert_eq!(outer, tlg::Provider::current_thread_activity_id());
+}
+
+#[test]
+fn activity_id_evaluation() {
+    use core::cell::Cell;
+
+    tlg::define_provider!(PROV, "TraceLoggingDynamicTest");
+    let calls = Cell::new(0);
+    let id = tlg::Guid::from_u128(&0x12345678_1234_1234_1234_123456789abc);
+    let get_id = || {
+        calls.set(calls.get() + 1);
+        return &id;
+    };
+
+    // PROV is not registered, so it is not enabled (except in testing mode) and the
+    // activity_id and related_id expressions must not be evaluated.
+    let enabled = PROV.enabled(tlg::Level::Verbose, 1);
+    assert_eq!(always_enabled(), enabled);
+    tlg::write_event!(
+        PROV,
+        "ActivityIds",
+        activity_id(get_id()),
+        related_id(get_id()),
+        u32("value", &1),
+    );
+    assert_eq!(if enabled { 2 } else { 0 }, calls.get());
+}
+
+#[test]
+fn provider_expression() {
+    use core::cell::Cell;
+
+    tlg::define_provider!(PROV, "TraceLoggingDynamicTest");
+
+    struct Component {
+        provider: &'static tlg::Provider,
+    }
+
+    impl Component {
+        fn trace(&self, value: u32) -> u32 {
+            return tlg::write_event!(self.provider, "FieldAccess", u32("value", &value));
+        }
+    }
+
+    let component = Component { provider: &PROV };
+    let calls = Cell::new(0);
+    let get_prov = || {
+        calls.set(calls.get() + 1);
+        return &PROV;
+    };
+
+    // Unregistered: no-op, but the provider expression is still evaluated once
+    // (never with the "disabled" feature).
+    assert_eq!(0, component.trace(1));
+    assert_eq!(0, tlg::write_event!(get_prov(), "FunctionCall"));
+    assert_eq!(if cfg!(feature = "disabled") { 0 } else { 1 }, calls.get());
+
+    let _u = Unregister(&PROV);
+    unsafe { PROV.register() };
+
+    assert_eq!(0, component.trace(2));
+    assert_eq!(
+        0,
+        tlg::write_event!(
+            get_prov(),
+            "FunctionCall",
+            level(Warning),
+            keyword(0x10),
+            u32("value", &3),
+        )
+    );
+    assert_eq!(if cfg!(feature = "disabled") { 0 } else { 2 }, calls.get());
+}
+
+#[test]
+fn runtime_descriptor() {
+    use core::cell::Cell;
+
+    tlg::define_provider!(PROV, "TraceLoggingDynamicTest");
+    let _u = Unregister(&PROV);
+    unsafe { PROV.register() };
+
+    // Descriptors computed at runtime, e.g. from a table.
+    let table = [
+        tli::EventDescriptor::from_parts(
+            10,
+            1,
+            tlg::Channel::TraceLogging,
+            tlg::Level::Critical,
+            tlg::Opcode::Info,
+            0,
+            0x1,
+        ),
+        tli::EventDescriptor::from_parts(
+            11,
+            0,
+            tlg::Channel::TraceLogging,
+            tlg::Level::Verbose,
+            tlg::Opcode::Info,
+            0,
+            0x8000,
+        ),
+    ];
+
+    for desc in &table {
+        // The enabled check uses the descriptor's level and keyword, so the field
+        // value is evaluated only if PROV is enabled for them.
+        let enabled = PROV.enabled(desc.level, desc.keyword);
+        let descriptors = Cell::new(0);
+        let values = Cell::new(0);
+        let get_desc = || {
+            descriptors.set(descriptors.get() + 1);
+            return desc;
+        };
+        let get_value = || {
+            values.set(values.get() + 1);
+            return 5u32;
+        };
+        tlg::write_event!(
+            PROV,
+            "RuntimeDescriptor",
+            descriptor(get_desc()),
+            u32("value", &get_value()),
+        );
+        assert_eq!(
+            if cfg!(feature = "disabled") { 0 } else { 1 },
+            descriptors.get()
+        );
+        assert_eq!(if enabled { 1 } else { 0 }, values.get());
+    }
+
+    // Other options may still be used with a descriptor.
+    assert_eq!(
+        0,
+        tlg::write_event!(
+            PROV,
+            "RuntimeDescriptor",
+            descriptor(&table[0]),
+            tag(0x1234),
+            write_flags(0),
+        )
+    );
+}
+
+#[test]
+fn write_events() {
+    use core::cell::Cell;
+
+    tlg::define_provider!(PROV, "TraceLoggingDynamicTest");
+    let _u = Unregister(&PROV);
+    unsafe { PROV.register() };
+
+    let enabled = PROV.enabled(tlg::Level::Verbose, 1);
+    let iterations = Cell::new(0);
+    let items = [(1u32, "a"), (2, "b"), (3, "c")];
+    let get_items = || {
+        iterations.set(iterations.get() + 1);
+        return items.iter();
+    };
+    let values = Cell::new(0);
+    let count = |id: &u32| {
+        values.set(values.get() + 1);
+        return *id;
+    };
+
+    // One enabled check; the iterator and field values are evaluated only if enabled.
+    assert_eq!(
+        0,
+        tlg::write_events!(
+            PROV,
+            "Batch",
+            for (id, name) in get_items(),
+            level(Verbose),
+            keyword(1),
+            u32("Id", &count(id)),
+            str8("Name", name),
+        )
+    );
+    assert_eq!(if enabled { 1 } else { 0 }, iterations.get());
+    assert_eq!(if enabled { items.len() } else { 0 }, values.get());
+
+    // Works with a provider variable, the other event options, and an empty batch.
+    let prov = &PROV;
+    let desc = tli::EventDescriptor::new(tlg::Level::Verbose, 1);
+    assert_eq!(
+        0,
+        tlg::write_events!(
+            (prov),
+            "Batch",
+            for x in core::iter::empty::<u32>(),
+            descriptor(&desc),
+            tag(0x1234),
+            u32("x", &x),
+        )
+    );
+}
+
+#[test]
+fn write_flags() {
+    tlg::define_provider!(PROV, "TraceLoggingDynamicTest");
+    let dd = [tli::EventDataDescriptor::from_raw_bytes(PROV.raw_meta(), 2)];
+    let desc = tli::EventDescriptor::new(tlg::Level::Verbose, 1);
+
+    // Unregistered: no-op.
+    assert_eq!(
+        0,
+        tli::provider_write_transfer_ex(
+            &PROV,
+            &desc,
+            &tli::EventWriteOptions {
+                flags: 2,
+                ..Default::default()
+            },
+            &dd
+        )
+    );
+    assert_eq!(0, tlg::write_event!(PROV, "Unregistered", write_flags(2)));
+
+    let _u = Unregister(&PROV);
+    unsafe { PROV.register() };
+
+    let flags = 1u32; // EVENT_WRITE_FLAG_NO_FAULTING
+    tlg::write_event!(PROV, "NoFaulting", write_flags(flags), u32("flags", &flags));
+    tlg::write_event!(
+        PROV,
+        "InPrivate",
+        write_flags(2), // EVENT_WRITE_FLAG_INPRIVATE
+        activity_id(&tlg::Guid::from_name("activity")),
+        u32("flags", &2),
+    );
+}
+
+#[test]
+fn write_options() {
+    tlg::define_provider!(PROV, "TraceLoggingDynamicTest");
+    let activity = tlg::Guid::from_name("activity");
+    let related = tlg::Guid::from_name("related");
+
+    let options = tli::EventWriteOptions::default();
+    assert_eq!(None, options.activity_id);
+    assert_eq!(None, options.related_id);
+    assert_eq!(0, options.flags);
+
+    let _u = Unregister(&PROV);
+    unsafe { PROV.register() };
+
+    // Two options.
+    tlg::write_event!(PROV, "AidRid", activity_id(&activity), related_id(&related));
+    tlg::write_event!(PROV, "AidFlags", activity_id(&activity), write_flags(1));
+    tlg::write_event!(PROV, "RidFlags", related_id(&related), write_flags(1));
+
+    // Three options, in any order, with fields.
+    tlg::write_event!(
+        PROV,
+        "All",
+        write_flags(1),
+        related_id(&related),
+        activity_id(activity.as_bytes_raw()),
+        u32("value", &1),
+    );
+
+    // Option values are evaluated only if enabled, once each, before the field values
+    // (activity_id, then related_id, then write_flags).
+    let mut order = Vec::new();
+    let mut next = |name: &'static str| {
+        order.push(name);
+        return 1u32;
+    };
+    assert_eq!(
+        0,
+        tlg::write_event!(
+            PROV,
+            "Order",
+            activity_id(&activity),
+            write_flags(next("flags")),
+            related_id(if next("related") == 1 {
+                &related
+            } else {
+                &activity
+            }),
+            u32("value", &next("value")),
+        )
+    );
+    let expected: &[&str] = if always_enabled() {
+        &["related", "flags", "value"]
+    } else {
+        &[]
+    };
+    assert_eq!(expected, &order[..]);
+}
+
+#[cfg(not(feature = "disabled"))] // Checks provider metadata.
+#[test]
+fn provider_traits() {
+    tlg::define_provider!(
+        PROV,
+        "Prov",
+        trait(2, b"\x01\xFFa"),
+        group_id("e3c21ff5-164e-4cbb-b08c-d47e2dcf9197"),
+        trait(0x80, &[0xAA, 0xBB]),
+    );
+
+    let mut expected = Vec::new();
+    expected.extend_from_slice(&[37, 0]); // size
+    expected.extend_from_slice(b"Prov\0"); // name
+    expected.extend_from_slice(&[19, 0, 1]); // group trait (always first)
+    expected.extend_from_slice(
+        &tlg::Guid::from_u128(&0xe3c21ff5_164e_4cbb_b08c_d47e2dcf9197).to_bytes_le(),
+    );
+    expected.extend_from_slice(&[6, 0, 2, 1, 0xFF, b'a']); // custom traits in order
+    expected.extend_from_slice(&[5, 0, 0x80, 0xAA, 0xBB]);
+    assert_eq!(expected.len(), 37);
+    assert_eq!(PROV.raw_meta(), &expected[..]);
+    assert_eq!(PROV.name(), "Prov");
+
+    tlg::define_provider!(PROV_EMPTY, "Prov", trait(2, &[]));
+    assert_eq!(PROV_EMPTY.raw_meta(), b"\x0A\0Prov\0\x03\0\x02");
+}
+
+#[cfg(not(feature = "disabled"))] // Checks provider metadata.
+#[test]
+fn provider_keyword_names() {
+    tlg::define_provider!(
+        PROV,
+        "Prov",
+        keyword_name(0x10, "Net"),
+        trait(2, b"x"),
+        keyword_name(0x8000_0000_0000_0000, "Hi"),
+    );
+
+    let mut expected = Vec::new();
+    expected.extend_from_slice(&[37, 0]); // size
+    expected.extend_from_slice(b"Prov\0"); // name
+    expected.extend_from_slice(&[4, 0, 2, b'x']); // custom traits
+    expected.extend_from_slice(&[26, 0, 128]); // keyword names trait (always last)
+    expected.extend_from_slice(&0x10u64.to_le_bytes());
+    expected.extend_from_slice(b"Net\0");
+    expected.extend_from_slice(&0x8000_0000_0000_0000u64.to_le_bytes());
+    expected.extend_from_slice(b"Hi\0");
+    assert_eq!(expected.len(), 37);
+    assert_eq!(PROV.raw_meta(), &expected[..]);
+    assert_eq!(PROV.name(), "Prov");
+
+    let _u = Unregister(&PROV);
+    unsafe { PROV.register() };
+    tlg::write_event!(PROV, "Event", keyword(0x10), u32("Value", &1));
+}
+
+tlg::define_provider!(
+    PROV_DEFAULTS_PATH,
+    "TraceLoggingDynamicTest",
+    default_level(Warning),
+);
+
+mod provider_defaults_path {
+    use tracelogging as tlg;
+
+    pub fn write() -> u32 {
+        return tlg::write_event!(super::PROV_DEFAULTS_PATH, "InheritPath");
+    }
+}
+
+#[test]
+fn provider_defaults() {
+    tlg::define_provider!(PROV, "TraceLoggingDynamicTest");
+    let defaults = tli::provider_defaults(&PROV);
+    assert_eq!(tlg::Level::Verbose, defaults.level);
+    assert_eq!(1, defaults.keyword);
+    assert_eq!(tlg::Opcode::Info, defaults.opcode);
+    assert_eq!(0, defaults.task);
+    assert!(!defaults.derive_event_ids);
+    assert!(defaults.task_names.is_empty());
+
+    tlg::define_provider!(
+        PROV_DEFAULTS,
+        "TraceLoggingDynamicTest",
+        default_level(Warning),
+        default_keyword(0x10),
+    );
+    assert_eq!(
+        tlg::Level::Warning,
+        tli::provider_defaults(&PROV_DEFAULTS).level
+    );
+    assert_eq!(0x10, tli::provider_defaults(&PROV_DEFAULTS).keyword);
+
+    tlg::define_provider!(
+        PROV_INT,
+        "TraceLoggingDynamicTest",
+        default_keyword(0x20 | 0x1),
+        default_level(2),
+    );
+    assert_eq!(tlg::Level::Error, tli::provider_defaults(&PROV_INT).level);
+    assert_eq!(0x21, tli::provider_defaults(&PROV_INT).keyword);
+
+    let _u = Unregister(&PROV_DEFAULTS);
+    unsafe { PROV_DEFAULTS.register() };
+
+    // Inherit level and keyword from provider.
+    tlg::write_event!(PROV_DEFAULTS, "Inherit");
+
+    // Override level, inherit keyword.
+    tlg::write_event!(PROV_DEFAULTS, "OverrideLevel", level(Critical));
+
+    // Override keyword, inherit level.
+    tlg::write_event!(PROV_DEFAULTS, "OverrideKeyword", keyword(0x4));
+
+    // Override both.
+    tlg::write_event!(
+        PROV_DEFAULTS,
+        "OverrideBoth",
+        level(Informational),
+        keyword(0x4),
+        keyword(0x8),
+    );
+
+    // Defaults also apply when the provider is a path or an expression.
+    assert_eq!(
+        tlg::Level::Warning,
+        tli::provider_defaults(&PROV_DEFAULTS_PATH).level
+    );
+    let _u = Unregister(&PROV_DEFAULTS_PATH);
+    unsafe { PROV_DEFAULTS_PATH.register() };
+    assert_eq!(0, provider_defaults_path::write());
+    tlg::write_event!(&PROV_DEFAULTS, "InheritExpr");
+    let provider: &tlg::Provider = &PROV_DEFAULTS;
+    tlg::write_event!((provider), "InheritLocal", u32("Value", &1));
+}
+
+#[test]
+fn provider_name_in_use() {
+    // A type with the same name as the provider does not conflict with the provider.
+    #[allow(dead_code, non_camel_case_types, clippy::upper_case_acronyms)]
+    struct PROV {}
+
+    tlg::define_provider!(PROV, "TraceLoggingDynamicTest", default_keyword(0x10));
+    assert_eq!(0x10, tli::provider_defaults(&PROV).keyword);
+
+    let _u = Unregister(&PROV);
+    unsafe { PROV.register() };
+    tlg::write_event!(PROV, "Inherit");
+}
+
+#[test]
+fn provider_tasks() {
+    tlg::define_provider!(
+        PROV,
+        "TraceLoggingDynamicTest",
+        task_name(1, "Download"),
+        task_name(0x2001, "Upload_2"),
+        default_task(5),
+        default_opcode(Start),
+    );
+    let defaults = tli::provider_defaults(&PROV);
+    assert_eq!(5, defaults.task);
+    assert_eq!(tlg::Opcode::Start, defaults.opcode);
+    assert_eq!(1, defaults.task_from_name("Download"));
+    assert_eq!(0x2001, defaults.task_from_name("Upload_2"));
+
+    const TASK: u16 = 7;
+    tlg::define_provider!(PROV_EXPR, "TraceLoggingDynamicTest", default_task(TASK + 1));
+    assert_eq!(8, tli::provider_defaults(&PROV_EXPR).task);
+
+    let _u = Unregister(&PROV);
+    unsafe { PROV.register() };
+
+    // Inherit task and opcode from provider.
+    tlg::write_event!(PROV, "Inherit");
+
+    // Named tasks.
+    tlg::write_event!(PROV, "Download", task("Download"), u32("Bytes", &1));
+    tlg::write_event!(PROV, "Upload", task("Upload_2"), opcode(Stop));
+
+    // Numeric task still works.
+    tlg::write_event!(PROV, "Numeric", task(3));
+}
+
+tlg::keywords! {
+    /// Test keywords.
+    pub struct TestKeywords {
+        NETWORK,
+        STORAGE,
+        /// User interface.
+        UI,
+    }
+}
+
+#[test]
+fn keywords() {
+    assert_eq!(0x1, TestKeywords::NETWORK);
+    assert_eq!(0x2, TestKeywords::STORAGE);
+    assert_eq!(0x4, TestKeywords::UI);
+
+    tlg::keywords! {
+        struct ManyKeywords { K0, K1, K2, K3, K4, K5, K6, K7, K8, K9, K10, K11, K12, K13,
+            K14, K15, K16, K17, K18, K19, K20, K21, K22, K23, K24, K25, K26, K27, K28, K29,
+            K30, K31, K32, K33, K34, K35, K36, K37, K38, K39, K40, K41, K42, K43, K44, K45,
+            K46, K47, K48, K49, K50, K51, K52, K53, K54, K55, K56, K57, K58, K59, K60, K61,
+            K62, K63 }
+    }
+    assert_eq!(0x1, ManyKeywords::K0);
+    assert_eq!(0x100000000, ManyKeywords::K32);
+    assert_eq!(0x8000000000000000, ManyKeywords::K63);
+
+    tlg::define_provider!(
+        PROV,
+        "TraceLoggingDynamicTest",
+        default_keyword(TestKeywords::STORAGE),
+    );
+    assert_eq!(0x2, tli::provider_defaults(&PROV).keyword);
+
+    let _u = Unregister(&PROV);
+    unsafe { PROV.register() };
+
+    // Named keywords are OR'ed together, same as integer keywords.
+    const NETWORK_STORAGE: u64 = TestKeywords::NETWORK | TestKeywords::STORAGE;
+    assert_eq!(0x3, NETWORK_STORAGE);
+    assert_eq!(
+        PROV.enabled(tlg::Level::Verbose, NETWORK_STORAGE),
+        tlg::provider_enabled!(
+            PROV,
+            tlg::Level::Verbose,
+            TestKeywords::NETWORK | TestKeywords::STORAGE
+        )
+    );
+    tlg::write_event!(PROV, "Named", keyword(TestKeywords::NETWORK));
+    tlg::write_event!(
+        PROV,
+        "NamedTwice",
+        keyword(TestKeywords::NETWORK),
+        keyword(TestKeywords::STORAGE),
+    );
+    tlg::write_event!(
+        PROV,
+        "NamedAndInteger",
+        keyword(TestKeywords::NETWORK),
+        keyword(0x10),
+    );
+}
+
+/// Flags type with the same const API as a type declared by the `bitflags` crate.
+#[derive(Clone, Copy)]
+struct FlagKeywords(u64);
+
+impl FlagKeywords {
+    const NETWORK: Self = Self(0x1);
+    const STORAGE: Self = Self(0x2);
+
+    const fn bits(self) -> u64 {
+        return self.0;
+    }
+
+    const fn union(self, other: Self) -> Self {
+        return Self(self.0 | other.0);
+    }
+}
+
+#[test]
+fn keyword_flags_type() {
+    tlg::define_provider!(
+        PROV,
+        "TraceLoggingDynamicTest",
+        default_keyword(FlagKeywords::STORAGE.bits()),
+    );
+    assert_eq!(0x2, tli::provider_defaults(&PROV).keyword);
+
+    let _u = Unregister(&PROV);
+    unsafe { PROV.register() };
+
+    // Flags values are converted to u64 via const fn, since keywords are constants.
+    tlg::write_event!(PROV, "Flags", keyword(FlagKeywords::NETWORK.bits()));
+    tlg::write_event!(
+        PROV,
+        "FlagsUnion",
+        keyword(FlagKeywords::NETWORK.union(FlagKeywords::STORAGE).bits()),
+    );
+    tlg::write_event!(
+        PROV,
+        "FlagsTwice",
+        keyword(FlagKeywords::NETWORK.bits()),
+        keyword(FlagKeywords::STORAGE.bits()),
+    );
+}
+
+#[test]
+fn derive_event_ids() {
+    // FNV-1a, folded to 16 bits.
+    assert_eq!(0x1CD9, tlg::event_id_from_name(""));
+    assert_eq!(0xCD20, tlg::event_id_from_name("a"));
+    assert_eq!(0x7D39, tlg::event_id_from_name("Event1"));
+    assert_eq!(0x7C44, tlg::event_id_from_name("Event2"));
+
+    // Stable for the same name, different for different names.
+    const ID1: u16 = tlg::event_id_from_name("Event1");
+    assert_eq!(ID1, tlg::event_id_from_name("Event1"));
+    assert_ne!(ID1, tlg::event_id_from_name("Event2"));
+    assert_ne!(0, tlg::event_id_from_name("MyEvent"));
+
+    tlg::define_provider!(PROV, "TraceLoggingDynamicTest", derive_event_ids());
+    assert!(tli::provider_defaults(&PROV).derive_event_ids);
+
+    let _u = Unregister(&PROV);
+    unsafe { PROV.register() };
+
+    // Derived id.
+    tlg::write_event!(PROV, "Event1");
+    tlg::write_event!(PROV, "Event2", u32("field", &2));
+
+    // Explicit id_version takes precedence.
+    tlg::write_event!(PROV, "Event3", id_version(3, 1));
+}
+
+#[cfg(feature = "disabled")]
+#[test]
+fn disabled() {
+    tlg::define_provider!(
+        PROV,
+        "TraceLoggingDynamicTest",
+        group_id("e3c21ff5-164e-4cbb-b08c-d47e2dcf9197"),
+        trait(2, b"data"),
+    );
+
+    // No provider name or traits: metadata is just size + NUL.
+    assert_eq!(PROV.raw_meta(), b"\x03\0\0");
+    assert_eq!(PROV.name(), "");
+
+    let _u = Unregister(&PROV);
+    unsafe { PROV.register() };
+    assert!(!PROV.enabled(tlg::Level::LogAlways, 0));
+
+    // Field values are type-checked but never evaluated.
+    let value = 5u32;
+    fn not_evaluated<T>() -> T {
+        panic!("field value evaluated in disabled mode");
+    }
+    assert_eq!(
+        0,
+        tlg::write_event!(
+            PROV,
+            "Disabled",
+            level(Critical),
+            keyword(0x10),
+            tag(0x1234),
+            activity_id(&not_evaluated::<tlg::Guid>()),
+            u32("value", &value),
+            u32("panic", &not_evaluated::<u32>()),
+            str8("str", not_evaluated::<&str>()),
+            str8_chunked("chunked", not_evaluated::<&str>()),
+        )
+    );
+
+    // Provider and descriptor expressions are type-checked but never evaluated.
+    let mut evaluated = 0;
+    let mut get_provider = || {
+        evaluated += 1;
+        return &PROV;
+    };
     assert_eq!(
-        tlg::win_filetime_from_systemtime!(epoch + d100),
-        tli::filetime_from_duration_after_1970(d100)
+        0,
+        tlg::write_event!(get_provider(), "ProviderExpr", u32("value", &value))
     );
     assert_eq!(
-        tlg::win_filetime_from_systemtime!(epoch - d100),
-        tli::filetime_from_duration_before_1970(d100)
+        0,
+        tlg::write_event!(
+            PROV,
+            "DescriptorExpr",
+            descriptor(&not_evaluated::<tli::EventDescriptor>()),
+            u32("value", &value),
+        )
     );
+    assert_eq!(0, evaluated);
 }
 
 #[test]
-fn filetime_from_duration_after_1970() {
-    use core::time::Duration;
-    use tli::filetime_from_duration_after_1970 as ft_from_dur_after;
-    const FT_PER_SEC: i64 = 10000000;
-    let ft_1970 = 0x19DB1DED53E8000;
-    let ft_30828_2 = 0x7FFF35F4F06C8000 - FT_PER_SEC * 2;
-    let n000 = Duration::ZERO;
-    let n001 = Duration::from_nanos(1);
-    let n099 = Duration::from_nanos(99);
-    let pos_max_2 = Duration::from_secs(0xD408314C00 - 2); // time between 1970 and 30828-2s
+fn provider_register_threads() {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::thread;
 
-    // Adding to 1970
-
-    // Test each second starting 2 seconds before boundary to 2 seconds after.
-    for seconds in 0..4 {
-        let dur_s = Duration::from_secs(seconds);
-        let ft_s = (10000000 * seconds) as i64;
-
-        // Test each tick starting 2 ticks before second to 2 ticks after.
-        for ticks in 0..4 {
-            let dur_ticks = Duration::from_nanos(ticks * 100);
-            let ft_ticks = ticks as i64;
+    tlg::define_provider!(PROV, "TraceLoggingDynamicTest");
+    let _u = Unregister(&PROV);
 
-            assert_eq!(
-                ft_clamp(ft_30828_2 + ft_s + ft_ticks),
-                ft_from_dur_after(pos_max_2 + dur_s + dur_ticks + n099)
-            );
-            assert_eq!(
-                ft_clamp(ft_30828_2 + ft_s + ft_ticks),
-                ft_from_dur_after(pos_max_2 + dur_s + dur_ticks + n001)
-            );
-            assert_eq!(
-                ft_clamp(ft_30828_2 + ft_s + ft_ticks),
-                ft_from_dur_after(pos_max_2 + dur_s + dur_ticks + n000)
-            );
+    // Racing calls to register: exactly one registers the provider, the others panic.
+    let results: Vec<thread::Result<u32>> = (0..8)
+        .map(|_| thread::spawn(|| unsafe { PROV.register() }))
+        .collect::<Vec<_>>()
+        .into_iter()
+        .map(|handle| handle.join())
+        .collect();
+    let succeeded = results.iter().filter(|result| result.is_ok()).count();
+    if cfg!(windows) {
+        assert_eq!(1, succeeded);
+        assert!(PROV.is_registered());
+    } else {
+        // Registration is a no-op without ETW.
+        assert_eq!(8, succeeded);
+        assert!(!PROV.is_registered());
+    }
 
-            assert_eq!(
-                ft_clamp(ft_1970 + ft_s + ft_ticks),
-                ft_from_dur_after(dur_s + dur_ticks + n099)
-            );
-            assert_eq!(
-                ft_clamp(ft_1970 + ft_s + ft_ticks),
-                ft_from_dur_after(dur_s + dur_ticks + n001)
-            );
-            assert_eq!(
-                ft_clamp(ft_1970 + ft_s + ft_ticks),
-                ft_from_dur_after(dur_s + dur_ticks + n000)
-            );
-        }
+    // Concurrent writes while the provider is repeatedly unregistered and registered.
+    static STOP: AtomicBool = AtomicBool::new(false);
+    let writers: Vec<thread::JoinHandle<()>> = (0..8)
+        .map(|n| {
+            thread::spawn(move || {
+                while !STOP.load(Ordering::Relaxed) {
+                    tlg::write_event!(PROV, "Concurrent", u32("thread", &n));
+                }
+            })
+        })
+        .collect();
+    for _ in 0..100 {
+        PROV.unregister();
+        assert!(!PROV.is_registered());
+        unsafe { PROV.register() };
+    }
+    STOP.store(true, Ordering::Relaxed);
+    for writer in writers {
+        writer.join().unwrap();
     }
 }
 
 #[test]
-fn filetime_from_duration_before_1970() {
-    use core::time::Duration;
-    use tli::filetime_from_duration_before_1970 as ft_from_dur_before;
-    const FT_PER_SEC: i64 = 10000000;
-    let ft_1601_2 = FT_PER_SEC * 2;
-    let ft_1970 = 0x19DB1DED53E8000;
-    let n000 = Duration::ZERO;
-    let n001 = Duration::from_nanos(1);
-    let n099 = Duration::from_nanos(99);
-    let neg_max_2 = Duration::from_secs(0x2B6109100 - 2); // time between 1601+2s and 1970
+fn provider_register_cycle() {
+    tlg::define_provider!(PROV, "TraceLoggingDynamicTest");
+    let _u = Unregister(&PROV);
+    let etw = matches!(
+        tlg::NATIVE_IMPLEMENTATION,
+        tlg::NativeImplementation::Windows
+    );
 
-    // Subtracting from 1970
+    assert!(!PROV.is_registered());
+    assert_eq!(0, tlg::write_event!(PROV, "Unregistered"));
 
-    // Test each second starting 2 seconds before boundary to 2 seconds after.
-    for seconds in 0..4 {
-        let dur_s = Duration::from_secs(seconds);
-        let ft_s = (10000000 * seconds) as i64;
+    assert_eq!(0, unsafe { PROV.register() });
+    assert_eq!(etw, PROV.is_registered());
+    tlg::write_event!(PROV, "Registered1");
 
-        // Test each tick starting 2 ticks before second to 2 ticks after.
-        for ticks in 0..4 {
-            let dur_ticks = Duration::from_nanos(ticks * 100);
-            let ft_ticks = ticks as i64;
+    assert_eq!(0, PROV.unregister());
+    assert!(!PROV.is_registered());
+    assert_eq!(always_enabled(), PROV.enabled(tlg::Level::LogAlways, 0));
+    assert_eq!(0, tlg::write_event!(PROV, "Unregistered"));
+    assert_eq!(0, PROV.unregister());
 
-            // Test rounding down at T-0.99, T-0.01, and T-0.00.
-            assert_eq!(
-                ft_clamp(ft_1601_2 - ft_s - ft_ticks - 1),
-                ft_from_dur_before(neg_max_2 + dur_s + dur_ticks + n099)
-            );
-            assert_eq!(
-                ft_clamp(ft_1601_2 - ft_s - ft_ticks - 1),
-                ft_from_dur_before(neg_max_2 + dur_s + dur_ticks + n001)
-            );
-            assert_eq!(
-                ft_clamp(ft_1601_2 - ft_s - ft_ticks),
-                ft_from_dur_before(neg_max_2 + dur_s + dur_ticks + n000)
-            );
+    assert_eq!(0, unsafe { PROV.register() });
+    assert_eq!(etw, PROV.is_registered());
+    tlg::write_event!(PROV, "Registered2");
 
-            assert_eq!(
-                ft_clamp(ft_1970 - ft_s - ft_ticks - 1),
-                ft_from_dur_before(dur_s + dur_ticks + n099)
-            );
-            assert_eq!(
-                ft_clamp(ft_1970 - ft_s - ft_ticks - 1),
-                ft_from_dur_before(dur_s + dur_ticks + n001)
-            );
-            assert_eq!(
-                ft_clamp(ft_1970 - ft_s - ft_ticks),
-                ft_from_dur_before(dur_s + dur_ticks + n000)
-            );
-        }
-    }
+    PROV.unregister();
+    assert!(!PROV.is_registered());
 }
 
-fn ft_clamp(ft: i64) -> i64 {
-    let ft_1601 = 0;
-    let ft_30828 = 0x7FFF35F4F06C8000;
-    return if ft < ft_1601 {
-        0
-    } else if ft >= ft_30828 {
-        ft_30828 - 1
-    } else {
-        ft
-    };
+#[test]
+fn registration_status() {
+    tlg::define_provider!(PROV, "TraceLoggingDynamicTest");
+    let _u = Unregister(&PROV);
+
+    // Never registered.
+    assert_eq!(0, PROV.registration_status());
+
+    // Status is the result of the most recent register call and is kept after
+    // unregister.
+    let result = unsafe { PROV.register() };
+    assert_eq!(result, PROV.registration_status());
+    PROV.unregister();
+    assert_eq!(result, PROV.registration_status());
 }
 
-struct Unregister(&'static tlg::Provider);
+#[test]
+fn provider_multiple_registrations() {
+    // Two registrations of the same provider are independent.
+    tlg::define_provider!(PROV_A, "TraceLoggingDynamicTest");
+    tlg::define_provider!(PROV_B, "TraceLoggingDynamicTest");
+    let _ua = Unregister(&PROV_A);
+    let _ub = Unregister(&PROV_B);
+    let etw = matches!(
+        tlg::NATIVE_IMPLEMENTATION,
+        tlg::NativeImplementation::Windows
+    );
 
-impl Drop for Unregister {
-    fn drop(&mut self) {
-        self.0.unregister();
-    }
+    assert_eq!(PROV_A.id(), PROV_B.id());
+    assert_eq!(0, unsafe { PROV_A.register() });
+    assert_eq!(etw, PROV_A.is_registered());
+    assert!(!PROV_B.is_registered());
+    assert_eq!(0, tlg::write_event!(PROV_B, "Unregistered"));
+    tlg::write_event!(PROV_A, "A");
+
+    assert_eq!(0, unsafe { PROV_B.register() });
+    assert_eq!(etw, PROV_B.is_registered());
+    tlg::write_event!(PROV_B, "B");
+
+    PROV_A.unregister();
+    assert!(!PROV_A.is_registered());
+    assert_eq!(etw, PROV_B.is_registered());
+    assert_eq!(0, tlg::write_event!(PROV_A, "Unregistered"));
+    tlg::write_event!(PROV_B, "B");
 }
 
+#[cfg(feature = "testing")]
 #[test]
-fn define_provider() {
+fn testing_always_enabled() {
     tlg::define_provider!(PROV, "TraceLoggingDynamicTest");
-
-    fn callback(
-        _s: &tlg::Guid,
-        _c: u32,
-        _l: tlg::Level,
-        _anyk: u64,
-        _allk: u64,
-        _flt: usize,
-        ctx: usize,
-    ) {
-        assert_eq!(12345usize, ctx);
+    assert_eq!(always_enabled(), PROV.enabled(tlg::Level::Verbose, 0x1234));
+    if !always_enabled() {
+        return; // Using ETW or disabled.
     }
 
-    let _u = Unregister(&PROV);
-    assert!(!PROV.enabled(tlg::Level::LogAlways, 0));
-    PROV.unregister();
-    assert!(!PROV.enabled(tlg::Level::LogAlways, 0));
-    unsafe { PROV.register() };
-    PROV.unregister();
-    assert!(!PROV.enabled(tlg::Level::LogAlways, 0));
-    PROV.unregister();
-    assert!(!PROV.enabled(tlg::Level::LogAlways, 0));
-    unsafe { PROV.register_with_callback(callback, 12345usize) };
-    PROV.unregister();
-    assert!(!PROV.enabled(tlg::Level::LogAlways, 0));
-    PROV.unregister();
-    assert!(!PROV.enabled(tlg::Level::LogAlways, 0));
-    PROV.raw_meta();
-
-    tlg::define_provider!(PROV1, "TestProvider1");
-    assert_eq!("TestProvider1", PROV1.name());
-    assert_eq!(&tlg::Guid::from_name("TestProvider1"), PROV1.id());
-
-    tlg::define_provider!(
-        PROV2,
-        "TestProvider2",
-        group_id("e3c21ff5-164e-4cbb-b08c-d47e2dcf9197")
-    );
-    assert_eq!("TestProvider2", PROV2.name());
-    assert_eq!(&tlg::Guid::from_name("TestProvider2"), PROV2.id());
-
-    tlg::define_provider!(
-        PROV3,
-        "TestProvider3",
-        id("d93270c9-8144-4209-99a6-3acd39aaae71")
-    );
-    assert_eq!("TestProvider3", PROV3.name());
+    // Field values are evaluated, and the field data is built, even though the
+    // provider is not registered and nothing is written.
+    let mut map = std::collections::BTreeMap::new();
+    map.insert("key", "value");
+    let mut evaluated = 0;
+    let mut captured = Vec::new();
+    let mut value = || {
+        evaluated += 1;
+        return 5u32;
+    };
     assert_eq!(
-        &tlg::Guid::from_u128(&0xd93270c9_8144_4209_99a6_3acd39aaae71),
-        PROV3.id()
+        0,
+        tlg::write_event!(
+            PROV,
+            "Testing",
+            level(Verbose),
+            u32("value", &value()),
+            map_str8(
+                "map",
+                map.iter().inspect(|(k, v)| captured.push((**k, **v)))
+            ),
+        )
     );
+    assert_eq!(1, evaluated);
+    assert_eq!(vec![("key", "value")], captured);
+}
 
-    tlg::define_provider!(
-        PROV4,
-        "TestProvider4",
-        id("632a8743-6a0d-456f-9ae8-a26febe2dbc3"),
-        group_id("e3c21ff5-164e-4cbb-b08c-d47e2dcf9197")
-    );
-    assert_eq!("TestProvider4", PROV4.name());
-    assert_eq!(
-        &tlg::Guid::from_u128(&0x632a8743_6a0d_456f_9ae8_a26febe2dbc3),
-        PROV4.id()
-    );
+#[test]
+fn keyword_zero() {
+    // Keyword 0 matches every keyword filter, so a keyword(0) event is not compiled
+    // out: it is enabled whenever the provider is enabled at the event's level.
+    tlg::define_provider!(PROV, "TraceLoggingDynamicTest");
+    assert_eq!(always_enabled(), PROV.enabled(tlg::Level::Verbose, 0));
+    assert_eq!(always_enabled(), PROV.enabled(tlg::Level::Verbose, 0x1));
+
+    let mut evaluated = 0;
+    let mut value = || {
+        evaluated += 1;
+        return 5u32;
+    };
+    tlg::write_event!(PROV, "KeywordZero", keyword(0), u32("value", &value()));
+    tlg::write_event!(PROV, "KeywordOne", keyword(0x1), u32("value", &value()));
+    assert_eq!(if always_enabled() { 2 } else { 0 }, evaluated);
+
+    // Events can be removed from a build with cfg on the statement.
+    #[cfg(any())]
+    tlg::write_event!(PROV, "Removed", u32("value", &value()));
 }
 
 #[test]
@@ -408,6 +3267,18 @@ fn write_event() {
     tlg::write_event!(PROV, "tag0xFEDC000", tag(0xFEDC000));
     tlg::write_event!(PROV, "tag0xFEDCBAF", tag(0xFEDCBAF));
 
+    // Tags may be any constant expression, including a const fn call.
+    const fn make_tag(category: u32, id: u32) -> u32 {
+        return (category << 16) | id;
+    }
+    const TAG_CATEGORY: u32 = 0x12;
+    tlg::write_event!(
+        PROV,
+        "tagConstFn",
+        tag(make_tag(TAG_CATEGORY, 1)),
+        u8("field", &1, tag(make_tag(TAG_CATEGORY, 2))),
+    );
+
     tlg::write_event!(
         PROV,
         "fieldtag",
@@ -438,6 +3309,19 @@ fn write_event() {
         }),
     );
 
+    tlg::write_event!(PROV, "nested-same-name",
+        u8("count", &0),
+        struct("struct1", {
+            u8("count", &1),
+            struct("struct2", {
+                u8("count", &2),
+            }),
+        }),
+        struct("struct2", {
+            u8("count", &3),
+        }),
+    );
+
     tlg::write_event!(
         PROV,
         "cstrs-L4-kFF",
@@ -462,7 +3346,7 @@ fn write_event() {
         cstr8_cp1252("cstr8e-a0", "a\0"),
         cstr8_cp1252("cstr8e-0a", "\0a"),
         cstr8_cp1252("cstr8e-a0a", "a\0a"),
-        char8_cp1252("A", &b'A'),
+        char8_cp1252("Z", &b'Z'),
     );
 
     tlg::write_event!(
@@ -470,7 +3354,7 @@ fn write_event() {
         "UnicodeString",
         char8_cp1252("A", &b'A'),
         cstr16("scalar", &Vec::from_iter("cstr-utf16".encode_utf16())),
-        char8_cp1252("A", &b'A'),
+        char8_cp1252("Z", &b'Z'),
     );
 
     tlg::write_event!(
@@ -478,7 +3362,7 @@ fn write_event() {
         "AnsiString",
         char8_cp1252("A", &b'A'),
         cstr8("scalar", "cstr-utf8"),
-        char8_cp1252("A", &b'A'),
+        char8_cp1252("Z", &b'Z'),
     );
 
     tlg::write_event!(
@@ -489,7 +3373,7 @@ fn write_event() {
         i8_slice("a0", &[]),
         i8_slice("a1", &[-8]),
         i8_slice("a2", &[-8, -8]),
-        char8_cp1252("A", &b'A'),
+        char8_cp1252("Z", &b'Z'),
     );
 
     tlg::write_event!(
@@ -500,7 +3384,7 @@ fn write_event() {
         u8_slice("a0", &[]),
         u8_slice("a1", &[8]),
         u8_slice("a2", &[8, 8]),
-        char8_cp1252("A", &b'A'),
+        char8_cp1252("Z", &b'Z'),
     );
 
     tlg::write_event!(
@@ -511,7 +3395,7 @@ fn write_event() {
         i16_slice("a0", &[]),
         i16_slice("a1", &[-16]),
         i16_slice("a2", &[-16, -16]),
-        char8_cp1252("A", &b'A'),
+        char8_cp1252("Z", &b'Z'),
     );
 
     tlg::write_event!(
@@ -522,7 +3406,7 @@ fn write_event() {
         u16_slice("a0", &[]),
         u16_slice("a1", &[16]),
         u16_slice("a2", &[16, 16]),
-        char8_cp1252("A", &b'A'),
+        char8_cp1252("Z", &b'Z'),
     );
 
     tlg::write_event!(
@@ -533,7 +3417,7 @@ fn write_event() {
         i32_slice("a0", &[]),
         i32_slice("a1", &[-32]),
         i32_slice("a2", &[-32, -32]),
-        char8_cp1252("A", &b'A'),
+        char8_cp1252("Z", &b'Z'),
     );
 
     tlg::write_event!(
@@ -544,7 +3428,7 @@ fn write_event() {
         u32_slice("a0", &[]),
         u32_slice("a1", &[32]),
         u32_slice("a2", &[32, 32]),
-        char8_cp1252("A", &b'A'),
+        char8_cp1252("Z", &b'Z'),
     );
 
     tlg::write_event!(
@@ -555,7 +3439,7 @@ fn write_event() {
         i64_slice("a0", &[]),
         i64_slice("a1", &[-64]),
         i64_slice("a2", &[-64, -64]),
-        char8_cp1252("A", &b'A'),
+        char8_cp1252("Z", &b'Z'),
     );
 
     tlg::write_event!(
@@ -566,7 +3450,7 @@ fn write_event() {
         u64_slice("a0", &[]),
         u64_slice("a1", &[64]),
         u64_slice("a2", &[64, 64]),
-        char8_cp1252("A", &b'A'),
+        char8_cp1252("Z", &b'Z'),
     );
 
     tlg::write_event!(
@@ -577,7 +3461,7 @@ fn write_event() {
         isize_slice("a0", &[]),
         isize_slice("a1", &[-3264]),
         isize_slice("a2", &[-3264, -3264]),
-        char8_cp1252("A", &b'A'),
+        char8_cp1252("Z", &b'Z'),
     );
 
     tlg::write_event!(
@@ -588,7 +3472,7 @@ fn write_event() {
         usize_slice("a0", &[]),
         usize_slice("a1", &[3264]),
         usize_slice("a2", &[3264, 3264]),
-        char8_cp1252("A", &b'A'),
+        char8_cp1252("Z", &b'Z'),
     );
 
     tlg::write_event!(
@@ -599,7 +3483,7 @@ fn write_event() {
         f32_slice("a0", &[]),
         f32_slice("a1", &[3.2]),
         f32_slice("a2", &[3.2, 3.2]),
-        char8_cp1252("A", &b'A'),
+        char8_cp1252("Z", &b'Z'),
     );
 
     tlg::write_event!(
@@ -610,7 +3494,7 @@ fn write_event() {
         f64_slice("a0", &[]),
         f64_slice("a1", &[6.4]),
         f64_slice("a2", &[6.4, 6.4]),
-        char8_cp1252("A", &b'A'),
+        char8_cp1252("Z", &b'Z'),
     );
 
     tlg::write_event!(
@@ -621,7 +3505,7 @@ fn write_event() {
         bool8_slice("a0", &[]),
         bool8_slice("a1", &[false]),
         bool8_slice("a2", &[false, false]),
-        char8_cp1252("A", &b'A'),
+        char8_cp1252("Z", &b'Z'),
     );
 
     tlg::write_event!(
@@ -632,7 +3516,7 @@ fn write_event() {
         bool8_slice("a0", &[]),
         bool8_slice("a1", &[true]),
         bool8_slice("a2", &[true, true]),
-        char8_cp1252("A", &b'A'),
+        char8_cp1252("Z", &b'Z'),
     );
 
     tlg::write_event!(
@@ -643,7 +3527,7 @@ fn write_event() {
         bool32_slice("a0", &[]),
         bool32_slice("a1", &[0]),
         bool32_slice("a2", &[0, 0]),
-        char8_cp1252("A", &b'A'),
+        char8_cp1252("Z", &b'Z'),
     );
 
     tlg::write_event!(
@@ -654,7 +3538,32 @@ fn write_event() {
         bool32_slice("a0", &[]),
         bool32_slice("a1", &[1]),
         bool32_slice("a2", &[1, 1]),
+        char8_cp1252("Z", &b'Z'),
+    );
+
+    tlg::write_event!(
+        PROV,
+        "Bool32Native",
+        char8_cp1252("A", &b'A'),
+        bool32_native("scalar", &true),
+        bool32_native_slice("a0", &[]),
+        bool32_native_slice("a1", &[true]),
+        bool32_native_slice("a2", &[false, true]),
+        bool32_native_slice("vec", &vec![true; 3]),
+        char8_cp1252("Z", &b'Z'),
+    );
+
+    tlg::write_event!(
+        PROV,
+        "Fmt",
         char8_cp1252("A", &b'A'),
+        display8(
+            "display",
+            &std::io::Error::from(std::io::ErrorKind::NotFound)
+        ),
+        debug8("debug", &Some(42)),
+        display8("str", "abc"),
+        char8_cp1252("Z", &b'Z'),
     );
 
     tlg::write_event!(
@@ -662,7 +3571,7 @@ fn write_event() {
         "Binary",
         char8_cp1252("A", &b'A'),
         binary("scalar", "0123"),
-        char8_cp1252("A", &b'A'),
+        char8_cp1252("Z", &b'Z'),
     );
 
     tlg::write_event!(
@@ -670,10 +3579,11 @@ fn write_event() {
         "Guid",
         char8_cp1252("A", &b'A'),
         guid("scalar", &sample_guid),
+        guid_bytes("bytes", &sample_guid.to_bytes_le()),
         guid_slice("a0", &[]),
         guid_slice("a1", &[sample_guid]),
         guid_slice("a2", &[sample_guid, sample_guid]),
-        char8_cp1252("A", &b'A'),
+        char8_cp1252("Z", &b'Z'),
     );
 
     tlg::write_event!(
@@ -684,7 +3594,7 @@ fn write_event() {
         win_filetime_slice("a0", &[]),
         win_filetime_slice("a1", &[sample_filetime]),
         win_filetime_slice("a2", &[sample_filetime, sample_filetime]),
-        char8_cp1252("A", &b'A'),
+        char8_cp1252("Z", &b'Z'),
     );
 
     tlg::write_event!(
@@ -692,7 +3602,7 @@ fn write_event() {
         "FileTime",
         char8_cp1252("A", &b'A'),
         systemtime("scalar", &sample_rusttime),
-        char8_cp1252("A", &b'A'),
+        char8_cp1252("Z", &b'Z'),
     );
 
     tlg::write_event!(
@@ -703,7 +3613,7 @@ fn write_event() {
         win_systemtime_slice("a0", &[]),
         win_systemtime_slice("a1", &[sample_systemtime]),
         win_systemtime_slice("a2", &[sample_systemtime, sample_systemtime]),
-        char8_cp1252("A", &b'A'),
+        char8_cp1252("Z", &b'Z'),
     );
 
     tlg::write_event!(
@@ -711,7 +3621,7 @@ fn write_event() {
         "Sid",
         char8_cp1252("A", &b'A'),
         win_sid("scalar", &sample_sid),
-        char8_cp1252("A", &b'A'),
+        char8_cp1252("Z", &b'Z'),
     );
 
     tlg::write_event!(
@@ -722,7 +3632,7 @@ fn write_event() {
         i32_hex_slice("a0", &[]),
         i32_hex_slice("a1", &[-559038737]),
         i32_hex_slice("a2", &[-559038737, -559038737]),
-        char8_cp1252("A", &b'A'),
+        char8_cp1252("Z", &b'Z'),
     );
 
     tlg::write_event!(
@@ -733,7 +3643,7 @@ fn write_event() {
         u32_hex_slice("a0", &[]),
         u32_hex_slice("a1", &[0xdeadbeef]),
         u32_hex_slice("a2", &[0xdeadbeef, 0xdeadbeef]),
-        char8_cp1252("A", &b'A'),
+        char8_cp1252("Z", &b'Z'),
     );
 
     tlg::write_event!(
@@ -744,7 +3654,7 @@ fn write_event() {
         i64_hex_slice("a0", &[]),
         i64_hex_slice("a1", &[-2401053088335073280]),
         i64_hex_slice("a2", &[-2401053088335073280, -2401053088335073280]),
-        char8_cp1252("A", &b'A'),
+        char8_cp1252("Z", &b'Z'),
     );
 
     tlg::write_event!(
@@ -755,7 +3665,7 @@ fn write_event() {
         u64_hex_slice("a0", &[]),
         u64_hex_slice("a1", &[0xdeadbeeffeeef000]),
         u64_hex_slice("a2", &[0xdeadbeeffeeef000, 0xdeadbeeffeeef000]),
-        char8_cp1252("A", &b'A'),
+        char8_cp1252("Z", &b'Z'),
     );
 
     tlg::write_event!(
@@ -766,7 +3676,7 @@ fn write_event() {
         isize_hex_slice("a0", &[]),
         isize_hex_slice("a1", &[0x1234]),
         isize_hex_slice("a2", &[0x1234, 0x1234]),
-        char8_cp1252("A", &b'A'),
+        char8_cp1252("Z", &b'Z'),
     );
 
     tlg::write_event!(
@@ -777,7 +3687,7 @@ fn write_event() {
         usize_hex_slice("a0", &[]),
         usize_hex_slice("a1", &[0x1234]),
         usize_hex_slice("a2", &[0x1234, 0x1234]),
-        char8_cp1252("A", &b'A'),
+        char8_cp1252("Z", &b'Z'),
     );
 
     tlg::write_event!(
@@ -785,7 +3695,18 @@ fn write_event() {
         "CountedString",
         char8_cp1252("A", &b'A'),
         str16("scalar", &Vec::from_iter("utf16".encode_utf16())),
+        char8_cp1252("Z", &b'Z'),
+    );
+
+    let big = "0123456789".repeat(20 * 1024);
+    tlg::write_event!(
+        PROV,
+        "str8_chunked",
         char8_cp1252("A", &b'A'),
+        str8_chunked("small", "utf8"),
+        str8_chunked("big", &big),
+        str8_chunked("tagged", big.as_bytes(), format(Json), tag(0x1234)),
+        char8_cp1252("Z", &b'Z'),
     );
 
     tlg::write_event!(
@@ -793,7 +3714,7 @@ fn write_event() {
         "CountedAnsiString",
         char8_cp1252("A", &b'A'),
         str8("scalar", "utf8"),
-        char8_cp1252("A", &b'A'),
+        char8_cp1252("Z", &b'Z'),
     );
 
     tlg::write_event!(
@@ -801,7 +3722,7 @@ fn write_event() {
         "BinaryC",
         char8_cp1252("A", &b'A'),
         binaryc("scalar", "0123"),
-        char8_cp1252("A", &b'A'),
+        char8_cp1252("Z", &b'Z'),
     );
 
     tlg::write_event!(
@@ -812,7 +3733,7 @@ fn write_event() {
         ipv4_slice("a0", &[]),
         ipv4_slice("a1", &[sample_ipv4]),
         ipv4_slice("a2", &[sample_ipv4, sample_ipv4]),
-        char8_cp1252("A", &b'A'),
+        char8_cp1252("Z", &b'Z'),
     );
 
     tlg::write_event!(
@@ -820,7 +3741,7 @@ fn write_event() {
         "IPv6",
         char8_cp1252("A", &b'A'),
         ipv6("scalar", &sample_ipv6),
-        char8_cp1252("A", &b'A'),
+        char8_cp1252("Z", &b'Z'),
     );
 
     tlg::write_event!(
@@ -828,6 +3749,6 @@ fn write_event() {
         "IPv6c",
         char8_cp1252("A", &b'A'),
         ipv6c("scalar", &sample_ipv6),
-        char8_cp1252("A", &b'A'),
+        char8_cp1252("Z", &b'Z'),
     );
 }
@@ -3,6 +3,7 @@
 
 use alloc::vec::Vec;
 use core::mem::size_of;
+use core::mem::size_of_val;
 use core::ptr::copy_nonoverlapping;
 
 use tracelogging::Channel;
@@ -1344,7 +1345,7 @@ impl EventBuilder {
     /// the TraceLogging encoding system. If done incorrectly, the resulting events will not
     /// decode properly.
     pub fn raw_add_data_slice<T: Copy>(&mut self, value: &[T]) -> &mut Self {
-        let value_size = value.len() * size_of::<T>();
+        let value_size = size_of_val(value);
         let old_data_size = self.data.len();
         self.data.reserve(value_size);
         unsafe {
@@ -3,6 +3,17 @@
 #[allow(unused_imports)]
 use crate::*; // For docs
 
+/// # v1.3.0 (Unreleased)
+/// - Add [`Provider::is_registered`]. [`EventBuilder::write`] via an unregistered
+///   provider is now always a no-op that returns 0.
+/// - Add [`Provider::registration_status`], which returns the result of the most
+///   recent call to [`Provider::register`].
+/// - Document that dropping a [`Provider`] unregisters it and that a provider is
+///   never unregistered twice.
+/// - Add [`Provider::with_activity`], which sets the thread-local activity id for the
+///   duration of a closure and restores the previous value afterwards.
+pub mod v1_3_0 {}
+
 /// # v1.2.1 (2023-06-29)
 /// - Guid: Fix `as_bytes_raw()` method and `borrow<[u8; 16]>` trait.
 pub mod v1_2_1 {}
@@ -39,6 +39,15 @@ use crate::EventBuilder;
 /// - The provider will automatically unregister when it is dropped. You can manually call
 ///   `unregister()` if you want to unregister sooner or if the provider is static.
 ///
+/// # Lifetime
+///
+/// Unlike the `'static` providers created by `tracelogging::define_provider!`, which
+/// are never dropped, a dynamic `Provider` owns its registration: dropping a
+/// registered provider calls `EventUnregister`. Unregistration happens at most once
+/// per registration. After the first [Provider::unregister()] (explicit or from
+/// `drop`) the provider holds no registration handle, so later calls, including the
+/// one made by `drop`, are no-ops that return 0.
+///
 /// # Pinning your provider
 ///
 /// Since the provider manages an asynchronous ETW callback, it must be pinned before you
@@ -126,6 +135,33 @@ impl Provider {
         return activity_id;
     }
 
+    /// Sets the current thread's thread-local activity id to `activity_id`, calls
+    /// `f(activity_id)`, then restores the previous thread-local activity id. The
+    /// previous value is restored even if `f` panics. Returns the result of `f`.
+    ///
+    /// Events written inside `f` by [EventBuilder::write] with an `activity_id` of `None`
+    /// use `activity_id` as their activity id, so a group of events can share an activity
+    /// without passing the id to each write.
+    ///
+    /// ```
+    /// # use tracelogging_dynamic as tld;
+    /// let activity_id = tld::Provider::create_activity_id();
+    /// tld::Provider::with_activity(&activity_id, |_activity_id| {
+    ///     // Events written here use activity_id unless they specify another one.
+    /// });
+    /// ```
+    pub fn with_activity<R>(activity_id: &Guid, f: impl FnOnce(&Guid) -> R) -> R {
+        struct RestoreActivityId(Guid);
+        impl Drop for RestoreActivityId {
+            fn drop(&mut self) {
+                Provider::set_current_thread_activity_id(&self.0);
+            }
+        }
+
+        let _restore = RestoreActivityId(Provider::set_current_thread_activity_id(activity_id));
+        return f(activity_id);
+    }
+
     /// Generates and returns a new 128-bit value suitable for use as an activity id.
     /// (Calls
     /// [EventActivityIdControl](https://docs.microsoft.com/windows/win32/api/evntprov/nf-evntprov-eventactivityidcontrol)
@@ -273,6 +309,30 @@ impl Provider {
         return self.context.enabled(level, keyword);
     }
 
+    /// Returns true if this provider is currently registered, i.e. if the most recent
+    /// call to [Provider::register()] succeeded and has not been followed by a call to
+    /// [Provider::unregister()].
+    ///
+    /// Writing an event via an unregistered provider is a no-op that returns 0. A
+    /// provider may be registered again after it is unregistered. Each registration gets
+    /// a new ETW registration handle.
+    ///
+    /// Always returns false if the crate was not compiled for Windows ETW (see
+    /// [NATIVE_IMPLEMENTATION](crate::NATIVE_IMPLEMENTATION)).
+    pub const fn is_registered(&self) -> bool {
+        return self.context.is_registered();
+    }
+
+    /// Returns the result of the most recent call to [Provider::register()]: 0
+    /// for success or a Win32 error from `EventRegister` for failure. Returns 0 if the
+    /// provider has never been registered.
+    ///
+    /// If registration fails, the provider is never enabled and events are silently
+    /// dropped. Use this to diagnose a provider whose events never show up.
+    pub fn registration_status(&self) -> u32 {
+        return self.context.registration_status();
+    }
+
     /// If this provider is not registered, does nothing and returns 0.
     /// Otherwise, unregisters the provider.
     ///
@@ -282,7 +342,9 @@ impl Provider {
     ///
     /// Use `provider.unregister()` if you want to unregister the provider before it goes
     /// out of scope. The provider automatically unregisters when it is dropped so most
-    /// users do  not need to call `unregister` directly.
+    /// users do  not need to call `unregister` directly. Calling `unregister` on a
+    /// provider that has already been unregistered is a no-op, so the provider is never
+    /// unregistered twice.
     pub fn unregister(&self) -> u32 {
         return self.context.unregister();
     }
@@ -37,6 +37,16 @@ fn provider() {
     assert_eq!(new_aid2, aid2);
     assert_eq!(aid2, Provider::set_current_thread_activity_id(&aid0));
 
+    assert_eq!(
+        7,
+        Provider::with_activity(&new_aid1, |aid| {
+            assert_eq!(&new_aid1, aid);
+            assert_eq!(new_aid1, Provider::current_thread_activity_id());
+            7
+        })
+    );
+    assert_eq!(aid0, Provider::current_thread_activity_id());
+
     assert_eq!(Guid::from_name("Hello"), Provider::guid_from_name("Hello"));
 
     fn my_callback(
@@ -125,6 +135,94 @@ fn provider() {
         .write(&provider, Some(&aid), None);
 }
 
+#[test]
+fn provider_register_cycle() {
+    let etw = matches!(NATIVE_IMPLEMENTATION, NativeImplementation::Windows);
+    let provider = pin!(Provider::new(
+        "TraceLoggingDynamicTest",
+        &Provider::options()
+    ));
+    let mut b = EventBuilder::new();
+
+    assert!(!provider.is_registered());
+    assert_eq!(
+        0,
+        b.reset("Unregistered", Level::LogAlways, 0, 0)
+            .write(&provider, None, None)
+    );
+
+    assert_eq!(0, unsafe { provider.as_ref().register() });
+    assert_eq!(etw, provider.is_registered());
+
+    assert_eq!(0, provider.unregister());
+    assert!(!provider.is_registered());
+    assert!(!provider.enabled(Level::LogAlways, 0));
+    assert_eq!(
+        0,
+        b.reset("Unregistered", Level::LogAlways, 0, 0)
+            .write(&provider, None, None)
+    );
+
+    assert_eq!(0, unsafe { provider.as_ref().register() });
+    assert_eq!(etw, provider.is_registered());
+
+    provider.unregister();
+    assert!(!provider.is_registered());
+}
+
+#[test]
+fn registration_status() {
+    let provider = pin!(Provider::new(
+        "TraceLoggingDynamicTest",
+        &Provider::options()
+    ));
+
+    // Never registered.
+    assert_eq!(0, provider.registration_status());
+
+    // Status is the result of the most recent register call and is kept after
+    // unregister.
+    let result = unsafe { provider.as_ref().register() };
+    assert_eq!(result, provider.registration_status());
+    provider.unregister();
+    assert_eq!(result, provider.registration_status());
+}
+
+#[test]
+fn provider_unregister_on_drop() {
+    let etw = matches!(NATIVE_IMPLEMENTATION, NativeImplementation::Windows);
+
+    // Explicit unregister, then drop: drop must not unregister a second time.
+    let provider = Box::pin(Provider::new(
+        "TraceLoggingDynamicTest",
+        &Provider::options(),
+    ));
+    assert_eq!(0, unsafe { provider.as_ref().register() });
+    assert_eq!(etw, provider.is_registered());
+    assert_eq!(0, provider.unregister());
+    assert!(!provider.is_registered());
+    assert_eq!(0, provider.unregister()); // No-op.
+    assert!(!provider.is_registered());
+    drop(provider);
+
+    // Drop while registered: drop unregisters, so the same provider can be created
+    // and registered again.
+    let provider = Box::pin(Provider::new(
+        "TraceLoggingDynamicTest",
+        &Provider::options(),
+    ));
+    assert_eq!(0, unsafe { provider.as_ref().register() });
+    assert_eq!(etw, provider.is_registered());
+    drop(provider);
+
+    let provider = pin!(Provider::new(
+        "TraceLoggingDynamicTest",
+        &Provider::options()
+    ));
+    assert_eq!(0, unsafe { provider.as_ref().register() });
+    assert_eq!(etw, provider.is_registered());
+}
+
 #[test]
 fn builder() {
     let p = Provider::new("TraceLoggingDynamicTest", &Provider::options()); // Temporary that will be shadowed.
@@ -147,6 +147,38 @@ impl ToMarkdown for FieldOption {
             FieldStrategy::Time32 | FieldStrategy::Time64 => {
                 self.normal_field(&mut s, self.value_type, false, "time");
             }
+            FieldStrategy::SystemTimeChrono => {
+                // The value is a NaiveDateTime, not the [u16; 8] passed to ETW.
+                let mut option = *self;
+                option.value_array_count = 0;
+                option.normal_field(&mut s, &["chrono", "NaiveDateTime"], false, "chrono");
+            }
+            FieldStrategy::Bool32 | FieldStrategy::Bool32Slice => {
+                // The value is a bool, not the i32 passed to ETW.
+                let is_slice = self.strategy.is_slice();
+                self.normal_field(&mut s, strings::BOOL_PATH, is_slice, "bool32_native");
+            }
+            FieldStrategy::ByteOrder => {
+                // The value is an integer, not the bytes passed to ETW.
+                let type_path = if self.value_array_count == 4 {
+                    strings::U32_PATH
+                } else {
+                    strings::U64_PATH
+                };
+                let mut option = *self;
+                option.value_array_count = 0;
+                option.normal_field(&mut s, type_path, false, "byte_order");
+            }
+            FieldStrategy::F16 | FieldStrategy::F16Slice => {
+                // The value is a half::f16, not the f32 passed to ETW.
+                let is_slice = self.strategy.is_slice();
+                self.normal_field(&mut s, &["half", "f16"], is_slice, "f16");
+            }
+            FieldStrategy::Address | FieldStrategy::AddressSlice => {
+                // The value is a pointer or reference, not the usize passed to ETW.
+                let is_slice = self.strategy.is_slice();
+                self.normal_field(&mut s, &["T"], is_slice, "raw_pointer");
+            }
             FieldStrategy::Sid => {
                 self.normal_field(&mut s, self.value_type, true, "sid");
             }
@@ -161,9 +193,38 @@ impl ToMarkdown for FieldOption {
                 };
                 self.normal_field(&mut s, self.value_type, self.value_array_count == 0, note);
             }
+            FieldStrategy::CountedOpt => {
+                // The value is an Option of the slice, not a reference to it.
+                let mut row = String::new();
+                self.normal_field(&mut row, self.value_type, true, "opt");
+                s.push_str(
+                    &row.replacen(" | `&[", " | `Option<&[", 1)
+                        .replacen("]` | ", "]>` | ", 1),
+                );
+            }
+            FieldStrategy::Display => {
+                self.normal_field(&mut s, &["impl Display"], false, "fmt");
+            }
+            FieldStrategy::Debug => {
+                self.normal_field(&mut s, &["impl Debug"], false, "fmt");
+            }
+            FieldStrategy::Json => {
+                self.normal_field(&mut s, &["impl Serialize"], false, "json");
+            }
+            FieldStrategy::Utf16 => {
+                self.normal_field(&mut s, &["str"], false, "utf16");
+            }
+            FieldStrategy::Chunked => {
+                self.normal_field(&mut s, self.value_type, true, "chunked");
+            }
+            FieldStrategy::MapStr8 => {
+                self.normal_field(&mut s, &["impl IntoIterator"], false, "map");
+            }
             FieldStrategy::Struct
+            | FieldStrategy::StructTuple
             | FieldStrategy::RawStruct
             | FieldStrategy::RawStructSlice
+            | FieldStrategy::RawStructValue
             | FieldStrategy::RawData
             | FieldStrategy::RawField
             | FieldStrategy::RawFieldSlice
@@ -46,6 +46,7 @@ pub enum InType {
 }
 
 impl InType {
+    pub const ConstantCountFlag: u8 = 0x20;
     pub const VariableCountFlag: u8 = 0x40;
 
     pub const fn to_token(self) -> EnumToken {
@@ -6,13 +6,16 @@ use proc_macro::*;
 use crate::enums::{EnumToken, InType};
 use crate::expression::Expression;
 use crate::field_info::FieldInfo;
-use crate::field_option::{FieldOption, FieldStrategy};
+use crate::field_option::{chunk_name, FieldOption, FieldStrategy, CHUNKS_MAX};
 use crate::ident_builder::IdentBuilder;
 use crate::strings::*;
 use crate::tree::Tree;
 
 use crate::event_info::EventInfo;
 
+/// Events with more fields than this use an `#[inline(never)]` helper function.
+const INLINE_FIELDS_MAX: u16 = 16;
+
 pub struct EventGenerator {
     /// tokens for declaring the _TLG_TAGn constants.
     tags_tree: Tree,
@@ -24,12 +27,16 @@ pub struct EventGenerator {
     func_args_tree: Tree,
     /// tokens in the _tlg_write(...) function call.
     func_call_tree: Tree,
+    /// statements at the start of the _tlg_write(...) function body.
+    func_checks_tree: Tree,
     /// tokens in the _tlg_lengths = [...] array initializer.
     lengths_init_tree: Tree,
     /// tokens in the EventDataDescriptor &[...] array initializer.
     data_desc_init_tree: Tree,
     /// Code that runs if the provider is enabled.
     enabled_tree: Tree,
+    /// tokens in the _TLG_FIELDS &[...] array initializer (for write_event_fields).
+    fields_tree: Tree,
     /// scratch tree 1
     tree1: Tree,
     /// scratch tree 2
@@ -46,6 +53,8 @@ pub struct EventGenerator {
     field_count: u16,
     /// number of runtime lengths needed
     lengths_count: u16,
+    /// field_count of the most recent struct_tuple field (its _tlg_argN suffix)
+    tuple_field_index: u16,
 }
 
 impl EventGenerator {
@@ -56,9 +65,11 @@ impl EventGenerator {
             meta_init_tree: Tree::new(span),
             func_args_tree: Tree::new(span),
             func_call_tree: Tree::new(span),
+            func_checks_tree: Tree::new(span),
             lengths_init_tree: Tree::new(span),
             data_desc_init_tree: Tree::new(span),
             enabled_tree: Tree::new(span),
+            fields_tree: Tree::new(span),
             tree1: Tree::new(span),
             tree2: Tree::new(span),
             tree3: Tree::new(span),
@@ -67,6 +78,7 @@ impl EventGenerator {
             meta_buffer: Vec::with_capacity(128),
             field_count: 0,
             lengths_count: 0,
+            tuple_field_index: 0,
         };
     }
 
@@ -86,13 +98,15 @@ impl EventGenerator {
 
         // event tag
         self.tag_n.clear_suffix();
-        self.add_tag(event.tag);
+        self.add_tag(&event.tag);
 
         // event name
         self.meta_buffer.extend(event.name.as_bytes());
         self.meta_buffer.push(0);
 
-        // data descriptors for provider metadata and event metadata
+        // data descriptors for provider metadata and event metadata. These are always
+        // present, even if no field has data (e.g. an event with only raw_meta fields),
+        // so the write always has at least these 2 descriptors.
         const EVENT_DATA_DESCRIPTOR_TYPE_PROVIDER_METADATA: u32 = 2;
         const EVENT_DATA_DESCRIPTOR_TYPE_EVENT_METADATA: u32 = 1;
         self.data_desc_init_tree
@@ -126,81 +140,132 @@ impl EventGenerator {
             )
             .add_punct(",");
 
+        // If the "disabled" feature is enabled, the event does not generate metadata or
+        // a descriptor. The helper becomes a closure that is never called. It only
+        // type-checks the field values.
+        let disabled = cfg!(feature = "disabled");
+
+        // If the event has a descriptor option, the descriptor is a runtime value.
+        let has_descriptor = !event.descriptor.is_empty();
+
+        // For a provider symbol, EventInfo fills in the values the event does not
+        // specify from the provider's defaults constant. For a provider expression, they
+        // are read from the provider at runtime, so the descriptor is also a runtime
+        // value: _TLG_DESC with the provider's defaults applied.
+        let default_level = !has_descriptor && event.level.is_empty();
+        let default_keyword = !has_descriptor && event.keywords.is_empty();
+        let default_opcode = !has_descriptor && event.opcode_tokens.is_empty();
+        let default_task = !has_descriptor && event.task_tokens.is_empty();
+        let uses_defaults =
+            default_level || default_keyword || default_opcode || default_task || event.derived_id;
+        let runtime_desc = has_descriptor || uses_defaults;
+
         // always-present args for the helper function's prototype
         self.func_args_tree
             // _tlg_prov: &tlg::Provider
             .add_ident(TLG_PROV_VAR)
             .add_punct(":")
             .add_punct("&")
-            .add_path(PROVIDER_PATH)
-            // , _tlg_meta: &[u8]
-            .add_punct(",")
-            .add_ident(TLG_META_VAR)
-            .add_punct(":")
-            .add_punct("&")
-            .add_group_square(self.tree1.add_path(U8_PATH).drain())
-            // , _tlg_desc: &tlg::EventDescriptor
-            .add_punct(",")
-            .add_ident(TLG_DESC_VAR)
-            .add_punct(":")
-            .add_punct("&")
-            .add_path(EVENTDESC_PATH)
-            // , activity_id: Option<&[u8; 16]>
-            .add_punct(",")
-            .add_ident(TLG_ACTIVITY_ID_VAR)
-            .add_punct(":")
-            .add_path(OPTION_PATH)
-            .add_punct("<")
-            .add_punct("&")
-            .add_group_square(
-                self.tree1
-                    .add_path(U8_PATH)
-                    .add_punct(";")
-                    .add_literal(Literal::usize_unsuffixed(16))
-                    .drain(),
-            )
-            .add_punct(">")
-            // , related_id: Option<&[u8; 16]>
-            .add_punct(",")
-            .add_ident(TLG_RELATED_ID_VAR)
-            .add_punct(":")
-            .add_path(OPTION_PATH)
-            .add_punct("<")
-            .add_punct("&")
-            .add_group_square(
-                self.tree1
-                    .add_path(U8_PATH)
-                    .add_punct(";")
-                    .add_literal(Literal::usize_unsuffixed(16))
-                    .drain(),
-            )
-            .add_punct(">");
+            .add_path(PROVIDER_PATH);
+        if !disabled {
+            self.func_args_tree
+                // , _tlg_meta: &[u8]
+                .add_punct(",")
+                .add_ident(TLG_META_VAR)
+                .add_punct(":")
+                .add_punct("&")
+                .add_group_square(self.tree1.add_path(U8_PATH).drain())
+                // , _tlg_desc: &tlg::EventDescriptor
+                .add_punct(",")
+                .add_ident(TLG_DESC_VAR)
+                .add_punct(":")
+                .add_punct("&")
+                .add_path(EVENTDESC_PATH);
+        }
 
         // always-present args for the helper function's call site
-        self.func_call_tree
-            // &PROVIDER
-            .add_punct("&")
-            .add_token(event.provider_symbol.clone())
-            // , tlg::meta_as_bytes(&_tlg_meta)
-            .add_punct(",")
-            .add_path_call(
-                META_AS_BYTES_PATH,
-                self.tree1.add_punct("&").add_ident(TLG_META_CONST).drain(),
-            )
-            // , &_TLG_DESC
-            .add_punct(",")
-            .add_punct("&")
-            .add_ident(TLG_DESC_CONST)
-            // , None-or-Some(borrow(activity_id_tokens...))
-            .add_punct(",")
-            .push_span(event.activity_id.context)
-            .add_borrowed_option_from_tokens(&mut self.tree1, event.activity_id.tokens)
-            .pop_span()
-            // , None-or-Some(borrow(related_id_tokens...))
-            .add_punct(",")
-            .push_span(event.related_id.context)
-            .add_borrowed_option_from_tokens(&mut self.tree1, event.related_id.tokens)
-            .pop_span();
+        if let Some(provider_symbol) = &event.provider_symbol {
+            self.func_call_tree
+                // &PROVIDER
+                .add_punct("&")
+                .add_tokens(provider_symbol.clone());
+        } else {
+            self.func_call_tree
+                // _tlg_prov
+                .add_ident(TLG_PROV_VAR);
+        }
+        if !disabled {
+            self.func_call_tree
+                // , tlg::meta_as_bytes(&_tlg_meta)
+                .add_punct(",")
+                .add_path_call(
+                    META_AS_BYTES_PATH,
+                    self.tree1.add_punct("&").add_ident(TLG_META_CONST).drain(),
+                )
+                .add_punct(",");
+            if runtime_desc {
+                // , _tlg_desc
+                self.func_call_tree.add_ident(TLG_DESC_VAR);
+            } else {
+                // , &_TLG_DESC
+                self.func_call_tree.add_punct("&").add_ident(TLG_DESC_CONST);
+            }
+        }
+
+        // Optional write parameters. If the event has none, the helper calls
+        // provider_write_transfer. Otherwise the helper takes an EventWriteOptions and
+        // calls provider_write_transfer_ex.
+        let has_write_options = !event.activity_id.is_empty()
+            || !event.related_id.is_empty()
+            || !event.write_flags.is_empty();
+        if has_write_options {
+            self.func_args_tree
+                // , _tlg_opts: &EventWriteOptions
+                .add_punct(",")
+                .add_ident(TLG_OPTIONS_VAR)
+                .add_punct(":")
+                .add_punct("&")
+                .add_path(EVENT_WRITE_OPTIONS_PATH);
+
+            self.tree1
+                // activity_id: None-or-Some(borrow(activity_id_tokens...)),
+                .add_ident("activity_id")
+                .add_punct(":")
+                .push_span(event.activity_id.context)
+                .add_borrowed_option_from_tokens(&mut self.tree2, event.activity_id.tokens)
+                .pop_span()
+                .add_punct(",")
+                // related_id: None-or-Some(borrow(related_id_tokens...)),
+                .add_ident("related_id")
+                .add_punct(":")
+                .push_span(event.related_id.context)
+                .add_borrowed_option_from_tokens(&mut self.tree2, event.related_id.tokens)
+                .pop_span()
+                .add_punct(",")
+                // flags: identity::<u32>(write_flags_tokens...) or 0
+                .add_ident("flags")
+                .add_punct(":");
+            if event.write_flags.is_empty() {
+                self.tree1.add_literal(Literal::u32_unsuffixed(0));
+            } else {
+                self.tree1
+                    .push_span(event.write_flags.context) // Use identity(...) as a target for error messages.
+                    .add_path(IDENTITY_PATH)
+                    .add_punct("::")
+                    .add_punct("<")
+                    .add_path(U32_PATH)
+                    .add_punct(">")
+                    .add_group_paren(event.write_flags.tokens)
+                    .pop_span();
+            }
+
+            self.func_call_tree
+                // , &EventWriteOptions { activity_id: ..., related_id: ..., flags: ... }
+                .add_punct(",")
+                .add_punct("&")
+                .add_path(EVENT_WRITE_OPTIONS_PATH)
+                .add_group_curly(self.tree1.drain());
+        }
 
         // Add the per-field stuff:
 
@@ -217,111 +282,231 @@ impl EventGenerator {
         struct _TlgMeta(meta_type_tree...);
         const _TLG_META = _TlgMeta(meta_init_tree...);
         fn _tlg_write(func_args_tree...) -> u32 {
+            func_checks_tree...
             let _tlg_lengths = [lengths_init_tree...];
-            provider_write_transfer(prov, desc, aid, rid, &[data_desc_init_tree...]);
+            provider_write_transfer(prov, desc, None, None, &[data_desc_init_tree...]);
+            // or provider_write_transfer_ex(prov, desc, opts, &[data_desc_init_tree...]);
         }
         _tlg_write(func_call_tree)
         */
 
-        self.enabled_tree
-            // const _TLG_DESC: EventDescriptor = EventDescriptor::from_raw_parts(...);
-            .add_const_from_tokens(
-                TLG_DESC_CONST,
-                EVENTDESC_PATH,
-                self.tree1
-                    .add_path_call(
-                        EVENTDESC_FROM_PARTS_PATH,
-                        self.tree2
-                            .add_tokens(event.id_tokens)
-                            .add_punct(",")
-                            .add_tokens(event.version_tokens)
-                            .add_punct(",")
-                            .add_tokens(event.channel_tokens)
-                            .add_punct(",")
-                            .add_ident(TLG_LEVEL_CONST)
-                            .add_punct(",")
-                            .add_tokens(event.opcode_tokens)
-                            .add_punct(",")
-                            .add_tokens(event.task_tokens)
-                            .add_punct(",")
-                            .add_ident(TLG_KEYWORD_CONST)
-                            .drain(),
-                    )
-                    .drain(),
-            )
-            // const _TLG_TAG: u32 = EVENT_TAG; const _TLG_TAG3: u32 = FIELD3_TAG;
-            .add_tokens(self.tags_tree.drain())
-            // #[repr(packed)]
-            .add_outer_attribute("repr", self.tree1.add_ident("packed").drain())
-            // struct _TlgMeta(meta_types...);
-            .add_ident("struct")
-            .add_ident(TLG_META_TYPE)
-            .add_group_paren(self.meta_type_tree.drain())
-            .add_punct(";")
-            // const _TLG_META: _TlgMeta = _TlgMeta(...);
-            .add_ident("const")
-            .add_ident(TLG_META_CONST)
-            .add_punct(":")
-            .add_ident(TLG_META_TYPE)
-            .add_punct("=")
-            .add_ident(TLG_META_TYPE)
-            .add_group_paren(self.meta_init_tree.drain())
-            .add_punct(";")
-            // #[allow(clippy::too_many_arguments)]
-            .add_outer_attribute(
-                "allow",
+        // "_tlg_opts," if the event has write options, otherwise "None, None,".
+        let write_options_args: Vec<TokenTree> = if has_write_options {
+            self.tree1
+                .add_ident(TLG_OPTIONS_VAR)
+                .add_punct(",")
+                .drain()
+                .collect()
+        } else {
+            self.tree1
+                .add_path(OPTION_NONE_PATH)
+                .add_punct(",")
+                .add_path(OPTION_NONE_PATH)
+                .add_punct(",")
+                .drain()
+                .collect()
+        };
+
+        // const _TLG_DESC: EventDescriptor = ...; (added to event_tree below)
+        let mut desc_tokens = Vec::new();
+
+        if disabled {
+            /*
+            #[allow(dead_code)] const _: () = { const _: u16 = TASK; const _: Opcode = OPCODE; tags_tree... };
+            let _ = (_TLG_LEVEL, _TLG_KEYWORD,);
+            let _tlg_write = |func_args_tree...| 0u32;
+            _tlg_write(func_call_tree)
+            */
+            // Task, opcode, and tags are still checked, e.g. tags must be constant and
+            // fit in 28 bits.
+            if !event.task_tokens.is_empty() {
+                // const _: u16 = TASK;
                 self.tree1
-                    .add_ident("clippy")
-                    .add_punct("::")
-                    .add_ident("too_many_arguments")
-                    .drain(),
-            )
-            // Make a helper function and then call it. This does the following:
-            // - Keep temporaries alive (this could also be done with a match expression).
-            // - Give the optimizer the option to merge identical helpers.
-            // fn _tlg_write(prov, meta, desc, aid, rid, args...) -> { ... }
-            .add_ident("fn")
-            .add_ident(TLG_WRITE_FUNC)
-            .add_group_paren(self.func_args_tree.drain())
-            .add_punct("->")
-            .add_path(U32_PATH)
-            .add_group_curly(
+                    .add_const_from_tokens("_", U16_PATH, event.task_tokens);
+            }
+            if !event.opcode_tokens.is_empty() {
+                // const _: Opcode = OPCODE;
                 self.tree1
-                    // let _tlg_lengths = [...];
+                    .add_const_from_tokens("_", OPCODE_PATH, event.opcode_tokens);
+            }
+            self.tree1
+                // const _TLG_TAG: u32 = TAG; ...
+                .add_tokens(self.tags_tree.drain());
+            self.enabled_tree
+                // #[allow(dead_code)] const _: () = { ... };
+                .add_outer_attribute("allow", self.tree2.add_ident("dead_code").drain())
+                .add_ident("const")
+                .add_ident("_")
+                .add_punct(":")
+                .add_group_paren([])
+                .add_punct("=")
+                .add_group_curly(self.tree1.drain())
+                .add_punct(";");
+            if !has_descriptor && (!default_level || !default_keyword) {
+                if !default_level {
+                    self.tree1.add_ident(TLG_LEVEL_CONST).add_punct(",");
+                }
+                if !default_keyword {
+                    self.tree1.add_ident(TLG_KEYWORD_CONST).add_punct(",");
+                }
+                self.enabled_tree
+                    // let _ = (_TLG_LEVEL, _TLG_KEYWORD,);
                     .add_ident("let")
-                    .add_ident(TLG_LENGTHS_VAR)
-                    .add_punct(":")
-                    .add_group_square(
-                        self.tree2
-                            .add_path(U16_PATH)
-                            .add_punct(";")
-                            .add_literal(Literal::u16_unsuffixed(self.lengths_count))
-                            .drain(),
-                    )
+                    .add_ident("_")
                     .add_punct("=")
-                    .add_group_square(self.lengths_init_tree.drain())
-                    .add_punct(";")
-                    // provider_write_transfer(_tlg_prov, meta, &_TLG_DESC, activity_id, related_id, &[data...])
-                    .add_path_call(
-                        PROVIDER_WRITE_TRANSFER_PATH,
-                        self.tree2
-                            .add_ident(TLG_PROV_VAR)
-                            .add_punct(",")
-                            .add_ident(TLG_DESC_VAR) // descriptor
-                            .add_punct(",")
-                            .add_ident(TLG_ACTIVITY_ID_VAR)
-                            .add_punct(",")
-                            .add_ident(TLG_RELATED_ID_VAR)
-                            .add_punct(",")
-                            .add_punct("&")
-                            .add_group_square(self.data_desc_init_tree.drain())
+                    .add_group_paren(self.tree1.drain())
+                    .add_punct(";");
+            }
+            self.enabled_tree
+                // let _tlg_write = |prov, opts, args...| 0u32;
+                .add_ident("let")
+                .add_ident(TLG_WRITE_FUNC)
+                .add_punct("=")
+                .add_punct("|")
+                .add_tokens(self.func_args_tree.drain())
+                .add_punct("|")
+                .add_literal(Literal::u32_suffixed(0))
+                .add_punct(";");
+            // _tlg_write(prov, opts, values...)
+            self.add_write_call(event.batch);
+        } else {
+            if !has_descriptor {
+                // Values that come from the provider's defaults are placeholders here.
+                // They are replaced when _tlg_desc is built at runtime.
+                self.tree2
+                    .add_tokens(event.id_tokens)
+                    .add_punct(",")
+                    .add_tokens(event.version_tokens)
+                    .add_punct(",")
+                    .add_tokens(event.channel_tokens)
+                    .add_punct(",");
+                if default_level {
+                    self.tree2.add_path(LEVEL_VERBOSE_PATH);
+                } else {
+                    self.tree2.add_ident(TLG_LEVEL_CONST);
+                }
+                self.tree2.add_punct(",");
+                if default_opcode {
+                    self.tree2.add_path(OPCODE_INFO_PATH);
+                } else {
+                    self.tree2.add_tokens(event.opcode_tokens);
+                }
+                self.tree2.add_punct(",");
+                if event.task_tokens.is_empty() {
+                    self.tree2.add_literal(Literal::u16_unsuffixed(0));
+                } else {
+                    self.tree2.add_tokens(event.task_tokens);
+                }
+                self.tree2.add_punct(",");
+                if default_keyword {
+                    self.tree2.add_literal(Literal::u64_suffixed(1));
+                } else {
+                    self.tree2.add_ident(TLG_KEYWORD_CONST);
+                }
+                desc_tokens = self
+                    .tree1
+                    // const _TLG_DESC: EventDescriptor = EventDescriptor::from_raw_parts(...);
+                    .add_const_from_tokens(
+                        TLG_DESC_CONST,
+                        EVENTDESC_PATH,
+                        self.tree3
+                            .add_path_call(EVENTDESC_FROM_PARTS_PATH, self.tree2.drain())
                             .drain(),
                     )
-                    .drain(),
-            )
-            // _tlg_write(prov, meta, aid, rid, values...)
-            .add_ident(TLG_WRITE_FUNC)
-            .add_group_paren(self.func_call_tree.drain());
+                    .drain()
+                    .collect();
+            }
+            self.enabled_tree
+                // const _TLG_TAG: u32 = EVENT_TAG; const _TLG_TAG3: u32 = FIELD3_TAG;
+                .add_tokens(self.tags_tree.drain())
+                // #[repr(packed)]
+                .add_outer_attribute("repr", self.tree1.add_ident("packed").drain())
+                // struct _TlgMeta(meta_types...);
+                .add_ident("struct")
+                .add_ident(TLG_META_TYPE)
+                .add_group_paren(self.meta_type_tree.drain())
+                .add_punct(";")
+                // const _TLG_META: _TlgMeta = _TlgMeta(...);
+                .add_ident("const")
+                .add_ident(TLG_META_CONST)
+                .add_punct(":")
+                .add_ident(TLG_META_TYPE)
+                .add_punct("=")
+                .add_ident(TLG_META_TYPE)
+                .add_group_paren(self.meta_init_tree.drain())
+                .add_punct(";")
+                // #[allow(clippy::too_many_arguments)]
+                .add_outer_attribute(
+                    "allow",
+                    self.tree1
+                        .add_ident("clippy")
+                        .add_punct("::")
+                        .add_ident("too_many_arguments")
+                        .drain(),
+                );
+
+            // The helper's frame holds the EventDataDescriptor array (16 bytes per
+            // field). If the helper is inlined, the caller's frame includes the array
+            // even when the event is disabled, e.g. a 64-field event adds about 1KB to
+            // the caller's frame. For large events, keep the array in the helper.
+            if self.field_count > INLINE_FIELDS_MAX {
+                // #[inline(never)]
+                self.enabled_tree
+                    .add_outer_attribute("inline", self.tree1.add_ident("never").drain());
+            }
+
+            self.enabled_tree
+                // Make a helper function and then call it. This does the following:
+                // - Keep temporaries alive (this could also be done with a match expression).
+                // - Give the optimizer the option to merge identical helpers.
+                // fn _tlg_write(prov, meta, desc, opts, args...) -> { ... }
+                .add_ident("fn")
+                .add_ident(TLG_WRITE_FUNC)
+                .add_group_paren(self.func_args_tree.drain())
+                .add_punct("->")
+                .add_path(U32_PATH)
+                .add_group_curly(
+                    self.tree1
+                        // let _tlg_argN = match _tlg_argN { ... };
+                        .add_tokens(self.func_checks_tree.drain())
+                        // let _tlg_lengths = [...];
+                        .add_ident("let")
+                        .add_ident(TLG_LENGTHS_VAR)
+                        .add_punct(":")
+                        .add_group_square(
+                            self.tree2
+                                .add_path(U16_PATH)
+                                .add_punct(";")
+                                .add_literal(Literal::u16_unsuffixed(self.lengths_count))
+                                .drain(),
+                        )
+                        .add_punct("=")
+                        .add_group_square(self.lengths_init_tree.drain())
+                        .add_punct(";")
+                        // provider_write_transfer(_tlg_prov, &_TLG_DESC, None, None, &[data...])
+                        // or, if the event has write options:
+                        // provider_write_transfer_ex(_tlg_prov, &_TLG_DESC, _tlg_opts, &[data...])
+                        .add_path_call(
+                            if has_write_options {
+                                PROVIDER_WRITE_TRANSFER_EX_PATH
+                            } else {
+                                PROVIDER_WRITE_TRANSFER_PATH
+                            },
+                            self.tree2
+                                .add_ident(TLG_PROV_VAR)
+                                .add_punct(",")
+                                .add_ident(TLG_DESC_VAR) // descriptor
+                                .add_punct(",")
+                                .add_tokens(write_options_args)
+                                .add_punct("&")
+                                .add_group_square(self.data_desc_init_tree.drain())
+                                .drain(),
+                        )
+                        .drain(),
+                );
+            // _tlg_write(prov, meta, desc, opts, values...)
+            self.add_write_call(event.batch);
+        }
 
         // put it all together:
         /*
@@ -336,74 +521,238 @@ impl EventGenerator {
 
         let event_tree = &mut self.tree2; // Alias tree2 to save a tree.
 
-        // _TLG_KEYWORD
-        if event.keywords.len() == 1 {
-            // Generate simple output if only one keyword.
-            // const _TLG_KEYWORD: u64 = KEYWORDS[0];
-            let keyword = event.keywords.pop().unwrap();
-            event_tree
-                .push_span(keyword.context)
-                .add_const_from_tokens(TLG_KEYWORD_CONST, U64_PATH, keyword.tokens)
-                .pop_span();
-        } else {
-            // More-complex output needed in other cases.
-            //
-            // We have suboptimal results if we combine the subexpressions ourselves,
-            // e.g. doing "const X = (KEYWORDS0) | (KEYWORDS1);"" would result in
-            // suboptimal error reporting for syntax errors in the user-supplied
-            // expressions as well as warnings for unnecessary parentheses. Instead,
-            // evaluate the subexpressions separately then combine the resulting
-            // constants. This works for any number of keywords.
-            //
-            // const _TLG_KEYWORD0: u64 = KEYWORDS0;
-            // const _TLG_KEYWORD1: u64 = KEYWORDS1;
-            // const _TLG_KEYWORD: u64 = 0u64 | _TLG_KEYWORD0 | _TLG_KEYWORD1;
-
-            let mut tlg_keyword_n = IdentBuilder::new(TLG_KEYWORD_CONST);
-
-            // Build up "const _TLG_KEYWORDn: u64 = KEYWORDSn; ..."" in event_tree.
-            // Build up "0u64 | _TLG_KEYWORD0 | _TLG_KEYWORD1 ..." in tree1.
-
-            // tree1 += "0u64"
-            self.tree1.add_literal(Literal::u64_suffixed(0));
-
-            for (n, keyword) in event.keywords.drain(..).enumerate() {
-                // event_tree += "const _TLG_KEYWORDn: u64 = KEYWORDSn;"
+        // With a descriptor option, the level and keyword come from the descriptor at
+        // runtime, so there are no _TLG_LEVEL or _TLG_KEYWORD constants.
+        if !has_descriptor {
+            // _TLG_KEYWORD
+            if default_keyword {
+                // The keyword comes from the provider's defaults.
+            } else if event.keywords.len() == 1 {
+                // Generate simple output if only one keyword.
+                // const _TLG_KEYWORD: u64 = KEYWORDS[0];
+                let keyword = event.keywords.pop().unwrap();
                 event_tree
                     .push_span(keyword.context)
-                    .add_const_from_tokens(tlg_keyword_n.set_suffix(n), U64_PATH, keyword.tokens)
+                    .add_const_from_tokens(TLG_KEYWORD_CONST, U64_PATH, keyword.tokens)
                     .pop_span();
+            } else {
+                // More-complex output needed in other cases.
+                //
+                // We have suboptimal results if we combine the subexpressions ourselves,
+                // e.g. doing "const X = (KEYWORDS0) | (KEYWORDS1);"" would result in
+                // suboptimal error reporting for syntax errors in the user-supplied
+                // expressions as well as warnings for unnecessary parentheses. Instead,
+                // evaluate the subexpressions separately then combine the resulting
+                // constants. This works for any number of keywords.
+                //
+                // const _TLG_KEYWORD0: u64 = KEYWORDS0;
+                // const _TLG_KEYWORD1: u64 = KEYWORDS1;
+                // const _TLG_KEYWORD: u64 = 0u64 | _TLG_KEYWORD0 | _TLG_KEYWORD1;
+
+                let mut tlg_keyword_n = IdentBuilder::new(TLG_KEYWORD_CONST);
+
+                // Build up "const _TLG_KEYWORDn: u64 = KEYWORDSn; ..."" in event_tree.
+                // Build up "0u64 | _TLG_KEYWORD0 | _TLG_KEYWORD1 ..." in tree1.
+
+                // tree1 += "0u64"
+                self.tree1.add_literal(Literal::u64_suffixed(0));
+
+                for (n, keyword) in event.keywords.drain(..).enumerate() {
+                    // event_tree += "const _TLG_KEYWORDn: u64 = KEYWORDSn;"
+                    event_tree
+                        .push_span(keyword.context)
+                        .add_const_from_tokens(
+                            tlg_keyword_n.set_suffix(n),
+                            U64_PATH,
+                            keyword.tokens,
+                        )
+                        .pop_span();
 
-                // tree1 += "| _TLG_KEYWORDn"
-                self.tree1.add_punct("|").add_ident(tlg_keyword_n.current());
+                    // tree1 += "| _TLG_KEYWORDn"
+                    self.tree1.add_punct("|").add_ident(tlg_keyword_n.current());
+                }
+
+                // event_tree += "const _TLG_KEYWORD: u64 = 0u64 | _TLG_KEYWORD0 | _TLG_KEYWORD1;"
+                event_tree.add_const_from_tokens(TLG_KEYWORD_CONST, U64_PATH, self.tree1.drain());
+            }
+
+            if !default_level {
+                event_tree
+                    // const _TLG_LEVEL: Level = LEVEL;
+                    .push_span(event.level.context)
+                    .add_const_from_tokens(TLG_LEVEL_CONST, LEVEL_PATH, event.level.tokens)
+                    .pop_span();
             }
 
-            // event_tree += "const _TLG_KEYWORD: u64 = 0u64 | _TLG_KEYWORD0 | _TLG_KEYWORD1;"
-            event_tree.add_const_from_tokens(TLG_KEYWORD_CONST, U64_PATH, self.tree1.drain());
+            // const _TLG_DESC: EventDescriptor = ...;
+            event_tree.add_tokens(desc_tokens);
         }
 
-        event_tree
-            // const _TLG_LEVEL: Level = LEVEL;
-            .push_span(event.level.context)
-            .add_const_from_tokens(TLG_LEVEL_CONST, LEVEL_PATH, event.level.tokens)
-            .pop_span()
-            // if !PROVIDER.enabled(_TLG_LEVEL, _TLG_KEYWORD) { 0 }
-            .add_ident("if")
-            .add_punct("!")
-            .add_token(event.provider_symbol)
-            .add_punct(".")
-            .add_ident("enabled")
-            .add_group_paren(
+        // Runtime provider and descriptor expressions. These go before the enabled
+        // check, or, if the "disabled" feature is enabled, into the never-executed block
+        // so that they are type-checked but not evaluated.
+        let exprs_tree = &mut self.tree3; // Alias tree3 to save a tree.
+
+        if !event.provider_expr.is_empty() {
+            // Evaluate the provider expression exactly once, before the enabled check.
+            exprs_tree
+                // #[allow(unused_parens)] let _tlg_prov: &Provider = PROVIDER_EXPR;
+                // (A local provider variable must be written as "(provider)".)
+                .push_span(event.provider_expr.context)
+                .add_outer_attribute("allow", self.tree1.add_ident("unused_parens").drain())
+                .add_ident("let")
+                .add_ident(TLG_PROV_VAR)
+                .add_punct(":")
+                .add_punct("&")
+                .add_path(PROVIDER_PATH)
+                .add_punct("=")
+                .add_tokens(event.provider_expr.tokens)
+                .add_punct(";")
+                .pop_span();
+        }
+
+        if has_descriptor {
+            // Evaluate the descriptor expression exactly once, before the enabled check.
+            exprs_tree
+                // let _tlg_desc: &EventDescriptor = DESCRIPTOR_EXPR;
+                .push_span(event.descriptor.context)
+                .add_ident("let")
+                .add_ident(TLG_DESC_VAR)
+                .add_punct(":")
+                .add_punct("&")
+                .add_path(EVENTDESC_PATH)
+                .add_punct("=")
+                .add_tokens(event.descriptor.tokens)
+                .add_punct(";")
+                .pop_span();
+        }
+
+        if uses_defaults && !disabled {
+            /*
+            let _tlg_defaults: &ProviderDefaults = provider_defaults(&PROVIDER);
+            let _tlg_desc: &EventDescriptor = &EventDescriptor { level: _tlg_defaults.level, ..., .._TLG_DESC };
+            */
+            if let Some(provider_symbol) = &event.provider_symbol {
+                // &PROVIDER
                 self.tree1
-                    .add_ident(TLG_LEVEL_CONST)
-                    .add_punct(",")
-                    .add_ident(TLG_KEYWORD_CONST)
-                    .drain(),
-            )
-            .add_group_curly(self.tree1.add_literal(Literal::u32_suffixed(0)).drain())
-            // else { enabled_tree... }
-            .add_ident("else")
-            .add_group_curly(self.enabled_tree.drain());
+                    .add_punct("&")
+                    .add_tokens(provider_symbol.clone());
+            } else {
+                // _tlg_prov
+                self.tree1.add_ident(TLG_PROV_VAR);
+            }
+            exprs_tree
+                // let _tlg_defaults: &ProviderDefaults = provider_defaults(&PROVIDER);
+                .add_ident("let")
+                .add_ident(TLG_DEFAULTS_VAR)
+                .add_punct(":")
+                .add_punct("&")
+                .add_path(PROVIDER_DEFAULTS_PATH)
+                .add_punct("=")
+                .add_path_call(PROVIDER_DEFAULTS_FN_PATH, self.tree1.drain())
+                .add_punct(";");
+
+            if event.derived_id {
+                // id: if _tlg_defaults.derive_event_ids { _TLG_DESC.id } else { 0 },
+                let mut id_tree = Tree::new(Span::call_site());
+                self.tree1
+                    .add_ident("id")
+                    .add_punct(":")
+                    .add_ident("if")
+                    .add_ident(TLG_DEFAULTS_VAR)
+                    .add_punct(".")
+                    .add_ident("derive_event_ids")
+                    .add_group_curly(
+                        id_tree
+                            .add_ident(TLG_DESC_CONST)
+                            .add_punct(".")
+                            .add_ident("id")
+                            .drain(),
+                    )
+                    .add_ident("else")
+                    .add_group_curly([TokenTree::from(Literal::u16_unsuffixed(0))])
+                    .add_punct(",");
+            }
+
+            for (is_default, name) in [
+                (default_level, "level"),
+                (default_opcode, "opcode"),
+                (default_task, "task"),
+                (default_keyword, "keyword"),
+            ] {
+                if is_default {
+                    // name: _tlg_defaults.name,
+                    self.tree1
+                        .add_ident(name)
+                        .add_punct(":")
+                        .add_ident(TLG_DEFAULTS_VAR)
+                        .add_punct(".")
+                        .add_ident(name)
+                        .add_punct(",");
+                }
+            }
+
+            exprs_tree
+                // let _tlg_desc: &EventDescriptor = &EventDescriptor { ..., .._TLG_DESC };
+                .add_ident("let")
+                .add_ident(TLG_DESC_VAR)
+                .add_punct(":")
+                .add_punct("&")
+                .add_path(EVENTDESC_PATH)
+                .add_punct("=")
+                .add_punct("&")
+                .add_path(EVENTDESC_PATH)
+                .add_group_curly(self.tree1.add_punct("..").add_ident(TLG_DESC_CONST).drain())
+                .add_punct(";");
+        }
+
+        if disabled {
+            event_tree
+                // if false { exprs_tree... enabled_tree... }
+                .add_ident("if")
+                .add_ident("false")
+                .add_group_curly(exprs_tree.add_tokens(self.enabled_tree.drain()).drain())
+                // else { 0 }
+                .add_ident("else")
+                .add_group_curly(self.tree1.add_literal(Literal::u32_suffixed(0)).drain());
+        } else {
+            event_tree
+                // exprs_tree...
+                .add_tokens(exprs_tree.drain())
+                // if !PROVIDER.enabled(_TLG_LEVEL, _TLG_KEYWORD) { 0 }
+                .add_ident("if")
+                .add_punct("!")
+                .add_tokens(match event.provider_symbol {
+                    Some(provider_symbol) => provider_symbol,
+                    None => TokenTree::from(Ident::new(TLG_PROV_VAR, Span::call_site())).into(),
+                })
+                .add_punct(".")
+                .add_ident("enabled")
+                .add_group_paren(if runtime_desc {
+                    // _tlg_desc.level, _tlg_desc.keyword
+                    self.tree1
+                        .add_ident(TLG_DESC_VAR)
+                        .add_punct(".")
+                        .add_ident("level")
+                        .add_punct(",")
+                        .add_ident(TLG_DESC_VAR)
+                        .add_punct(".")
+                        .add_ident("keyword")
+                        .drain()
+                } else {
+                    // _TLG_LEVEL, _TLG_KEYWORD
+                    self.tree1
+                        .add_ident(TLG_LEVEL_CONST)
+                        .add_punct(",")
+                        .add_ident(TLG_KEYWORD_CONST)
+                        .drain()
+                })
+                .add_group_curly(self.tree1.add_literal(Literal::u32_suffixed(0)).drain())
+                .add_ident("else");
+            event_tree
+                // { enabled_tree... }
+                .add_group_curly(self.enabled_tree.drain());
+        }
 
         // Wrap the event in "{...}":
         let event_tokens = TokenStream::from(TokenTree::Group(Group::new(
@@ -418,11 +767,63 @@ impl EventGenerator {
         return event_tokens;
     }
 
+    /// Generates the write_event_fields expression for the event, i.e. a
+    /// `&'static [(&'static str, InType, OutType)]` listing the fields described by the
+    /// event's metadata.
+    pub fn generate_fields(&mut self, mut event: EventInfo) -> TokenStream {
+        // Same field walk as write_event, but only the fields_tree is used.
+        for field in event.fields.drain(..) {
+            self.add_field(field);
+        }
+
+        let fields_tree = &mut self.tree3; // Alias tree3 to save a tree.
+        fields_tree
+            // const _TLG_FIELDS: &[(&str, InType, OutType)] = &[fields_tree...];
+            .add_ident("const")
+            .add_ident(TLG_FIELDS_CONST)
+            .add_punct(":")
+            .add_punct("&")
+            .add_group_square(
+                self.tree1
+                    .add_group_paren(
+                        self.tree2
+                            .add_punct("&")
+                            .add_path(STR_PATH)
+                            .add_punct(",")
+                            .add_path(INTYPE_PATH)
+                            .add_punct(",")
+                            .add_path(OUTTYPE_PATH)
+                            .drain(),
+                    )
+                    .drain(),
+            )
+            .add_punct("=")
+            .add_punct("&")
+            .add_group_square(self.fields_tree.drain())
+            .add_punct(";")
+            // _TLG_FIELDS
+            .add_ident(TLG_FIELDS_CONST);
+
+        // Wrap the expression in "{...}":
+        let fields_tokens = TokenStream::from(TokenTree::Group(Group::new(
+            Delimiter::Brace,
+            fields_tree.drain().collect(),
+        )));
+
+        if event.debug {
+            println!("{}", fields_tokens);
+        }
+
+        return fields_tokens;
+    }
+
     fn add_field(&mut self, field: FieldInfo) {
         // Metadata
 
-        if field.option.strategy.has_metadata() {
-            self.meta_buffer.extend(field.name.as_bytes());
+        // A chunked field has one metadata entry per chunk. Other fields have at most one.
+        for chunk in 0..field.option.strategy.meta_count() {
+            self.meta_buffer
+                .extend(chunk_name(&field.name, chunk).as_bytes());
             self.meta_buffer.push(0);
 
             let has_out = !field.outtype_or_field_count_expr.is_empty()
@@ -430,14 +831,16 @@ impl EventGenerator {
             let has_tag = !field.tag.is_empty();
 
             let inflags = (if has_out || has_tag { 0x80 } else { 0 })
-                | (if field.option.strategy.is_slice() {
+                | (if field.fixed_count != 0 {
+                    InType::ConstantCountFlag
+                } else if field.option.strategy.is_slice() {
                     InType::VariableCountFlag
                 } else {
                     0
                 });
             self.add_typecode_meta(
                 INTYPE_PATH,
-                field.intype_tokens,
+                field.intype_tokens.clone(),
                 field.type_name_span,
                 field.option.intype.to_token(),
                 inflags,
@@ -447,16 +850,33 @@ impl EventGenerator {
                 let outflags = if has_tag { 0x80 } else { 0 };
                 self.add_typecode_meta(
                     OUTTYPE_PATH,
-                    field.outtype_or_field_count_expr.tokens,
+                    field.outtype_or_field_count_expr.tokens.clone(),
                     field.outtype_or_field_count_expr.context,
                     EnumToken::U8(field.outtype_or_field_count_int),
                     outflags,
                 );
             }
 
+            self.add_fields_entry(
+                &field,
+                chunk,
+                inflags & (InType::ConstantCountFlag | InType::VariableCountFlag),
+                has_out,
+            );
+
             if has_tag {
-                self.tag_n.set_suffix(self.field_count as usize);
-                self.add_tag(field.tag);
+                if chunk == 0 {
+                    self.tag_n.set_suffix(self.field_count as usize);
+                    self.add_tag(&field.tag);
+                } else {
+                    // Reuse the _TLG_TAGn constant declared for chunk 0.
+                    self.add_tag_meta(field.tag.context);
+                }
+            }
+
+            if field.fixed_count != 0 {
+                // Element count follows the typecodes and tag.
+                self.meta_buffer.extend(field.fixed_count.to_le_bytes());
             }
         }
 
@@ -466,6 +886,22 @@ impl EventGenerator {
         self.arg_n.set_suffix(self.field_count as usize);
 
         match field.option.strategy {
+            FieldStrategy::Scalar if field.tuple_item.is_some() => {
+                // Element of the preceding struct_tuple field: no arg of its own.
+                self.arg_n.set_suffix(self.tuple_field_index as usize);
+                self.tree1
+                    // &_tlg_argN.INDEX
+                    .add_punct("&")
+                    .add_ident(self.arg_n.current())
+                    .add_punct(".")
+                    .add_literal(Literal::u8_unsuffixed(field.tuple_item.unwrap()));
+
+                // EventDataDescriptor::from_value(&_tlg_argN.INDEX),
+                self.data_desc_init_tree
+                    .add_path_call(DATADESC_FROM_VALUE_PATH, self.tree1.drain())
+                    .add_punct(",");
+            }
+
             FieldStrategy::Scalar => {
                 self.tree1
                     // , identity::<&VALUE_TYPE>(value_tokens...)
@@ -508,6 +944,173 @@ impl EventGenerator {
                 self.add_data_desc_for_arg_n(DATADESC_FROM_VALUE_PATH);
             }
 
+            FieldStrategy::SystemTimeChrono => {
+                self.tree1
+                    // , &systemtime_words_from_naive(value_tokens...)
+                    .push_span(field.type_name_span) // Use systemtime_words_from_naive(...) as a target for error messages.
+                    .add_punct("&")
+                    .add_path_call(SYSTEMTIME_WORDS_FROM_NAIVE_PATH, field.value_tokens)
+                    .pop_span();
+
+                // Prototype: , _tlg_argN: &[u16; 8]
+                // Call site: , &systemtime_words_from_naive(value_tokens...)
+                self.add_func_scalar_arg(field.option); // consumes tree1
+
+                // EventDataDescriptor::from_value(_tlg_argN),
+                self.add_data_desc_for_arg_n(DATADESC_FROM_VALUE_PATH);
+            }
+
+            FieldStrategy::Bool32 => {
+                self.tree1
+                    // , &bool32_from_bool(value_tokens...)
+                    .push_span(field.type_name_span) // Use bool32_from_bool(...) as a target for error messages.
+                    .add_punct("&")
+                    .add_path_call(BOOL32_FROM_BOOL_PATH, field.value_tokens)
+                    .pop_span();
+
+                // Prototype: , _tlg_argN: &i32
+                // Call site: , &bool32_from_bool(value_tokens...)
+                self.add_func_scalar_arg(field.option); // consumes tree1
+
+                // EventDataDescriptor::from_value(_tlg_argN),
+                self.add_data_desc_for_arg_n(DATADESC_FROM_VALUE_PATH);
+            }
+
+            FieldStrategy::Bool32Slice => {
+                // , _tlg_argN: Option<&[i32]>
+                self.add_func_checked_slice_arg(field.option);
+
+                // , Bool32Array::new(AsRef::<[bool]>::as_ref(&(value_tokens...))).as_slice()
+                self.func_call_tree
+                    .add_punct(",")
+                    .push_span(field.type_name_span) // Use as_ref(...) as a target for error messages.
+                    .add_path_call(
+                        BOOL32_ARRAY_NEW_PATH,
+                        self.tree1
+                            .add_path(ASREF_PATH)
+                            .add_punct("::")
+                            .add_punct("<")
+                            .add_group_square(self.tree2.add_path(BOOL_PATH).drain())
+                            .add_punct(">")
+                            .add_punct("::")
+                            .add_ident("as_ref")
+                            .add_group_paren(
+                                self.tree2
+                                    .push_span(value_span(
+                                        &field.value_tokens,
+                                        field.type_name_span,
+                                    )) // Use the value as the target for type errors.
+                                    .add_punct("&")
+                                    .add_group_paren(field.value_tokens)
+                                    .pop_span()
+                                    .drain(),
+                            )
+                            .drain(),
+                    )
+                    .add_punct(".")
+                    .add_ident("as_slice")
+                    .add_group_paren([])
+                    .pop_span();
+
+                // EventDataDescriptor::from_value(&_tlg_lengths[N]),
+                // EventDataDescriptor::from_slice(_tlg_argN),
+                self.add_data_desc_with_length(SLICE_COUNT_PATH, DATADESC_FROM_SLICE_PATH);
+            }
+
+            FieldStrategy::F16 => {
+                self.tree1
+                    // , &f32_from_f16(value_tokens...)
+                    .push_span(field.type_name_span) // Use f32_from_f16(...) as a target for error messages.
+                    .add_punct("&")
+                    .add_path_call(F32_FROM_F16_PATH, field.value_tokens)
+                    .pop_span();
+
+                // Prototype: , _tlg_argN: &f32
+                // Call site: , &f32_from_f16(value_tokens...)
+                self.add_func_scalar_arg(field.option); // consumes tree1
+
+                // EventDataDescriptor::from_value(_tlg_argN),
+                self.add_data_desc_for_arg_n(DATADESC_FROM_VALUE_PATH);
+            }
+
+            FieldStrategy::F16Slice => {
+                // , _tlg_argN: Option<&[f32]>
+                self.add_func_checked_slice_arg(field.option);
+
+                // , F32Array::new(value_tokens...).as_slice()
+                self.func_call_tree
+                    .add_punct(",")
+                    .push_span(field.type_name_span) // Use new(...) as a target for error messages.
+                    .add_path_call(F32_ARRAY_NEW_PATH, field.value_tokens)
+                    .add_punct(".")
+                    .add_ident("as_slice")
+                    .add_group_paren([])
+                    .pop_span();
+
+                // EventDataDescriptor::from_value(&_tlg_lengths[N]),
+                // EventDataDescriptor::from_slice(_tlg_argN),
+                self.add_data_desc_with_length(SLICE_COUNT_PATH, DATADESC_FROM_SLICE_PATH);
+            }
+
+            FieldStrategy::ByteOrder => {
+                // e.g. u32_be => u32_be_bytes
+                let bytes_fn = format!("{}_bytes", field.option.option_name);
+                self.tree1
+                    // , &uNN_xe_bytes(value_tokens...)
+                    .push_span(field.type_name_span) // Use uNN_xe_bytes(...) as a target for error messages.
+                    .add_punct("&")
+                    .add_path_call(
+                        &["tracelogging", "_internal", &bytes_fn],
+                        field.value_tokens,
+                    )
+                    .pop_span();
+
+                // Prototype: , _tlg_argN: &[u8; value_array_count]
+                // Call site: , &uNN_xe_bytes(value_tokens...)
+                self.add_func_scalar_arg(field.option); // consumes tree1
+
+                // EventDataDescriptor::from_value(&_tlg_lengths[N]),
+                // EventDataDescriptor::from_counted(_tlg_argN),
+                self.add_data_desc_with_length(COUNTED_SIZE_PATH, DATADESC_FROM_COUNTED_PATH);
+            }
+
+            FieldStrategy::Address => {
+                self.tree1
+                    // , &address_from_pointer(value_tokens...)
+                    .push_span(field.type_name_span) // Use address_from_pointer(...) as a target for error messages.
+                    .add_punct("&")
+                    .add_path_call(ADDRESS_FROM_POINTER_PATH, field.value_tokens)
+                    .pop_span();
+
+                // Prototype: , _tlg_argN: &usize
+                // Call site: , &address_from_pointer(value_tokens...)
+                self.add_func_scalar_arg(field.option); // consumes tree1
+
+                // EventDataDescriptor::from_value(_tlg_argN),
+                self.add_data_desc_for_arg_n(DATADESC_FROM_VALUE_PATH);
+            }
+
+            FieldStrategy::AddressSlice => {
+                // , _tlg_argN: &[usize]
+                self.func_args_tree
+                    .add_punct(",")
+                    .add_ident(self.arg_n.current())
+                    .add_punct(":")
+                    .add_punct("&")
+                    .add_group_square(self.tree1.add_path(field.option.value_type).drain());
+
+                // , addresses_from_pointers(value_tokens...)
+                self.func_call_tree
+                    .add_punct(",")
+                    .push_span(field.type_name_span) // Use addresses_from_pointers(...) as a target for error messages.
+                    .add_path_call(ADDRESSES_FROM_POINTERS_PATH, field.value_tokens)
+                    .pop_span();
+
+                // EventDataDescriptor::from_value(&_tlg_lengths[N]),
+                // EventDataDescriptor::from_slice(_tlg_argN),
+                self.add_data_desc_with_length(SLICE_COUNT_PATH, DATADESC_FROM_SLICE_PATH);
+            }
+
             FieldStrategy::SystemTime => {
                 self.tree1
                     // match SystemTime::duration_since(value_tokens, SystemTime::UNIX_EPOCH) { ... }
@@ -561,7 +1164,7 @@ impl EventGenerator {
 
             FieldStrategy::RawData | FieldStrategy::RawField | FieldStrategy::RawFieldSlice => {
                 // Prototype: , _tlg_argN: &[value_type]
-                // Call site: , AsRef::<[value_type]>::as_ref(value_tokens...)
+                // Call site: , AsRef::<[value_type]>::as_ref(&(value_tokens...))
                 self.add_func_slice_arg(field.option, field.type_name_span, field.value_tokens);
 
                 // EventDataDescriptor::from_counted(_tlg_argN),
@@ -570,7 +1173,7 @@ impl EventGenerator {
 
             FieldStrategy::Sid => {
                 // Prototype: , _tlg_argN: &[value_type]
-                // Call site: , AsRef::<[value_type]>::as_ref(value_tokens...)
+                // Call site: , AsRef::<[value_type]>::as_ref(&(value_tokens...))
                 self.add_func_slice_arg(field.option, field.type_name_span, field.value_tokens);
 
                 // EventDataDescriptor::from_sid(_tlg_argN),
@@ -579,12 +1182,14 @@ impl EventGenerator {
 
             FieldStrategy::CStr => {
                 // Prototype: , _tlg_argN: &[value_type]
-                // Call site: , AsRef::<[value_type]>::as_ref(value_tokens...)
+                // Call site: , AsRef::<[value_type]>::as_ref(&(value_tokens...))
                 self.add_func_slice_arg(field.option, field.type_name_span, field.value_tokens);
 
                 // EventDataDescriptor::from_cstr(_tlg_argN),
                 self.add_data_desc_for_arg_n(DATADESC_FROM_CSTR_PATH);
 
+                // from_cstr stops before the value's first NUL (if any), so this is
+                // always the string's only terminator.
                 self.data_desc_init_tree
                     // EventDataDescriptor::from_value<value_type>(&0),
                     .add_path(DATADESC_FROM_VALUE_PATH)
@@ -604,7 +1209,7 @@ impl EventGenerator {
             FieldStrategy::Counted => {
                 if field.option.value_array_count == 0 {
                     // Prototype: , _tlg_argN: &[value_type]
-                    // Call site: , AsRef::<[value_type]>::as_ref(value_tokens...)
+                    // Call site: , AsRef::<[value_type]>::as_ref(&(value_tokens...))
                     self.add_func_slice_arg(field.option, field.type_name_span, field.value_tokens);
                 } else {
                     // e.g. ipv6 takes a fixed-length array, not a variable-length slice
@@ -629,6 +1234,122 @@ impl EventGenerator {
                 self.add_data_desc_with_length(COUNTED_SIZE_PATH, DATADESC_FROM_COUNTED_PATH);
             }
 
+            FieldStrategy::CountedOpt => {
+                // Option::unwrap_or_default(value_tokens...)
+                let value_tokens = self
+                    .tree1
+                    .push_span(field.type_name_span) // Use unwrap_or_default(...) as a target for error messages.
+                    .add_path_call(OPTION_UNWRAP_OR_DEFAULT_PATH, field.value_tokens)
+                    .pop_span()
+                    .drain()
+                    .collect();
+
+                // Prototype: , _tlg_argN: &[value_type]
+                // Call site: , AsRef::<[value_type]>::as_ref(&(Option::unwrap_or_default(value_tokens...)))
+                self.add_func_slice_arg(field.option, field.type_name_span, value_tokens);
+
+                // EventDataDescriptor::from_value(&_tlg_lengths[N]),
+                // EventDataDescriptor::from_counted(_tlg_argN),
+                self.add_data_desc_with_length(COUNTED_SIZE_PATH, DATADESC_FROM_COUNTED_PATH);
+            }
+
+            FieldStrategy::Chunked => {
+                // Prototype: , _tlg_argN: &[&[value_type]; CHUNKS_MAX]
+                // Call site: , &str8_chunks(AsRef::<[value_type]>::as_ref(&(value_tokens...)))
+                self.add_func_chunks_arg(field.option, field.type_name_span, field.value_tokens);
+
+                for chunk in 0..CHUNKS_MAX {
+                    // EventDataDescriptor::from_value(&_tlg_lengths[N]),
+                    // EventDataDescriptor::from_counted(_tlg_argN[chunk]),
+                    self.add_data_desc_with_length_impl(
+                        COUNTED_SIZE_PATH,
+                        DATADESC_FROM_COUNTED_PATH,
+                        Some(chunk),
+                    );
+                }
+            }
+
+            FieldStrategy::Display
+            | FieldStrategy::Debug
+            | FieldStrategy::Json
+            | FieldStrategy::Utf16 => {
+                let (buffer_path, as_bytes) = match field.option.strategy {
+                    FieldStrategy::Debug => (FMT_BUFFER_FROM_DEBUG_PATH, "as_bytes"),
+                    FieldStrategy::Json => (TO_JSON_BYTES_PATH, "as_slice"),
+                    FieldStrategy::Utf16 => (UTF16_BUFFER_NEW_PATH, "as_slice"),
+                    _ => (FMT_BUFFER_FROM_DISPLAY_PATH, "as_bytes"),
+                };
+
+                if let FieldStrategy::Utf16 = field.option.strategy {
+                    // , _tlg_argN: Option<&[value_type]>
+                    self.add_func_checked_slice_arg(field.option);
+                } else {
+                    // , _tlg_argN: &[value_type]
+                    self.func_args_tree
+                        .add_punct(",")
+                        .add_ident(self.arg_n.current())
+                        .add_punct(":")
+                        .add_punct("&")
+                        .add_group_square(self.tree1.add_path(field.option.value_type).drain());
+                }
+
+                // The buffer is a temporary in the call expression, so it lives until
+                // _tlg_write returns.
+                // , FmtBuffer::from_display(value_tokens...).as_bytes()
+                // , to_json_bytes(value_tokens...).as_slice()
+                // , Utf16Buffer::new(value_tokens...).as_slice()
+                self.func_call_tree
+                    .add_punct(",")
+                    .push_span(field.type_name_span) // Use from_display(...) as a target for error messages.
+                    .add_path_call(buffer_path, field.value_tokens)
+                    .add_punct(".")
+                    .add_ident(as_bytes)
+                    .add_group_paren([])
+                    .pop_span();
+
+                // EventDataDescriptor::from_value(&_tlg_lengths[N]),
+                // EventDataDescriptor::from_counted(_tlg_argN),
+                self.add_data_desc_with_length(COUNTED_SIZE_PATH, DATADESC_FROM_COUNTED_PATH);
+            }
+
+            FieldStrategy::Slice if field.fixed_count != 0 => {
+                // , _tlg_argN: &[value_type; fixed_count]
+                self.func_args_tree
+                    .add_punct(",")
+                    .add_ident(self.arg_n.current())
+                    .add_punct(":")
+                    .add_punct("&")
+                    .add_group_square(
+                        self.tree1
+                            .add_path(field.option.value_type)
+                            .add_punct(";")
+                            .add_literal(Literal::usize_unsuffixed(field.fixed_count as usize))
+                            .drain(),
+                    );
+
+                // , identity::<&[value_type; fixed_count]>(value_tokens...)
+                self.func_call_tree
+                    .add_punct(",")
+                    .push_span(field.type_name_span) // Use identity(...) as a target for error messages.
+                    .add_path(IDENTITY_PATH)
+                    .add_punct("::")
+                    .add_punct("<")
+                    .add_punct("&")
+                    .add_group_square(
+                        self.tree1
+                            .add_path(field.option.value_type)
+                            .add_punct(";")
+                            .add_literal(Literal::usize_unsuffixed(field.fixed_count as usize))
+                            .drain(),
+                    )
+                    .add_punct(">")
+                    .add_group_paren(field.value_tokens)
+                    .pop_span();
+
+                // EventDataDescriptor::from_value(_tlg_argN),
+                self.add_data_desc_for_arg_n(DATADESC_FROM_VALUE_PATH);
+            }
+
             FieldStrategy::Slice => {
                 self.add_func_slice_arg(field.option, field.type_name_span, field.value_tokens);
 
@@ -637,6 +1358,146 @@ impl EventGenerator {
                 self.add_data_desc_with_length(SLICE_COUNT_PATH, DATADESC_FROM_SLICE_PATH);
             }
 
+            FieldStrategy::RawStructValue => {
+                // <TYPE as RawStruct>
+                self.tree3
+                    .push_span(field.type_name_span)
+                    .add_punct("<")
+                    .add_tokens(field.struct_type_tokens.clone())
+                    .add_ident("as")
+                    .add_path(RAW_STRUCT_PATH)
+                    .add_punct(">")
+                    .pop_span();
+                let raw_struct_tokens: TokenStream = self.tree3.drain().collect();
+
+                // Metadata for the struct's fields comes from <TYPE as RawStruct>::RAW_META.
+                self.flush_meta_buffer();
+
+                // , [u8; <TYPE as RawStruct>::RAW_META.len()]
+                self.meta_type_tree.add_punct(",").add_group_square(
+                    self.tree1
+                        .add_path(U8_PATH)
+                        .add_punct(";")
+                        .add_tokens(raw_struct_tokens.clone())
+                        .add_punct("::")
+                        .add_ident("RAW_META")
+                        .add_punct(".")
+                        .add_ident("len")
+                        .add_group_paren([])
+                        .drain(),
+                );
+
+                // , raw_meta_array(<TYPE as RawStruct>::RAW_META)
+                self.meta_init_tree.add_punct(",").add_path_call(
+                    RAW_META_ARRAY_PATH,
+                    self.tree1
+                        .add_tokens(raw_struct_tokens.clone())
+                        .add_punct("::")
+                        .add_ident("RAW_META")
+                        .drain(),
+                );
+
+                // , _tlg_argN: &TYPE
+                self.func_args_tree
+                    .add_punct(",")
+                    .add_ident(self.arg_n.current())
+                    .add_punct(":")
+                    .add_punct("&")
+                    .add_tokens(field.struct_type_tokens.clone());
+
+                // , identity::<&TYPE>(value_tokens...)
+                self.func_call_tree
+                    .add_punct(",")
+                    .push_span(field.type_name_span) // Use identity(...) as a target for error messages.
+                    .add_path(IDENTITY_PATH)
+                    .add_punct("::")
+                    .add_punct("<")
+                    .add_punct("&")
+                    .add_tokens(field.struct_type_tokens)
+                    .add_punct(">")
+                    .add_group_paren(field.value_tokens)
+                    .pop_span();
+
+                // EventDataDescriptor::from_counted(<TYPE as RawStruct>::raw_data(_tlg_argN)),
+                self.data_desc_init_tree
+                    .add_path_call(
+                        DATADESC_FROM_COUNTED_PATH,
+                        self.tree1
+                            .add_tokens(raw_struct_tokens)
+                            .add_punct("::")
+                            .add_ident("raw_data")
+                            .add_group_paren(self.tree2.add_ident(self.arg_n.current()).drain())
+                            .drain(),
+                    )
+                    .add_punct(",");
+            }
+
+            FieldStrategy::MapStr8 => {
+                // , _tlg_argN: &MapStr8Buffer
+                self.func_args_tree
+                    .add_punct(",")
+                    .add_ident(self.arg_n.current())
+                    .add_punct(":")
+                    .add_punct("&")
+                    .add_path(MAP_STR8_BUFFER_PATH);
+
+                // The buffer is a temporary in the call expression, so it lives until
+                // _tlg_write returns. It holds the element count followed by the
+                // counted Key and Value strings of each element, and the number of
+                // elements that did not fit.
+                // , &MapStr8Buffer::new(value_tokens...)
+                self.func_call_tree
+                    .add_punct(",")
+                    .push_span(field.type_name_span) // Use new(...) as a target for error messages.
+                    .add_punct("&")
+                    .add_path_call(MAP_STR8_BUFFER_NEW_PATH, field.value_tokens)
+                    .pop_span();
+
+                // EventDataDescriptor::from_counted(_tlg_argN.as_bytes()),
+                // EventDataDescriptor::from_value(_tlg_argN.dropped()),
+                for (new_desc_path, method) in [
+                    (DATADESC_FROM_COUNTED_PATH, "as_bytes"),
+                    (DATADESC_FROM_VALUE_PATH, "dropped"),
+                ] {
+                    self.data_desc_init_tree
+                        .add_path_call(
+                            new_desc_path,
+                            self.tree1
+                                .add_ident(self.arg_n.current())
+                                .add_punct(".")
+                                .add_ident(method)
+                                .add_group_paren([])
+                                .drain(),
+                        )
+                        .add_punct(",");
+                }
+            }
+
+            FieldStrategy::StructTuple => {
+                self.tuple_field_index = self.field_count;
+
+                // , _tlg_argN: &(TYPE0, TYPE1, ...,)
+                self.func_args_tree
+                    .add_punct(",")
+                    .add_ident(self.arg_n.current())
+                    .add_punct(":")
+                    .add_punct("&")
+                    .add_tokens(field.struct_type_tokens.clone());
+
+                // , identity::<&(TYPE0, TYPE1, ...,)>(value_tokens...)
+                self.func_call_tree
+                    .add_punct(",")
+                    .push_span(field.type_name_span) // Use identity(...) as a target for error messages.
+                    .add_path(IDENTITY_PATH)
+                    .add_punct("::")
+                    .add_punct("<")
+                    .add_punct("&")
+                    .add_tokens(field.struct_type_tokens)
+                    .add_punct(">")
+                    .add_group_paren(field.value_tokens)
+                    .pop_span();
+            }
+
             FieldStrategy::Struct
             | FieldStrategy::RawStruct
             | FieldStrategy::RawStructSlice
@@ -652,22 +1513,49 @@ impl EventGenerator {
     }
 
     fn add_data_desc_for_arg_n(&mut self, new_desc_path: &[&str]) {
+        self.add_data_desc_for_arg_n_impl(new_desc_path, None);
+    }
+
+    /// If chunk is Some, uses `_tlg_argN[chunk]` instead of `_tlg_argN`.
+    fn add_data_desc_for_arg_n_impl(&mut self, new_desc_path: &[&str], chunk: Option<u8>) {
+        self.tree1.add_ident(self.arg_n.current());
+        if let Some(chunk) = chunk {
+            self.tree1.add_group_square(
+                self.tree2
+                    .add_literal(Literal::u8_unsuffixed(chunk))
+                    .drain(),
+            );
+        }
+
         self.data_desc_init_tree
             // EventDataDescriptor::new_desc_path(_tlg_argN),
-            .add_path_call(
-                new_desc_path,
-                self.tree1.add_ident(self.arg_n.current()).drain(),
-            )
+            .add_path_call(new_desc_path, self.tree1.drain())
             .add_punct(",");
     }
 
     fn add_data_desc_with_length(&mut self, get_length_path: &[&str], new_desc_path: &[&str]) {
+        self.add_data_desc_with_length_impl(get_length_path, new_desc_path, None);
+    }
+
+    /// If chunk is Some, uses `_tlg_argN[chunk]` instead of `_tlg_argN`.
+    fn add_data_desc_with_length_impl(
+        &mut self,
+        get_length_path: &[&str],
+        new_desc_path: &[&str],
+        chunk: Option<u8>,
+    ) {
+        self.tree1.add_ident(self.arg_n.current());
+        if let Some(chunk) = chunk {
+            self.tree1.add_group_square(
+                self.tree2
+                    .add_literal(Literal::u8_unsuffixed(chunk))
+                    .drain(),
+            );
+        }
+
         // get_length_path(_tlg_argN),
         self.lengths_init_tree
-            .add_path_call(
-                get_length_path,
-                self.tree1.add_ident(self.arg_n.current()).drain(),
-            )
+            .add_path_call(get_length_path, self.tree1.drain())
             .add_punct(",");
 
         // EventDataDescriptor::from_value(&_tlg_lengths[N]),
@@ -686,7 +1574,7 @@ impl EventGenerator {
                     .drain(),
             )
             .add_punct(",");
-        self.add_data_desc_for_arg_n(new_desc_path);
+        self.add_data_desc_for_arg_n_impl(new_desc_path, chunk);
 
         self.lengths_count += 1;
     }
@@ -701,6 +1589,60 @@ impl EventGenerator {
 
     /// Prototype: , _tlg_argN: &VALUE_TYPE
     /// Call site: , tree1_tokens...
+    /// Adds the call to the _tlg_write helper to enabled_tree. For write_events, the
+    /// call is made once per item and the result is 0 or the most recent error.
+    fn add_write_call(&mut self, batch: Option<(TokenStream, TokenStream)>) {
+        match batch {
+            None => {
+                // _tlg_write(func_call_tree...)
+                self.enabled_tree
+                    .add_ident(TLG_WRITE_FUNC)
+                    .add_group_paren(self.func_call_tree.drain());
+            }
+            Some((pattern, iterator)) => {
+                self.enabled_tree
+                    // let mut _tlg_result = 0u32;
+                    .add_ident("let")
+                    .add_ident("mut")
+                    .add_ident(TLG_RESULT_VAR)
+                    .add_punct("=")
+                    .add_literal(Literal::u32_suffixed(0))
+                    .add_punct(";")
+                    // for PATTERN in ITERATOR { ... }
+                    .add_ident("for")
+                    .add_tokens(pattern)
+                    .add_ident("in")
+                    .add_tokens(iterator)
+                    .add_group_curly(
+                        self.tree1
+                            // let _tlg_err = _tlg_write(func_call_tree...);
+                            .add_ident("let")
+                            .add_ident(TLG_ERR_VAR)
+                            .add_punct("=")
+                            .add_ident(TLG_WRITE_FUNC)
+                            .add_group_paren(self.func_call_tree.drain())
+                            .add_punct(";")
+                            // if _tlg_err != 0 { _tlg_result = _tlg_err; }
+                            .add_ident("if")
+                            .add_ident(TLG_ERR_VAR)
+                            .add_punct("!=")
+                            .add_literal(Literal::u32_unsuffixed(0))
+                            .add_group_curly(
+                                self.tree2
+                                    .add_ident(TLG_RESULT_VAR)
+                                    .add_punct("=")
+                                    .add_ident(TLG_ERR_VAR)
+                                    .add_punct(";")
+                                    .drain(),
+                            )
+                            .drain(),
+                    )
+                    // _tlg_result
+                    .add_ident(TLG_RESULT_VAR);
+            }
+        }
+    }
+
     fn add_func_scalar_arg(&mut self, field_option: &FieldOption) {
         // , _tlg_argN: &VALUE_TYPE
         self.func_args_tree
@@ -728,14 +1670,58 @@ impl EventGenerator {
             .add_tokens(self.tree1.drain());
     }
 
+    /// Prototype: , _tlg_argN: Option<&[VALUE_TYPE]>
+    /// Body: let _tlg_argN = match _tlg_argN { Some(v) => v, None => return OVERFLOW };
+    /// For slices converted into a fixed-capacity buffer at the call site. The buffer's
+    /// as_slice() returns None if the value didn't fit, and the event is not written.
+    fn add_func_checked_slice_arg(&mut self, field_option: &FieldOption) {
+        // , _tlg_argN: Option<&[VALUE_TYPE]>
+        self.func_args_tree
+            .add_punct(",")
+            .add_ident(self.arg_n.current())
+            .add_punct(":")
+            .add_path(OPTION_PATH)
+            .add_punct("<")
+            .add_punct("&")
+            .add_group_square(self.tree1.add_path(field_option.value_type).drain())
+            .add_punct(">");
+
+        // let _tlg_argN = match _tlg_argN { Some(_tlg_value) => _tlg_value, None => return ERROR_ARITHMETIC_OVERFLOW };
+        self.func_checks_tree
+            .add_ident("let")
+            .add_ident(self.arg_n.current())
+            .add_punct("=")
+            .add_ident("match")
+            .add_ident(self.arg_n.current())
+            .add_group_curly(
+                self.tree1
+                    .add_path_call(
+                        OPTION_SOME_PATH,
+                        self.tree2.add_ident(TLG_VALUE_VAR).drain(),
+                    )
+                    .add_punct("=>")
+                    .add_ident(TLG_VALUE_VAR)
+                    .add_punct(",")
+                    .add_path(OPTION_NONE_PATH)
+                    .add_punct("=>")
+                    .add_ident("return")
+                    .add_path(ERROR_ARITHMETIC_OVERFLOW_PATH)
+                    .add_punct(",")
+                    .drain(),
+            )
+            .add_punct(";");
+    }
+
     /// Prototype: , _tlg_argN: &[VALUE_TYPE]
-    /// Call site: , AsRef::<[VALUE_TYPE]>::as_ref(value_tokens...)
+    /// Call site: , AsRef::<[VALUE_TYPE]>::as_ref(&(value_tokens...))
     fn add_func_slice_arg(
         &mut self,
         field_option: &FieldOption,
         field_type_name_span: Span,
         field_value_tokens: TokenStream,
     ) {
+        let value_span = value_span(&field_value_tokens, field_type_name_span);
+
         // , _tlg_argN: &[VALUE_TYPE]
         self.func_args_tree
             .add_punct(",")
@@ -758,8 +1744,13 @@ impl EventGenerator {
         // accept &[u8] (they wouldn't be able to accept &str or &String). This also
         // applies to 3rd-party types, e.g. widestring's U16String implements
         // AsRef<[u16]> so it just works as a value for the str16 field types.
+        //
+        // The value is borrowed before calling as_ref so that owned values (e.g.
+        // Vec<T>, [T; N], String) work as well as references (&Vec<T>, &[T; N], &[T],
+        // &str). AsRef is implemented for &T when it is implemented for T, so the
+        // extra reference doesn't affect reference values.
 
-        // , AsRef::<[VALUE_TYPE]>::as_ref(value_tokens...)
+        // , AsRef::<[VALUE_TYPE]>::as_ref(&(value_tokens...))
         self.func_call_tree
             .add_punct(",")
             .push_span(field_type_name_span) // Use as_ref(...) as a target for error messages.
@@ -778,7 +1769,67 @@ impl EventGenerator {
             .add_punct(">")
             .add_punct("::")
             .add_ident("as_ref")
-            .add_group_paren(field_value_tokens)
+            .add_group_paren(
+                self.tree1
+                    .push_span(value_span) // Use the value as the target for type errors.
+                    .add_punct("&")
+                    .add_group_paren(field_value_tokens)
+                    .pop_span()
+                    .drain(),
+            )
+            .pop_span();
+    }
+
+    /// Prototype: , _tlg_argN: &[&[VALUE_TYPE]; CHUNKS_MAX]
+    /// Call site: , &str8_chunks(AsRef::<[VALUE_TYPE]>::as_ref(&(value_tokens...)))
+    fn add_func_chunks_arg(
+        &mut self,
+        field_option: &FieldOption,
+        field_type_name_span: Span,
+        field_value_tokens: TokenStream,
+    ) {
+        let value_span = value_span(&field_value_tokens, field_type_name_span);
+
+        // , _tlg_argN: &[&[VALUE_TYPE]; CHUNKS_MAX]
+        self.func_args_tree
+            .add_punct(",")
+            .add_ident(self.arg_n.current())
+            .add_punct(":")
+            .add_punct("&")
+            .add_group_square(
+                self.tree1
+                    .add_punct("&")
+                    .add_group_square(self.tree2.add_path(field_option.value_type).drain())
+                    .add_punct(";")
+                    .add_literal(Literal::u8_unsuffixed(CHUNKS_MAX))
+                    .drain(),
+            );
+
+        // , &str8_chunks(AsRef::<[VALUE_TYPE]>::as_ref(&(value_tokens...)))
+        self.func_call_tree
+            .add_punct(",")
+            .push_span(field_type_name_span) // Use as_ref(...) as a target for error messages.
+            .add_punct("&")
+            .add_path_call(
+                STR8_CHUNKS_PATH,
+                self.tree1
+                    .add_path(ASREF_PATH)
+                    .add_punct("::")
+                    .add_punct("<")
+                    .add_group_square(self.tree2.add_path(field_option.value_type).drain())
+                    .add_punct(">")
+                    .add_punct("::")
+                    .add_ident("as_ref")
+                    .add_group_paren(
+                        self.tree2
+                            .push_span(value_span) // Use the value as the target for type errors.
+                            .add_punct("&")
+                            .add_group_paren(field_value_tokens)
+                            .pop_span()
+                            .drain(),
+                    )
+                    .drain(),
+            )
             .pop_span();
     }
 
@@ -843,15 +1894,90 @@ impl EventGenerator {
         self.meta_init_tree.pop_span();
     }
 
-    fn add_tag(&mut self, expression: Expression) {
-        // Implicitly uses self.tag_const as the name for the tag's constant.
+    /// Adds `("name", InType, OutType),` to fields_tree.
+    fn add_fields_entry(&mut self, field: &FieldInfo, chunk: u8, inflags: u8, has_out: bool) {
+        // InType::from_int(INTYPE.as_int() | flags)
+        self.tree1.push_span(field.type_name_span);
+        if !field.intype_tokens.is_empty() {
+            // identity::<InType>(...).as_int()
+            self.tree1
+                .add_path(IDENTITY_PATH)
+                .add_punct("::")
+                .add_punct("<")
+                .add_path(INTYPE_PATH)
+                .add_punct(">")
+                .add_group_paren(field.intype_tokens.clone())
+                .add_punct(".")
+                .add_ident("as_int")
+                .add_group_paren([]);
+        } else {
+            match field.option.intype.to_token() {
+                EnumToken::U8(enum_int) => {
+                    self.tree1.add_literal(Literal::u8_unsuffixed(enum_int));
+                }
+                EnumToken::Str(enum_name) => {
+                    self.tree1
+                        .add_path(INTYPE_PATH)
+                        .add_punct("::")
+                        .add_ident(enum_name)
+                        .add_punct(".")
+                        .add_ident("as_int")
+                        .add_group_paren([]);
+                }
+            }
+        }
+        if inflags != 0 {
+            self.tree1
+                .add_punct("|")
+                .add_literal(Literal::u8_unsuffixed(inflags));
+        }
+        self.tree1.pop_span();
 
-        self.flush_meta_buffer();
+        self.tree2
+            // "name",
+            .add_literal(Literal::string(&chunk_name(&field.name, chunk)))
+            .add_punct(",")
+            // InType::from_int(...),
+            .add_path_call(INTYPE_FROM_INT_PATH, self.tree1.drain())
+            .add_punct(",");
+
+        // OutType
+        if !field.outtype_or_field_count_expr.is_empty() {
+            // identity::<OutType>(...)
+            self.tree2
+                .push_span(field.outtype_or_field_count_expr.context)
+                .add_path(IDENTITY_PATH)
+                .add_punct("::")
+                .add_punct("<")
+                .add_path(OUTTYPE_PATH)
+                .add_punct(">")
+                .add_group_paren(field.outtype_or_field_count_expr.tokens.clone())
+                .pop_span();
+        } else if has_out {
+            // OutType::from_int(N)
+            self.tree2.add_path_call(
+                OUTTYPE_FROM_INT_PATH,
+                [TokenTree::from(Literal::u8_unsuffixed(
+                    field.outtype_or_field_count_int,
+                ))],
+            );
+        } else {
+            // OutType::Default
+            self.tree2.add_path(OUTTYPE_DEFAULT_PATH);
+        }
+
+        self.fields_tree
+            .add_group_paren(self.tree2.drain())
+            .add_punct(",");
+    }
+
+    fn add_tag(&mut self, expression: &Expression) {
+        // Implicitly uses self.tag_const as the name for the tag's constant.
 
         // const _TLG_TAGn: u32 = TAG;
         self.tags_tree
             .push_span(expression.context)
-            .add_const_from_tokens(self.tag_n.current(), U32_PATH, expression.tokens)
+            .add_const_from_tokens(self.tag_n.current(), U32_PATH, expression.tokens.clone())
             // #[allow(clippy::assertions_on_constants)]
             .add_outer_attribute(
                 "allow",
@@ -885,6 +2011,13 @@ impl EventGenerator {
             .add_punct(";")
             .pop_span();
 
+        self.add_tag_meta(expression.context);
+    }
+
+    /// Adds the metadata for the tag in the already-declared _TLG_TAGn constant.
+    fn add_tag_meta(&mut self, context: Span) {
+        self.flush_meta_buffer();
+
         // , [u8; tag_size(_TLG_TAGn)]
         self.meta_type_tree.add_punct(",").add_group_square(
             self.tree1
@@ -893,7 +2026,7 @@ impl EventGenerator {
                 .add_path_call(
                     TAG_SIZE_PATH,
                     self.tree2
-                        .push_span(expression.context)
+                        .push_span(context)
                         .add_ident(self.tag_n.current())
                         .pop_span()
                         .drain(),
@@ -929,3 +2062,14 @@ impl EventGenerator {
         }
     }
 }
+
+/// Returns the span of the first token of the value expression, or `default_span` if
+/// the expression is empty. Slice values are passed as `as_ref(&(value_tokens...))`;
+/// giving the `&(...)` this span makes rustc report a value of the wrong type (e.g. a
+/// `u32` passed to `u32_slice`) at the value instead of at the whole macro invocation.
+fn value_span(value_tokens: &TokenStream, default_span: Span) -> Span {
+    return match value_tokens.clone().into_iter().next() {
+        Some(first) => first.span(),
+        None => default_span,
+    };
+}
@@ -3,68 +3,177 @@
 
 use proc_macro::*;
 
-use crate::enums::OutType;
+use crate::enums::{InType, OutType};
 use crate::errors::Errors;
 use crate::expression::Expression;
 use crate::field_info::FieldInfo;
-use crate::field_option::FieldStrategy;
+use crate::field_option::{chunk_name, dropped_name, FieldStrategy};
 use crate::field_options::FIELD_OPTIONS;
-use crate::parser::{ArgConstraints::*, ArgResult, Parser};
+use crate::name_style::NameStyle;
+use crate::parser::{parse_int, ArgConstraints::*, ArgResult, Parser};
+use crate::provider_info::{defaults_const_ident, is_task_name};
+use crate::raw_struct_info::{find_raw_struct_type, RawStructType};
 use crate::strings::*;
 use crate::tree::Tree;
 
-const METADATA_BYTES_MAX: u16 = u16::MAX; // TraceLogging limit
+const METADATA_BYTES_MAX: usize = u16::MAX as usize; // TraceLogging limit
 const STRUCT_FIELDS_MAX: u8 = 127; // TraceLogging limit
 const DATA_DESC_MAX: u8 = 128; // EventWrite limit
 const FIELDS_MAX: usize = 128; // TDH limit
+const TUPLE_ITEMS_MAX: usize = 12;
+const RAW_FIELD_INVALID_INTYPES: [&str; 3] = ["Invalid", "Sid", "Struct"];
+const MAP_ITEM_NAMES: [&str; 2] = ["Key", "Value"];
+const EXPECTED_NAME_STYLE: &str =
+    "expected name style (snake_case, lowerCamelCase, or UpperCamelCase), e.g. name_style(snake_case)";
+const EVENT_OPTIONS: &[&str] = &[
+    "activity_id",
+    "channel",
+    "debug",
+    "descriptor",
+    "id_version",
+    "keyword",
+    "level",
+    "name_style",
+    "opcode",
+    "related_id",
+    "tag",
+    "task",
+    "write_flags",
+];
+
+// Formats allowed by the format(...) option, by InType. Strings are OUTTYPE_ENUMS names.
+const FORMATS_STR8: &[&str] = &["Default", "Json", "NoPrint", "String", "Utf8", "Xml"];
+const FORMATS_STR16: &[&str] = &["Default", "Json", "NoPrint", "String", "Xml"];
+const FORMATS_INT8: &[&str] = &[
+    "Boolean", "Default", "Hex", "NoPrint", "Signed", "String", "Unsigned", "Utf8",
+];
+const FORMATS_INT16: &[&str] = &[
+    "Default", "Hex", "NoPrint", "Port", "Signed", "String", "Unsigned",
+];
+const FORMATS_INT32: &[&str] = &[
+    "Boolean",
+    "CodePointer",
+    "Default",
+    "HResult",
+    "Hex",
+    "IPv4",
+    "NoPrint",
+    "NtStatus",
+    "Pid",
+    "Signed",
+    "Tid",
+    "Unsigned",
+    "Win32Error",
+];
+const FORMATS_INT64: &[&str] = &[
+    "CodePointer",
+    "Default",
+    "Hex",
+    "NoPrint",
+    "Signed",
+    "Unsigned",
+];
+const FORMATS_FLOAT: &[&str] = &["Default", "NoPrint"];
+const FORMATS_BOOL32: &[&str] = &["Boolean", "Default", "Hex", "NoPrint"];
+const FORMATS_BINARY: &[&str] = &[
+    "Default",
+    "Hex",
+    "IPv6",
+    "NoPrint",
+    "Pkcs7WithTypeInfo",
+    "SocketAddress",
+];
+const FORMATS_TIME: &[&str] = &[
+    "DateTime",
+    "DateTimeCultureInsensitive",
+    "DateTimeUtc",
+    "Default",
+    "NoPrint",
+];
+const FORMATS_OPAQUE: &[&str] = &["Default", "NoPrint"];
 
 pub struct EventInfo {
-    pub provider_symbol: Ident,
+    /// Set if the provider argument is a symbol or path, e.g. `MY_PROVIDER` or
+    /// `super::MY_PROVIDER`.
+    pub provider_symbol: Option<TokenStream>,
+    /// Set if the provider argument is a `&Provider` expression, e.g. `self.provider`.
+    pub provider_expr: Expression,
     pub name: String,
     pub id_tokens: TokenStream,
+    /// Set if the provider is an expression and id_tokens is the derived id, used only
+    /// if the provider was defined with derive_event_ids.
+    pub derived_id: bool,
     pub version_tokens: TokenStream,
     pub channel_tokens: TokenStream,
     pub opcode_tokens: TokenStream,
     pub task_tokens: TokenStream,
+    /// From task("Name"). Resolved against the provider's task names in task_tokens.
+    pub task_name: Option<Literal>,
     pub level: Expression,
     pub keywords: Vec<Expression>,
     pub tag: Expression,
     pub activity_id: Expression,
     pub related_id: Expression,
+    pub write_flags: Expression,
+    /// Set if the event uses a caller-supplied `&EventDescriptor`.
+    pub descriptor: Expression,
+    /// For write_events: the `PATTERN` and `ITERATOR` of `for PATTERN in ITERATOR`.
+    pub batch: Option<(TokenStream, TokenStream)>,
     pub fields: Vec<FieldInfo>,
     pub debug: bool,
 
+    // From name_style(...). Applied to each field name as it is parsed.
+    name_style: NameStyle,
+
     // Set to 0 if we've already emitted an error message.
     data_desc_used: u8,
 
-    // Set to 0 if we've already emitted an error message.
+    // Number of TDH fields used so far (a chunked field uses more than one).
+    fields_used: usize,
+
     // Accurate except that we assume all structs have at least one field and all tags
-    // require 4 bytes.
-    estimated_metadata_bytes_used: u16,
+    // require 4 bytes. Not limited to METADATA_BYTES_MAX so that the error can report
+    // the full size.
+    estimated_metadata_bytes_used: usize,
+
+    // The item that first pushed the estimate past METADATA_BYTES_MAX, if any.
+    metadata_too_large_span: Option<Span>,
 }
 
 impl EventInfo {
+    /// If batch is true, parses write_events arguments, i.e. expects
+    /// `for PATTERN in ITERATOR` after the event name.
     pub fn try_from_tokens(
         arg_span: Span,
         arg_tokens: TokenStream,
+        batch: bool,
     ) -> Result<EventInfo, TokenStream> {
         let mut event = EventInfo {
-            provider_symbol: Ident::new("x", arg_span),
+            provider_symbol: None,
+            provider_expr: Expression::empty(arg_span),
             name: String::new(),
             id_tokens: TokenStream::new(),
+            derived_id: false,
             version_tokens: TokenStream::new(),
             channel_tokens: TokenStream::new(),
             opcode_tokens: TokenStream::new(),
             task_tokens: TokenStream::new(),
+            task_name: None,
             level: Expression::empty(arg_span),
             keywords: Vec::new(),
             tag: Expression::empty(arg_span),
             activity_id: Expression::empty(arg_span),
             related_id: Expression::empty(arg_span),
+            write_flags: Expression::empty(arg_span),
+            descriptor: Expression::empty(arg_span),
+            batch: None,
             fields: Vec::new(),
             debug: false,
+            name_style: NameStyle::Verbatim,
             data_desc_used: 2,                    // provider_meta, event_meta
             estimated_metadata_bytes_used: 2 + 4, // metadata_size + estimated event tag size
+            metadata_too_large_span: None,
+            fields_used: 0,
         };
         let mut errors = Errors::new();
         let mut root_parser = Parser::new(&mut errors, arg_span, arg_tokens);
@@ -84,11 +193,19 @@ impl EventInfo {
 
         // provider
 
-        if let Some(ident) = root_parser.next_ident(
+        // An identifier or path is a provider symbol from define_provider!, e.g.
+        // MY_PROVIDER or super::MY_PROVIDER. Anything else is an expression that
+        // evaluates to a &Provider.
+        let provider_tokens = root_parser.next_tokens(
             RequiredNotLast,
-            "expected identifier for provider symbol, e.g. MY_PROVIDER",
-        ) {
-            event.provider_symbol = ident;
+            "expected provider symbol or &Provider expression, e.g. MY_PROVIDER",
+        );
+        if let Some(first) = provider_tokens.clone().into_iter().next() {
+            if is_path(provider_tokens.clone()) {
+                event.provider_symbol = Some(provider_tokens);
+            } else {
+                event.provider_expr = Expression::new(first.span(), provider_tokens);
+            }
         }
 
         // event name
@@ -98,25 +215,107 @@ impl EventInfo {
             "expected string literal for event name, e.g. write_event!(PROVIDER, \"EventName\", ...)",
         ) {
             event.name = event_name;
-            event.add_estimated_metadata(root_parser.errors(), span, event.name.len() + 1);
+            event.add_estimated_metadata(span, event.name.len() + 1);
 
             if event.name.contains('\0') {
                 root_parser.errors().add(span, "event name must not contain '\\0'");
             }
+            check_ascii_name(root_parser.errors(), span, "event", &event.name);
+        }
+
+        // for PATTERN in ITERATOR (write_events only)
+
+        if batch {
+            const EXPECTED_FOR: &str =
+                "expected `for PATTERN in ITERATOR`, e.g. for (x, y) in items.iter()";
+            let mut for_tokens = root_parser.next_tokens(Required, EXPECTED_FOR).into_iter();
+            match for_tokens.next() {
+                Some(TokenTree::Ident(for_ident)) if for_ident.to_string() == "for" => {
+                    let mut pattern = Vec::new();
+                    let mut found_in = false;
+                    for token in for_tokens.by_ref() {
+                        if let TokenTree::Ident(ident) = &token {
+                            if ident.to_string() == "in" {
+                                found_in = true;
+                                break;
+                            }
+                        }
+                        pattern.push(token);
+                    }
+
+                    let iterator: TokenStream = for_tokens.collect();
+                    if !found_in || pattern.is_empty() || iterator.is_empty() {
+                        root_parser.errors().add(for_ident.span(), EXPECTED_FOR);
+                    } else {
+                        event.batch = Some((TokenStream::from_iter(pattern), iterator));
+                    }
+                }
+                Some(token) => root_parser.errors().add(token.span(), EXPECTED_FOR),
+                None => {} // next_tokens already reported the error.
+            }
         }
 
         // options
 
         event.parse_event_options(&mut root_parser, false, &mut scratch_tree);
 
+        // The descriptor supplies id, version, channel, level, opcode, task, and keyword.
+        if !event.descriptor.is_empty()
+            && (!event.id_tokens.is_empty()
+                || !event.channel_tokens.is_empty()
+                || !event.level.is_empty()
+                || !event.opcode_tokens.is_empty()
+                || !event.task_tokens.is_empty()
+                || event.task_name.is_some()
+                || !event.keywords.is_empty())
+        {
+            root_parser.errors().add(
+                event.descriptor.context,
+                "descriptor cannot be combined with id_version, channel, level, opcode, task, or keyword",
+            );
+        }
+
         // Set defaults for optional values
 
-        // id default: 0
-        if event.id_tokens.is_empty() {
-            event.id_tokens = scratch_tree
-                .add_literal(Literal::u16_unsuffixed(0))
-                .drain()
-                .collect();
+        // For a provider symbol, the defaults come from the provider's defaults
+        // constant, e.g. super::_TLG_DEFAULTS_PROVIDER for super::PROVIDER. For a
+        // provider expression, they are read from the provider at runtime (see
+        // EventGenerator), so they are left empty here.
+        let provider_defaults = match &event.provider_symbol {
+            Some(provider_symbol) if event.descriptor.is_empty() => {
+                let mut path: Vec<TokenTree> = provider_symbol.clone().into_iter().collect();
+                if let Some(TokenTree::Ident(ident)) = path.pop() {
+                    path.push(defaults_const_ident(&ident).into());
+                }
+                Some(path)
+            }
+            _ => None,
+        };
+
+        // id default: if DEFAULTS.derive_event_ids { event_id_from_name("EventName") } else { 0 }
+        // id default for a provider expression: event_id_from_name("EventName"), used
+        // at runtime only if the provider was defined with derive_event_ids.
+        if event.id_tokens.is_empty() && event.descriptor.is_empty() {
+            let mut id_from_name_tree = Tree::new(arg_span);
+            id_from_name_tree.add_path_call(
+                EVENT_ID_FROM_NAME_PATH,
+                [TokenTree::from(Literal::string(&event.name))],
+            );
+            if let Some(provider_defaults) = &provider_defaults {
+                event.id_tokens = scratch_tree
+                    .add_ident("if")
+                    .add_tokens(provider_defaults.clone())
+                    .add_punct(".")
+                    .add_ident("derive_event_ids")
+                    .add_group_curly(id_from_name_tree.drain())
+                    .add_ident("else")
+                    .add_group_curly([TokenTree::from(Literal::u16_unsuffixed(0))])
+                    .drain()
+                    .collect();
+            } else {
+                event.derived_id = true;
+                event.id_tokens = id_from_name_tree.drain().collect();
+            }
         }
 
         // version default: 0
@@ -135,36 +334,68 @@ impl EventInfo {
                 .collect();
         }
 
-        // level default: Level::Verbose
-        if event.level.is_empty() {
-            event.level = Expression::new(
-                arg_span,
-                scratch_tree.add_path(LEVEL_VERBOSE_PATH).drain().collect(),
-            );
-        }
+        // level, opcode, task, and keyword defaults: DEFAULTS.level, etc.
+        if let Some(provider_defaults) = &provider_defaults {
+            if event.level.is_empty() {
+                event.level = Expression::new(
+                    arg_span,
+                    scratch_tree
+                        .add_tokens(provider_defaults.clone())
+                        .add_punct(".")
+                        .add_ident("level")
+                        .drain()
+                        .collect(),
+                );
+            }
 
-        // opcode default: Opcode::Info
-        if event.opcode_tokens.is_empty() {
-            event.opcode_tokens = scratch_tree.add_path(OPCODE_INFO_PATH).drain().collect();
-        }
+            if event.opcode_tokens.is_empty() {
+                event.opcode_tokens = scratch_tree
+                    .add_tokens(provider_defaults.clone())
+                    .add_punct(".")
+                    .add_ident("opcode")
+                    .drain()
+                    .collect();
+            }
 
-        // task default: 0
-        if event.task_tokens.is_empty() {
-            event.task_tokens = scratch_tree
-                .add_literal(Literal::u16_unsuffixed(0))
-                .drain()
-                .collect();
+            if let Some(task_name) = event.task_name.take() {
+                // task("Name"): DEFAULTS.task_from_name("Name"), evaluated in a constant
+                // so that an unknown name is a compile error.
+                event.task_tokens = scratch_tree
+                    .add_tokens(provider_defaults.clone())
+                    .add_punct(".")
+                    .add_ident("task_from_name")
+                    .add_group_paren([TokenTree::from(task_name)])
+                    .drain()
+                    .collect();
+            } else if event.task_tokens.is_empty() {
+                event.task_tokens = scratch_tree
+                    .add_tokens(provider_defaults.clone())
+                    .add_punct(".")
+                    .add_ident("task")
+                    .drain()
+                    .collect();
+            }
+
+            if event.keywords.is_empty() {
+                event.keywords.push(Expression::new(
+                    arg_span,
+                    scratch_tree
+                        .add_tokens(provider_defaults.clone())
+                        .add_punct(".")
+                        .add_ident("keyword")
+                        .drain()
+                        .collect(),
+                ));
+            }
         }
 
-        // keyword default: 1u64
-        if event.keywords.is_empty() {
-            event.keywords.push(Expression::new(
-                arg_span,
-                scratch_tree
-                    .add_literal(Literal::u64_suffixed(1))
-                    .drain()
-                    .collect(),
-            ));
+        if let Some(task_name) = &event.task_name {
+            if event.provider_symbol.is_none() {
+                root_parser.errors().add(
+                    task_name.span(),
+                    "task(\"TaskName\") requires a provider symbol, e.g. MY_PROVIDER",
+                );
+            }
         }
 
         // tag default: 0
@@ -178,6 +409,16 @@ impl EventInfo {
             );
         }
 
+        if let Some(span) = event.metadata_too_large_span {
+            errors.add(
+                span,
+                &format!(
+                    "event metadata is too large (estimated {} bytes; includes event name string, field name strings, and field type codes; limit is 65535 bytes)",
+                    event.estimated_metadata_bytes_used,
+                ),
+            );
+        }
+
         // Done.
 
         return if errors.is_empty() {
@@ -195,6 +436,12 @@ impl EventInfo {
         scratch_tree: &mut Tree,
     ) -> u8 {
         let mut logical_fields_added: u8 = 0;
+        // Names used at this nesting level. Cleared after a raw_struct since the nesting
+        // of the fields after a raw_struct can't be determined at compile time.
+        let mut field_names: Option<Vec<String>> = Some(Vec::new());
+        // Open raw_structs (span, number of logical fields still expected). None if a
+        // raw_struct field count is not a literal, since it can't be checked.
+        let mut raw_structs_open: Option<Vec<(Span, u8)>> = Some(Vec::new());
 
         while let ArgResult::Option(option_ident, mut option_parser) = parent_parser.next_arg(false)
         {
@@ -213,9 +460,15 @@ impl EventInfo {
                     outtype_or_field_count_expr: Expression::empty(option_ident.span()),
                     outtype_or_field_count_int: FIELD_OPTIONS[field_option_index].outtype as u8,
                     tag: Expression::empty(option_ident.span()),
+                    struct_type_tokens: TokenStream::new(),
+                    fixed_count: 0,
+                    tuple_item: None,
                 };
+                let mut tuple_items = Vec::new();
+                let mut raw_struct_field_count = None;
 
                 let field_has_metadata = field.option.strategy.has_metadata();
+                let field_meta_count = field.option.strategy.meta_count();
 
                 if !field_has_metadata {
                     // No metadata, so don't try to parse a field name.
@@ -223,27 +476,65 @@ impl EventInfo {
                     RequiredNotLast,
                     "expected field name (must be a string literal, e.g. \"field name\")",
                 ) {
-                    field.name = field_name;
+                    field.name = self.name_style.apply(&field_name);
                     if field.name.contains('\0') {
                         option_parser
                             .errors()
                             .add(field_span, "field name must not contain '\\0'");
                     }
+                    check_ascii_name(option_parser.errors(), field_span, "field", &field.name);
+
+                    if let Some(names) = &mut field_names {
+                        let mut new_names: Vec<String> = (0..field.option.strategy.meta_count())
+                            .map(|chunk| chunk_name(&field.name, chunk))
+                            .collect();
+                        if let FieldStrategy::MapStr8 = field.option.strategy {
+                            new_names.push(dropped_name(&field.name));
+                        }
+
+                        for name in new_names {
+                            if names.contains(&name) {
+                                option_parser.errors().add(
+                                    field_span,
+                                    "duplicate field name (another field in the same event or struct has this name)",
+                                );
+                                break;
+                            } else {
+                                names.push(name);
+                            }
+                        }
+                    }
                 }
 
                 let field_accepts_tag;
                 let field_accepts_format;
                 let field_wants_struct;
+                let field_accepts_fixed_count =
+                    matches!(field.option.strategy, FieldStrategy::Slice);
 
                 match field.option.strategy {
                     FieldStrategy::Scalar
                     | FieldStrategy::SystemTime
                     | FieldStrategy::Time32
                     | FieldStrategy::Time64
+                    | FieldStrategy::SystemTimeChrono
+                    | FieldStrategy::Bool32
+                    | FieldStrategy::F16
+                    | FieldStrategy::ByteOrder
+                    | FieldStrategy::Address
                     | FieldStrategy::Sid
                     | FieldStrategy::CStr
                     | FieldStrategy::Counted
-                    | FieldStrategy::Slice => {
+                    | FieldStrategy::CountedOpt
+                    | FieldStrategy::Display
+                    | FieldStrategy::Debug
+                    | FieldStrategy::Json
+                    | FieldStrategy::Utf16
+                    | FieldStrategy::Chunked
+                    | FieldStrategy::Slice
+                    | FieldStrategy::Bool32Slice
+                    | FieldStrategy::F16Slice
+                    | FieldStrategy::AddressSlice => {
                         field_accepts_tag = true;
                         field_accepts_format = true;
                         field_wants_struct = false;
@@ -253,6 +544,43 @@ impl EventInfo {
                         field_accepts_format = false;
                         field_wants_struct = true;
                     }
+                    FieldStrategy::StructTuple => {
+                        field_accepts_tag = true;
+                        field_accepts_format = false;
+                        field_wants_struct = false;
+
+                        let tokens = option_parser.next_tokens(
+                            RequiredNotLast,
+                            "expected tuple type, e.g. (i32, i32, f64) or (x: i32, y: i32, z: f64)",
+                        );
+                        tuple_items = parse_tuple_items(
+                            option_parser.errors(),
+                            option_ident.span(),
+                            tokens,
+                            self.name_style,
+                        );
+
+                        // (TYPE0, TYPE1, ...,)
+                        for (_, span, item_type) in &tuple_items {
+                            scratch_tree
+                                .push_span(*span)
+                                .add_path(item_type.type_path)
+                                .pop_span()
+                                .add_punct(",");
+                        }
+                        let item_types: Vec<TokenTree> = scratch_tree.drain().collect();
+                        field.struct_type_tokens =
+                            scratch_tree.add_group_paren(item_types).drain().collect();
+                        field.outtype_or_field_count_int = tuple_items.len() as u8;
+                    }
+                    FieldStrategy::MapStr8 => {
+                        field_accepts_tag = true;
+                        field_accepts_format = false;
+                        field_wants_struct = false;
+
+                        // Each element is a struct with Key and Value fields.
+                        field.outtype_or_field_count_int = MAP_ITEM_NAMES.len() as u8;
+                    }
                     FieldStrategy::RawField
                     | FieldStrategy::RawFieldSlice
                     | FieldStrategy::RawMeta
@@ -261,11 +589,20 @@ impl EventInfo {
                         field_accepts_format = true;
                         field_wants_struct = false;
 
+                        let intype_tokens = option_parser
+                            .next_tokens(Required, &expected_enum_message("InType", "Bool32", 13));
+                        if matches!(
+                            field.option.strategy,
+                            FieldStrategy::RawField | FieldStrategy::RawFieldSlice
+                        ) {
+                            check_raw_field_intype(
+                                option_parser.errors(),
+                                &option_name,
+                                &intype_tokens,
+                            );
+                        }
                         field.intype_tokens = filter_enum_tokens(
-                            option_parser.next_tokens(
-                                Required,
-                                &expected_enum_message("InType", "Bool32", 13),
-                            ),
+                            intype_tokens,
                             "InType",
                             INTYPE_ENUMS,
                             option_ident.span(),
@@ -283,8 +620,14 @@ impl EventInfo {
                                 .add(option_ident.span(), "RawStruct not allowed within Struct");
                         }
 
+                        field_names = None;
+
                         let tokens = option_parser
                             .next_tokens(Required, "expected struct field count value, e.g. 2");
+                        raw_struct_field_count = literal_int(&tokens);
+                        if raw_struct_field_count.is_none() {
+                            raw_structs_open = None;
+                        }
                         field.outtype_or_field_count_expr = Expression::new(
                             option_ident.span(),
                             scratch_tree
@@ -295,6 +638,37 @@ impl EventInfo {
                                 .collect(),
                         );
                     }
+                    FieldStrategy::RawStructValue => {
+                        field_accepts_tag = true;
+                        field_accepts_format = false;
+                        field_wants_struct = false;
+
+                        field.struct_type_tokens = option_parser
+                            .next_tokens(RequiredNotLast, "expected RawStruct type, e.g. MyStruct");
+
+                        // OutType::from_int(<TYPE as RawStruct>::FIELD_COUNT)
+                        let field_count_tokens = scratch_tree
+                            .push_span(option_ident.span())
+                            .add_punct("<")
+                            .add_tokens(field.struct_type_tokens.clone())
+                            .add_ident("as")
+                            .add_path(RAW_STRUCT_PATH)
+                            .add_punct(">")
+                            .add_punct("::")
+                            .add_ident("FIELD_COUNT")
+                            .pop_span()
+                            .drain()
+                            .collect::<TokenStream>();
+                        field.outtype_or_field_count_expr = Expression::new(
+                            option_ident.span(),
+                            scratch_tree
+                                .push_span(option_ident.span())
+                                .add_path_call(OUTTYPE_FROM_INT_PATH, field_count_tokens)
+                                .pop_span()
+                                .drain()
+                                .collect(),
+                        );
+                    }
                     FieldStrategy::RawData => {
                         field_accepts_tag = false;
                         field_accepts_format = false;
@@ -302,7 +676,9 @@ impl EventInfo {
                     }
                 }
 
-                if field.option.strategy.data_count() != 0 {
+                if field.option.strategy.data_count() != 0
+                    || matches!(field.option.strategy, FieldStrategy::StructTuple)
+                {
                     field.value_tokens =
                         option_parser.next_tokens(Required, "expected field value");
                 }
@@ -310,7 +686,99 @@ impl EventInfo {
                 loop {
                     match option_parser.next_arg(field_wants_struct) {
                         ArgResult::None => {
+                            let span = field.type_name_span;
+                            let strategy = field.option.strategy;
+                            let field_name = field.name.clone();
                             self.push_field(option_parser.errors(), field);
+
+                            // Each tuple element is a scalar field within the struct.
+                            for (index, (name, _, item_type)) in tuple_items.drain(..).enumerate() {
+                                let option_name = match item_type.type_name {
+                                    "bool" => "bool8",
+                                    "Guid" => "guid",
+                                    type_name => type_name,
+                                };
+                                let option_index = FIELD_OPTIONS
+                                    .binary_search_by(|o| o.option_name.cmp(option_name))
+                                    .unwrap();
+                                self.push_field(
+                                    option_parser.errors(),
+                                    FieldInfo {
+                                        type_name_span: span,
+                                        option: &FIELD_OPTIONS[option_index],
+                                        name,
+                                        value_tokens: TokenStream::new(),
+                                        intype_tokens: TokenStream::new(),
+                                        outtype_or_field_count_expr: Expression::empty(span),
+                                        outtype_or_field_count_int: FIELD_OPTIONS[option_index]
+                                            .outtype
+                                            as u8,
+                                        tag: Expression::empty(span),
+                                        struct_type_tokens: TokenStream::new(),
+                                        fixed_count: 0,
+                                        tuple_item: Some(index as u8),
+                                    },
+                                );
+                            }
+
+                            // The map's Key and Value fields have metadata only. Their data
+                            // is included in the map field's data.
+                            if let FieldStrategy::MapStr8 = strategy {
+                                let raw_meta_index = FIELD_OPTIONS
+                                    .binary_search_by(|o| o.option_name.cmp("raw_meta"))
+                                    .unwrap();
+                                for name in MAP_ITEM_NAMES {
+                                    self.push_field(
+                                        option_parser.errors(),
+                                        FieldInfo {
+                                            type_name_span: span,
+                                            option: &FIELD_OPTIONS[raw_meta_index],
+                                            name: self.name_style.apply(name),
+                                            value_tokens: TokenStream::new(),
+                                            intype_tokens: scratch_tree
+                                                .push_span(span)
+                                                .add_path(INTYPE_PATH)
+                                                .add_punct("::")
+                                                .add_ident("Str8")
+                                                .pop_span()
+                                                .drain()
+                                                .collect(),
+                                            outtype_or_field_count_expr: Expression::empty(span),
+                                            outtype_or_field_count_int: OutType::Utf8 as u8,
+                                            tag: Expression::empty(span),
+                                            struct_type_tokens: TokenStream::new(),
+                                            fixed_count: 0,
+                                            tuple_item: None,
+                                        },
+                                    );
+                                }
+
+                                // The dropped count follows the map's Key and Value fields.
+                                // Its data follows the map field's data.
+                                self.push_field(
+                                    option_parser.errors(),
+                                    FieldInfo {
+                                        type_name_span: span,
+                                        option: &FIELD_OPTIONS[raw_meta_index],
+                                        name: dropped_name(&field_name),
+                                        value_tokens: TokenStream::new(),
+                                        intype_tokens: scratch_tree
+                                            .push_span(span)
+                                            .add_path(INTYPE_PATH)
+                                            .add_punct("::")
+                                            .add_ident("U16")
+                                            .pop_span()
+                                            .drain()
+                                            .collect(),
+                                        outtype_or_field_count_expr: Expression::empty(span),
+                                        outtype_or_field_count_int: 0,
+                                        tag: Expression::empty(span),
+                                        struct_type_tokens: TokenStream::new(),
+                                        fixed_count: 0,
+                                        tuple_item: None,
+                                    },
+                                );
+                            }
                             break;
                         }
                         ArgResult::Struct(mut struct_parser) => {
@@ -346,13 +814,22 @@ impl EventInfo {
                                     if !field.outtype_or_field_count_expr.is_empty() {
                                         errors.add(field_option_ident.span(), "format already set");
                                     }
+                                    let format_tokens = field_option_parser.next_tokens(
+                                        RequiredLast,
+                                        &expected_enum_message("OutType", "String", 2),
+                                    );
+                                    if !field.option.strategy.is_raw() {
+                                        check_format_outtype(
+                                            field_option_parser.errors(),
+                                            field.option.option_name,
+                                            field.option.intype,
+                                            &format_tokens,
+                                        );
+                                    }
                                     field.outtype_or_field_count_expr = Expression::new(
                                         field_option_ident.span(),
                                         filter_enum_tokens(
-                                            field_option_parser.next_tokens(
-                                                RequiredLast,
-                                                &expected_enum_message("OutType", "String", 2),
-                                            ),
+                                            format_tokens,
                                             "OutType",
                                             OUTTYPE_ENUMS,
                                             field_option_ident.span(),
@@ -360,8 +837,56 @@ impl EventInfo {
                                         ),
                                     );
                                 }
+                                "format" | "tag"
+                                    if matches!(field.option.strategy, FieldStrategy::RawData) =>
+                                {
+                                    // raw_data has no metadata, and one raw_data may supply the
+                                    // data for any number of fields (or part of one), so there is
+                                    // no single field to apply the option to.
+                                    errors.add(
+                                        field_option_ident.span(),
+                                        &format!(
+                                            "raw_data has no metadata; put {}(...) on the raw_meta field that declares the data's type",
+                                            field_option_name,
+                                        ),
+                                    );
+                                }
+                                "fixed_count" if field_accepts_fixed_count => {
+                                    if field.fixed_count != 0 {
+                                        errors.add(
+                                            field_option_ident.span(),
+                                            "fixed_count already set",
+                                        );
+                                    }
+                                    if let Some((count, count_span)) = field_option_parser
+                                        .next_int_literal(
+                                            RequiredLast,
+                                            "expected fixed_count value (integer literal), e.g. 6",
+                                        )
+                                    {
+                                        if count == 0 || count > 65535 {
+                                            field_option_parser.errors().add(
+                                                count_span,
+                                                "fixed_count must be in the range 1 to 65535",
+                                            );
+                                        } else {
+                                            field.fixed_count = count as u16;
+                                        }
+                                    }
+                                }
                                 _ => {
-                                    errors.add(field_option_ident.span(), "unrecognized option");
+                                    let known = [
+                                        ("fixed_count", field_accepts_fixed_count),
+                                        ("format", field_accepts_format),
+                                        ("tag", field_accepts_tag),
+                                    ];
+                                    errors.add(
+                                        field_option_ident.span(),
+                                        &unrecognized_option_message(
+                                            &field_option_name,
+                                            known.iter().filter(|k| k.1).map(|k| k.0),
+                                        ),
+                                    );
                                 }
                             }
                         }
@@ -369,13 +894,34 @@ impl EventInfo {
                 }
 
                 if field_has_metadata {
-                    if in_struct && logical_fields_added == STRUCT_FIELDS_MAX {
+                    if in_struct
+                        && logical_fields_added <= STRUCT_FIELDS_MAX
+                        && logical_fields_added as usize + field_meta_count as usize
+                            > STRUCT_FIELDS_MAX as usize
+                    {
                         option_parser
                             .errors()
                             .add(option_ident.span(), "too many fields in struct (limit 127)");
                     }
 
-                    logical_fields_added = logical_fields_added.saturating_add(1);
+                    logical_fields_added = logical_fields_added.saturating_add(field_meta_count);
+
+                    if let Some(open) = &mut raw_structs_open {
+                        for _ in 0..field_meta_count {
+                            if let Some(innermost) = open.last_mut() {
+                                innermost.1 -= 1;
+                                if innermost.1 == 0 {
+                                    open.pop();
+                                }
+                            }
+                        }
+
+                        if let Some(count) = raw_struct_field_count {
+                            if count != 0 && count <= STRUCT_FIELDS_MAX as u64 {
+                                open.push((option_ident.span(), count as u8));
+                            }
+                        }
+                    }
                 }
             } else {
                 match option_name.as_str() {
@@ -387,10 +933,12 @@ impl EventInfo {
                         if !self.id_tokens.is_empty() {
                             errors.add(option_ident.span(), "id_version already set");
                         }
-                        self.id_tokens = option_parser
-                            .next_tokens(RequiredNotLast, "expected Id value, e.g. 1 or 0x200F");
-                        self.version_tokens = option_parser
-                            .next_tokens(RequiredLast, "expected Version value, e.g. 0 or 0x1F");
+                        const EXPECTED_ID_VERSION: &str =
+                            "id_version requires both an id and a version, e.g. id_version(5, 0)";
+                        self.id_tokens =
+                            option_parser.next_tokens(RequiredNotLast, EXPECTED_ID_VERSION);
+                        self.version_tokens =
+                            option_parser.next_tokens(RequiredLast, EXPECTED_ID_VERSION);
                     }
                     "channel" if !in_struct => {
                         if !self.channel_tokens.is_empty() {
@@ -441,19 +989,65 @@ impl EventInfo {
                         );
                     }
                     "task" if !in_struct => {
-                        if !self.task_tokens.is_empty() {
+                        if !self.task_tokens.is_empty() || self.task_name.is_some() {
                             errors.add(option_ident.span(), "task already set");
                         }
-                        self.task_tokens = option_parser
-                            .next_tokens(RequiredLast, "expected Task value, e.g. 1 or 0x2001");
+                        self.task_tokens = option_parser.next_tokens(
+                            RequiredLast,
+                            "expected Task value, e.g. 1, 0x2001, or \"TaskName\"",
+                        );
+                        let mut task_iter = self.task_tokens.clone().into_iter();
+                        if let (Some(TokenTree::Literal(literal)), None) =
+                            (task_iter.next(), task_iter.next())
+                        {
+                            // task("Name") refers to define_provider's task_name(TASK, "Name").
+                            let lit_str = literal.to_string();
+                            if lit_str.len() >= 2 && lit_str.starts_with('"') {
+                                let name = &lit_str[1..lit_str.len() - 1];
+                                let span = literal.span();
+                                if !is_task_name(name) {
+                                    option_parser.errors().add(
+                                        span,
+                                        "task name must be a valid identifier, e.g. \"Download\"",
+                                    );
+                                } else {
+                                    self.task_name = Some(literal);
+                                    self.task_tokens = TokenStream::new();
+                                }
+                            }
+                        }
                     }
                     "keyword" if !in_struct => {
+                        // Any constant u64 expression, e.g. 0x100F or MyKeywords::NETWORK.
                         self.keywords.push(Expression::new(
                             option_ident.span(),
-                            option_parser
-                                .next_tokens(RequiredLast, "expected Keyword value, e.g. 0x100F"),
+                            option_parser.next_tokens(
+                                RequiredLast,
+                                "expected Keyword value, e.g. 0x100F or MyKeywords::NETWORK",
+                            ),
                         ));
                     }
+                    "name_style" if !in_struct => {
+                        if self.name_style != NameStyle::Verbatim {
+                            errors.add(option_ident.span(), "name_style already set");
+                        }
+                        if !self.fields.is_empty() {
+                            errors.add(
+                                option_ident.span(),
+                                "name_style must come before the event's fields",
+                            );
+                        }
+                        if let Some(style_ident) =
+                            option_parser.next_ident(RequiredLast, EXPECTED_NAME_STYLE)
+                        {
+                            match NameStyle::from_name(&style_ident.to_string()) {
+                                Some(style) => self.name_style = style,
+                                None => option_parser
+                                    .errors()
+                                    .add(style_ident.span(), EXPECTED_NAME_STYLE),
+                            }
+                        }
+                    }
                     "tag" if !in_struct => {
                         if !self.tag.is_empty() {
                             errors.add(option_ident.span(), "tag already set");
@@ -485,41 +1079,107 @@ impl EventInfo {
                             option_parser.next_tokens(RequiredLast, "expected Related Id variable"),
                         );
                     }
+                    "descriptor" if !in_struct => {
+                        if !self.descriptor.is_empty() {
+                            errors.add(option_ident.span(), "descriptor already set");
+                        }
+                        self.descriptor = Expression::new(
+                            option_ident.span(),
+                            option_parser
+                                .next_tokens(RequiredLast, "expected &EventDescriptor expression"),
+                        );
+                    }
+                    "write_flags" if !in_struct => {
+                        if !self.write_flags.is_empty() {
+                            errors.add(option_ident.span(), "write_flags already set");
+                        }
+                        self.write_flags = Expression::new(
+                            option_ident.span(),
+                            option_parser.next_tokens(
+                                RequiredLast,
+                                "expected EventWriteEx flags value, e.g. 0x2",
+                            ),
+                        );
+                    }
                     _ => {
-                        errors.add(option_ident.span(), "unrecognized option");
+                        // Event options are not valid in a struct, so only suggest them at
+                        // the top level.
+                        let event_options = if in_struct { &[][..] } else { EVENT_OPTIONS };
+                        errors.add(
+                            option_ident.span(),
+                            &unrecognized_option_message(
+                                &option_name,
+                                event_options
+                                    .iter()
+                                    .copied()
+                                    .chain(FIELD_OPTIONS.iter().map(|o| o.option_name)),
+                            ),
+                        );
                         continue;
                     }
                 }
             }
         }
 
+        if let Some(open) = raw_structs_open {
+            for (span, missing) in open {
+                parent_parser.errors().add(
+                    span,
+                    &format!(
+                        "raw_struct FIELD_COUNT is larger than the number of fields that follow it ({} missing)",
+                        missing
+                    ),
+                );
+            }
+        }
+
         return logical_fields_added;
     }
 
     fn push_field(&mut self, errors: &mut Errors, field: FieldInfo) {
-        let metadata_size = field.name.len()
-            + 1 // name nul-termination
-            + if !field.tag.is_empty() {
-                6 // intype + outtype + tag
-            } else if field.outtype_or_field_count_int != 0 {
-                2 // intype + outtype
-            } else {
-                1 // intype
-            };
-        self.add_estimated_metadata(errors, field.type_name_span, metadata_size);
+        let meta_count = field.option.strategy.meta_count();
+        let metadata_size = (0..meta_count)
+            .map(|chunk| {
+                chunk_name(&field.name, chunk).len()
+                    + 1 // name nul-termination
+                    + if !field.tag.is_empty() {
+                        6 // intype + outtype + tag
+                    } else if field.outtype_or_field_count_int != 0 {
+                        2 // intype + outtype
+                    } else {
+                        1 // intype
+                    }
+                    + if field.fixed_count != 0 {
+                        2 // count
+                    } else {
+                        0
+                    }
+            })
+            .sum();
+        self.add_estimated_metadata(field.type_name_span, metadata_size);
         self.add_data_desc_used(
             errors,
             field.type_name_span,
-            field.option.strategy.data_count(),
+            if field.fixed_count != 0 {
+                1 // Fixed-length array has no length block.
+            } else {
+                field.option.strategy.data_count()
+            },
         );
 
-        if self.fields.len() == FIELDS_MAX {
+        let fields_count = if meta_count == 0 {
+            1
+        } else {
+            meta_count as usize
+        };
+        if self.fields_used <= FIELDS_MAX && self.fields_used + fields_count > FIELDS_MAX {
             errors.add(
                 field.type_name_span,
                 "event has too many fields (limit is 128 fields)",
             );
         }
 
+        self.fields_used += fields_count;
         self.fields.push(field);
     }
 
@@ -536,21 +1196,205 @@ impl EventInfo {
         }
     }
 
-    fn add_estimated_metadata(&mut self, errors: &mut Errors, span: Span, size: usize) {
-        if self.estimated_metadata_bytes_used == 0 {
-            // Already emitted an error for this. Don't emit another.
-        } else if (METADATA_BYTES_MAX - self.estimated_metadata_bytes_used) as usize >= size {
-            self.estimated_metadata_bytes_used += size as u16;
+    /// The error (if any) is reported after all fields are parsed so that it can include
+    /// the event's total estimated size.
+    fn add_estimated_metadata(&mut self, span: Span, size: usize) {
+        self.estimated_metadata_bytes_used =
+            self.estimated_metadata_bytes_used.saturating_add(size);
+        if self.estimated_metadata_bytes_used > METADATA_BYTES_MAX
+            && self.metadata_too_large_span.is_none()
+        {
+            self.metadata_too_large_span = Some(span);
+        }
+    }
+}
+
+/// Parses the element types of a struct_tuple field, e.g. `(i32, i32, f64)` or
+/// `(x: i32, y: i32, z: f64)`. Returns the name, span, and type of each element.
+/// Unnamed elements are named `item0`, `item1`, etc.
+fn parse_tuple_items(
+    errors: &mut Errors,
+    option_span: Span,
+    tokens: TokenStream,
+    name_style: NameStyle,
+) -> Vec<(String, Span, &'static RawStructType)> {
+    let mut items = Vec::new();
+
+    let mut tokens_iter = tokens.into_iter();
+    let group = match (tokens_iter.next(), tokens_iter.next()) {
+        (Some(TokenTree::Group(group)), None) if group.delimiter() == Delimiter::Parenthesis => {
+            group
+        }
+        (first, _) => {
+            errors.add(
+                first.map_or(option_span, |token| token.span()),
+                "expected tuple type, e.g. (i32, i32, f64) or (x: i32, y: i32, z: f64)",
+            );
+            return items;
+        }
+    };
+
+    let mut names = Vec::new();
+    let mut item_tokens = Vec::new();
+    let mut group_tokens = group.stream().into_iter().peekable();
+    loop {
+        let token = group_tokens.next();
+        match &token {
+            Some(TokenTree::Punct(punct)) if punct.as_char() == ',' => {}
+            Some(token) => {
+                item_tokens.push(token.clone());
+                continue;
+            }
+            None if item_tokens.is_empty() => break, // Trailing comma or empty tuple.
+            None => {}
+        }
+
+        let span = item_tokens
+            .first()
+            .map_or(group.span(), |token| token.span());
+
+        // name: TYPE (single ':', not the '::' of a path)
+        let (name, type_tokens) = match (item_tokens.first(), item_tokens.get(1)) {
+            (Some(TokenTree::Ident(ident)), Some(TokenTree::Punct(punct)))
+                if punct.as_char() == ':' && punct.spacing() == Spacing::Alone =>
+            {
+                (name_style.apply(&ident.to_string()), &item_tokens[2..])
+            }
+            _ => (format!("item{}", items.len()), &item_tokens[..]),
+        };
+
+        if names.contains(&name) {
+            errors.add(
+                span,
+                "duplicate field name (another tuple element has this name)",
+            );
+        }
+
+        if let Some(item_type) = find_raw_struct_type(type_tokens) {
+            items.push((name.clone(), span, item_type));
         } else {
-            self.estimated_metadata_bytes_used = 0; // Don't give any additional size errors.
             errors.add(
                 span,
-                "event metadata is too large (includes event name string, field name strings, and field type codes; limit is 65535 bytes)");
+                "unsupported struct_tuple element type (supported types: bool, f32, f64, i8, i16, i32, i64, isize, u8, u16, u32, u64, usize, Guid)",
+            );
+        }
+
+        names.push(name);
+        item_tokens.clear();
+        if token.is_none() {
+            break;
+        }
+    }
+
+    if names.is_empty() {
+        errors.add(group.span(), "struct_tuple requires at least one element");
+    } else if names.len() > TUPLE_ITEMS_MAX {
+        errors.add(group.span(), "too many elements in struct_tuple (limit 12)");
+    }
+
+    return items;
+}
+
+/// If the "ascii_names" feature is enabled, reports an error if name contains
+/// non-ASCII characters. Otherwise, any UTF-8 name (without '\0') is allowed.
+pub fn check_ascii_name(errors: &mut Errors, span: Span, name_kind: &str, name: &str) {
+    if cfg!(feature = "ascii_names") && !name.is_ascii() {
+        errors.add(
+            span,
+            &format!(
+                "{} name must contain only ASCII characters (\"ascii_names\" feature is enabled)",
+                name_kind
+            ),
+        );
+    }
+}
+
+/// Returns `unrecognized option "NAME"`, followed by `; did you mean "KNOWN"?` if one
+/// of the known option names is a likely misspelling of NAME (small edit distance).
+pub fn unrecognized_option_message<'a>(
+    name: &str,
+    known_names: impl Iterator<Item = &'a str>,
+) -> String {
+    // Allow about one edit per 3 characters, e.g. "keywords" -> "keyword" (1 edit) or
+    // "levle" -> "level" (2 edits), but not "x" -> "id".
+    let max_distance = (name.len() / 3).max(1);
+    let mut best: Option<(usize, &str)> = None;
+    for known_name in known_names {
+        let distance = edit_distance(name, known_name);
+        if distance <= max_distance && best.map_or(true, |b| distance < b.0) {
+            best = Some((distance, known_name));
+        }
+    }
+
+    return match best {
+        Some((_, suggestion)) => format!(
+            "unrecognized option \"{}\"; did you mean \"{}\"?",
+            name, suggestion
+        ),
+        None => format!("unrecognized option \"{}\"", name),
+    };
+}
+
+/// Returns the Levenshtein distance between a and b (counting chars).
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, b_char) in b.iter().enumerate() {
+            let substitute = diagonal + if a_char == *b_char { 0 } else { 1 };
+            diagonal = row[j + 1];
+            row[j + 1] = substitute.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    return row[b.len()];
+}
+
+/// Returns the value of tokens if they are a single integer literal, e.g. `2`.
+/// Returns true if tokens is a path such as `MY_PROVIDER`, `super::MY_PROVIDER`, or
+/// `::my_crate::MY_PROVIDER`.
+fn is_path(tokens: TokenStream) -> bool {
+    let mut expect_ident = true;
+    let mut iter = tokens.into_iter().peekable();
+
+    // Optional leading "::".
+    if let Some(TokenTree::Punct(punct)) = iter.peek() {
+        if punct.as_char() != ':' {
+            return false;
+        }
+        expect_ident = false;
+    }
+
+    while let Some(token) = iter.next() {
+        match token {
+            TokenTree::Ident(_) if expect_ident => expect_ident = false,
+            TokenTree::Punct(first) if !expect_ident && first.as_char() == ':' => {
+                match iter.next() {
+                    Some(TokenTree::Punct(second))
+                        if first.spacing() == Spacing::Joint && second.as_char() == ':' =>
+                    {
+                        expect_ident = true;
+                    }
+                    _ => return false,
+                }
+            }
+            _ => return false,
         }
     }
+
+    return !expect_ident;
 }
 
-fn expected_enum_message(
+fn literal_int(tokens: &TokenStream) -> Option<u64> {
+    let mut iter = tokens.clone().into_iter();
+    return match (iter.next(), iter.next()) {
+        (Some(TokenTree::Literal(literal)), None) => parse_int(&literal.to_string()),
+        _ => None,
+    };
+}
+
+pub fn expected_enum_message(
     enum_name: &str,
     suggested_string_value: &str,
     suggested_integer_value: u8,
@@ -561,7 +1405,95 @@ fn expected_enum_message(
     );
 }
 
-fn filter_enum_tokens(
+/// Reports an error if `tokens` names an InType that is only valid for field types
+/// with special handling, e.g. `Struct` (use raw_struct) or `Sid` (use win_sid).
+/// Only checks `NAME` or `...InType::NAME`. Other expressions are not checked.
+fn check_raw_field_intype(errors: &mut Errors, option_name: &str, tokens: &TokenStream) {
+    let trees: Vec<TokenTree> = tokens.clone().into_iter().collect();
+    if let Some((TokenTree::Ident(ident), prefix)) = trees.split_last() {
+        let prefix: String = prefix.iter().map(|t| t.to_string()).collect();
+        let name = ident.to_string();
+        if (prefix.is_empty() || prefix.ends_with("InType::"))
+            && RAW_FIELD_INVALID_INTYPES.contains(&name.as_str())
+        {
+            let allowed: Vec<&str> = INTYPE_ENUMS
+                .iter()
+                .copied()
+                .filter(|value| !RAW_FIELD_INVALID_INTYPES.contains(value))
+                .collect();
+            errors.add(
+                ident.span(),
+                &format!(
+                    "InType::{} is not allowed in {} (allowed values: {})",
+                    name,
+                    option_name,
+                    allowed.join(", "),
+                ),
+            );
+        }
+    }
+}
+
+/// Returns the formats that make sense for a field with the given InType, or None
+/// if the InType does not restrict the format.
+fn allowed_formats(intype: InType) -> Option<&'static [&'static str]> {
+    return match intype {
+        InType::CStr8 | InType::Str8 => Some(FORMATS_STR8),
+        InType::CStr16 | InType::Str16 => Some(FORMATS_STR16),
+        InType::I8 | InType::U8 => Some(FORMATS_INT8),
+        InType::I16 | InType::U16 => Some(FORMATS_INT16),
+        InType::I32 | InType::U32 | InType::Hex32 => Some(FORMATS_INT32),
+        InType::I64
+        | InType::U64
+        | InType::Hex64
+        | InType::ISize
+        | InType::USize
+        | InType::HexSize => Some(FORMATS_INT64),
+        InType::F32 | InType::F64 => Some(FORMATS_FLOAT),
+        InType::Bool32 => Some(FORMATS_BOOL32),
+        InType::Binary | InType::BinaryC => Some(FORMATS_BINARY),
+        InType::FileTime | InType::SystemTime => Some(FORMATS_TIME),
+        InType::Guid | InType::Sid => Some(FORMATS_OPAQUE),
+        InType::Invalid | InType::_HexSizePlatformSpecific | InType::Struct => None,
+    };
+}
+
+/// Reports an error if the format(...) tokens name a known OutType that does not make
+/// sense for the field's InType. Values that are not a known OutType name (e.g.
+/// `OutType::from_int(2)` or a constant) are not checked.
+fn check_format_outtype(
+    errors: &mut Errors,
+    option_name: &str,
+    intype: InType,
+    tokens: &TokenStream,
+) {
+    let allowed = match allowed_formats(intype) {
+        Some(allowed) => allowed,
+        None => return,
+    };
+
+    let trees: Vec<TokenTree> = tokens.clone().into_iter().collect();
+    if let Some((TokenTree::Ident(ident), prefix)) = trees.split_last() {
+        let prefix: String = prefix.iter().map(|t| t.to_string()).collect();
+        let name = ident.to_string();
+        if (prefix.is_empty() || prefix.ends_with("OutType::"))
+            && OUTTYPE_ENUMS.binary_search(&name.as_str()).is_ok()
+            && !allowed.contains(&name.as_str())
+        {
+            errors.add(
+                ident.span(),
+                &format!(
+                    "format(OutType::{}) is not valid for {} (allowed values: {})",
+                    name,
+                    option_name,
+                    allowed.join(", "),
+                ),
+            );
+        }
+    }
+}
+
+pub fn filter_enum_tokens(
     tokens: TokenStream,
     enum_name: &str,
     known_values: &[&str],
@@ -15,4 +15,7 @@ pub struct FieldInfo {
     pub outtype_or_field_count_expr: Expression, // If empty, use outtype_or_field_count_int
     pub outtype_or_field_count_int: u8, // Use only if outtype_or_field_count_expr is empty
     pub tag: Expression,
+    pub struct_type_tokens: TokenStream, // For RawStructValue: the RawStruct type.
+    pub fixed_count: u16, // For Slice: if nonzero, the field is a fixed-length array.
+    pub tuple_item: Option<u8>, // For struct_tuple elements: index within the tuple value.
 }
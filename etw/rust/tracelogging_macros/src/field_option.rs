@@ -3,6 +3,10 @@
 
 use crate::enums::{InType, OutType};
 
+/// Number of fields generated for a `str8_chunked` value. Must match the length of the
+/// array returned by `_internal::str8_chunks`.
+pub const CHUNKS_MAX: u8 = 4;
+
 #[derive(Clone, Copy)]
 pub enum FieldStrategy {
     /// meta = scalar; data = from_value
@@ -13,20 +17,55 @@ pub enum FieldStrategy {
     Time32,
     /// meta = scalar; data = from_value(filetime_from_time64)
     Time64,
+    /// meta = scalar; data = from_value(systemtime_words_from_naive)
+    SystemTimeChrono,
+    /// meta = scalar; data = from_value(bool32_from_bool)
+    Bool32,
+    /// meta = array; data = slice_count + from_slice(Bool32Array), adds bit to intype.
+    Bool32Slice,
+    /// meta = scalar; data = from_value(f32_from_f16)
+    F16,
+    /// meta = array; data = slice_count + from_slice(F32Array), adds bit to intype.
+    F16Slice,
+    /// meta = scalar; data = counted_size + from_counted(uNN_xe_bytes)
+    ByteOrder,
+    /// meta = scalar; data = from_value(address_from_pointer)
+    Address,
+    /// meta = array; data = slice_count + from_slice(addresses_from_pointers), adds bit to intype.
+    AddressSlice,
     /// meta = scalar; data = from_sid
     Sid,
     /// meta = scalar; data = from_cstr + nul
     CStr,
     /// meta = scalar; data = counted_size + from_counted
     Counted,
+    /// meta = scalar; data = counted_size + from_counted(Option::unwrap_or_default)
+    CountedOpt,
+    /// meta = scalar; data = counted_size + from_counted(FmtBuffer::from_display)
+    Display,
+    /// meta = scalar; data = counted_size + from_counted(FmtBuffer::from_debug)
+    Debug,
+    /// meta = scalar; data = counted_size + from_counted(to_json_bytes)
+    Json,
+    /// meta = scalar; data = counted_size + from_counted(Utf16Buffer::new)
+    Utf16,
     /// meta = array; data = slice_count + from_slice, adds bit to intype.
     Slice,
+    /// meta = scalar * CHUNKS_MAX; data = (counted_size + from_counted) * CHUNKS_MAX
+    Chunked,
     /// meta = scalar; data = none
     Struct,
+    /// meta = scalar; data = none (each element is a separate field with tuple_item set)
+    StructTuple,
+    /// meta = array (struct of Key, Value) + u16 name_dropped;
+    /// data = from_counted(MapStr8Buffer::as_bytes) + from_value(MapStr8Buffer::dropped)
+    MapStr8,
     /// meta = scalar; data = none
     RawStruct,
     /// meta = array; data = none
     RawStructSlice,
+    /// meta = scalar + RawStruct::RAW_META; data = from_counted(raw_data)
+    RawStructValue,
     /// meta = none; data = from_slice
     RawData,
     /// meta = scalar; data = from_slice
@@ -46,29 +85,67 @@ impl FieldStrategy {
             | FieldStrategy::SystemTime
             | FieldStrategy::Time32
             | FieldStrategy::Time64
+            | FieldStrategy::SystemTimeChrono
+            | FieldStrategy::Bool32
+            | FieldStrategy::F16
+            | FieldStrategy::ByteOrder
+            | FieldStrategy::Address
             | FieldStrategy::Sid
             | FieldStrategy::CStr
             | FieldStrategy::Counted
+            | FieldStrategy::CountedOpt
+            | FieldStrategy::Display
+            | FieldStrategy::Debug
+            | FieldStrategy::Json
+            | FieldStrategy::Utf16
+            | FieldStrategy::Chunked
             | FieldStrategy::Struct
+            | FieldStrategy::StructTuple
             | FieldStrategy::RawStruct
+            | FieldStrategy::RawStructValue
             | FieldStrategy::RawData
             | FieldStrategy::RawField
             | FieldStrategy::RawMeta => false,
 
             FieldStrategy::Slice
+            | FieldStrategy::Bool32Slice
+            | FieldStrategy::F16Slice
+            | FieldStrategy::AddressSlice
+            | FieldStrategy::MapStr8
             | FieldStrategy::RawStructSlice
             | FieldStrategy::RawFieldSlice
             | FieldStrategy::RawMetaSlice => true,
         }
     }
 
+    /// True for the raw_* strategies, which accept any intype/outtype combination.
+    pub const fn is_raw(self) -> bool {
+        return matches!(
+            self,
+            FieldStrategy::RawField
+                | FieldStrategy::RawFieldSlice
+                | FieldStrategy::RawMeta
+                | FieldStrategy::RawMetaSlice
+        );
+    }
+
     pub const fn has_metadata(self) -> bool {
         return !matches!(self, FieldStrategy::RawData);
     }
 
+    /// Number of logical fields (metadata entries) generated for the field.
+    pub const fn meta_count(self) -> u8 {
+        match self {
+            FieldStrategy::RawData => 0,
+            FieldStrategy::Chunked => CHUNKS_MAX,
+            _ => 1,
+        }
+    }
+
     pub const fn data_count(self) -> u8 {
         match self {
             FieldStrategy::Struct
+            | FieldStrategy::StructTuple
             | FieldStrategy::RawStruct
             | FieldStrategy::RawStructSlice
             | FieldStrategy::RawMeta
@@ -78,14 +155,31 @@ impl FieldStrategy {
             | FieldStrategy::SystemTime
             | FieldStrategy::Time32
             | FieldStrategy::Time64
+            | FieldStrategy::SystemTimeChrono
+            | FieldStrategy::Bool32
+            | FieldStrategy::F16
+            | FieldStrategy::Address
             | FieldStrategy::Sid
+            | FieldStrategy::RawStructValue
             | FieldStrategy::RawData
             | FieldStrategy::RawField
             | FieldStrategy::RawFieldSlice => 1,
 
             | FieldStrategy::CStr       // 1 for data, 1 for nul termination.
             | FieldStrategy::Counted    // 1 for size, 1 for data.
-            | FieldStrategy::Slice => 2,// 1 for size, 1 for data.
+            | FieldStrategy::CountedOpt // 1 for size, 1 for data.
+            | FieldStrategy::ByteOrder  // 1 for size, 1 for data.
+            | FieldStrategy::Display    // 1 for size, 1 for data.
+            | FieldStrategy::Debug      // 1 for size, 1 for data.
+            | FieldStrategy::Json       // 1 for size, 1 for data.
+            | FieldStrategy::Utf16      // 1 for size, 1 for data.
+            | FieldStrategy::MapStr8    // 1 for data, 1 for dropped count.
+            | FieldStrategy::Slice      // 1 for size, 1 for data.
+            | FieldStrategy::Bool32Slice    // 1 for size, 1 for data.
+            | FieldStrategy::F16Slice       // 1 for size, 1 for data.
+            | FieldStrategy::AddressSlice => 2,// 1 for size, 1 for data.
+
+            FieldStrategy::Chunked => CHUNKS_MAX * 2, // 1 for size, 1 for data, per chunk.
         }
     }
 }
@@ -105,6 +199,22 @@ pub struct FieldOption {
     pub value_array_count: u8,
 }
 
+/// Returns the name of the metadata field for the given chunk of a chunked field,
+/// i.e. `name` for chunk 0, `name_1` for chunk 1, etc.
+pub fn chunk_name(name: &str, chunk: u8) -> String {
+    return if chunk == 0 {
+        name.to_string()
+    } else {
+        format!("{}_{}", name, chunk)
+    };
+}
+
+/// Returns the name of the metadata field that counts the elements that did not fit
+/// in a map_str8 field, i.e. `name_dropped`.
+pub fn dropped_name(name: &str) -> String {
+    return format!("{}_dropped", name);
+}
+
 impl FieldOption {
     pub const fn new(
         option_name: &'static str,
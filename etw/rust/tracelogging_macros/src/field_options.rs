@@ -10,8 +10,13 @@ use crate::strings::*;
 #[rustfmt::skip]
 pub static FIELD_OPTIONS: &[Opt] = &[
     Opt::new("binary",                  U8_PATH,    I::Binary,     O::Default,       Counted,    0),
+    Opt::new("binary_u16",              U16_PATH,   I::Binary,     O::Hex,           Counted,    0),
+    Opt::new("binary_u32",              U32_PATH,   I::Binary,     O::Hex,           Counted,    0),
+    Opt::new("binary_u64",              U64_PATH,   I::Binary,     O::Hex,           Counted,    0),
     Opt::new("binaryc",                 U8_PATH,    I::BinaryC,    O::Default,       Counted,    0),
     Opt::new("bool32",                  I32_PATH,   I::Bool32,     O::Default,       Scalar,     0),
+    Opt::new("bool32_native",           I32_PATH,   I::Bool32,     O::Default,       Bool32,     0),
+    Opt::new("bool32_native_slice",     I32_PATH,   I::Bool32,     O::Default,       Bool32Slice, 0),
     Opt::new("bool32_slice",            I32_PATH,   I::Bool32,     O::Default,       Slice,      0),
     Opt::new("bool8",                   BOOL_PATH,  I::U8,         O::Boolean,       Scalar,     0),
     Opt::new("bool8_slice",             BOOL_PATH,  I::U8,         O::Boolean,       Slice,      0),
@@ -19,6 +24,8 @@ pub static FIELD_OPTIONS: &[Opt] = &[
     Opt::new("char16_slice",            U16_PATH,   I::U16,        O::String,        Slice,      0),
     Opt::new("char8_cp1252",            U8_PATH,    I::U8,         O::String,        Scalar,     0),
     Opt::new("char8_cp1252_slice",      U8_PATH,    I::U8,         O::String,        Slice,      0),
+    Opt::new("char8_utf8",              U8_PATH,    I::U8,         O::Utf8,          Scalar,     0),
+    Opt::new("char8_utf8_slice",        U8_PATH,    I::U8,         O::Utf8,          Slice,      0),
     Opt::new("codepointer",             USIZE_PATH, I::HexSize,    O::CodePointer,   Scalar,     0),
     Opt::new("codepointer_slice",       USIZE_PATH, I::HexSize,    O::CodePointer,   Slice,      0),
     Opt::new("cstr16",                  U16_PATH,   I::CStr16,     O::Default,       CStr,       0),
@@ -28,13 +35,18 @@ pub static FIELD_OPTIONS: &[Opt] = &[
     Opt::new("cstr8_cp1252",            U8_PATH,    I::CStr8,      O::Default,       CStr,       0),
     Opt::new("cstr8_json",              U8_PATH,    I::CStr8,      O::Json,          CStr,       0),
     Opt::new("cstr8_xml",               U8_PATH,    I::CStr8,      O::Xml,           CStr,       0),
+    Opt::new("debug8",                  U8_PATH,    I::Str8,       O::Utf8,          Debug,      0),
+    Opt::new("display8",                U8_PATH,    I::Str8,       O::Utf8,          Display,    0),
     Opt::new("errno",                   I32_PATH,   I::I32,        O::Default,       Scalar,     0),
     Opt::new("errno_slice",             I32_PATH,   I::I32,        O::Default,       Slice,      0),
+    Opt::new("f16",                     F32_PATH,   I::F32,        O::Default,       F16,        0),
+    Opt::new("f16_slice",               F32_PATH,   I::F32,        O::Default,       F16Slice,   0),
     Opt::new("f32",                     F32_PATH,   I::F32,        O::Default,       Scalar,     0),
     Opt::new("f32_slice",               F32_PATH,   I::F32,        O::Default,       Slice,      0),
     Opt::new("f64",                     F64_PATH,   I::F64,        O::Default,       Scalar,     0),
     Opt::new("f64_slice",               F64_PATH,   I::F64,        O::Default,       Slice,      0),
     Opt::new("guid",                    GUID_PATH,  I::Guid,       O::Default,       Scalar,     0),
+    Opt::new("guid_bytes",              U8_PATH,    I::Guid,       O::Default,       Scalar,     16),
     Opt::new("guid_slice",              GUID_PATH,  I::Guid,       O::Default,       Slice,      0),
     Opt::new("hresult",                 I32_PATH,   I::I32,        O::HResult,       Scalar,     0),
     Opt::new("hresult_slice",           I32_PATH,   I::I32,        O::HResult,       Slice,      0),
@@ -62,6 +74,8 @@ pub static FIELD_OPTIONS: &[Opt] = &[
     Opt::new("isize_hex",               ISIZE_PATH, I::HexSize,    O::Default,       Scalar,     0),
     Opt::new("isize_hex_slice",         ISIZE_PATH, I::HexSize,    O::Default,       Slice,      0),
     Opt::new("isize_slice",             ISIZE_PATH, I::ISize,      O::Default,       Slice,      0),
+    Opt::new("json8",                   U8_PATH,    I::Str8,       O::Json,          Json,       0),
+    Opt::new("map_str8",                U8_PATH,    I::Struct,     O::Default,       MapStr8,    0),
     Opt::new("pid",                     U32_PATH,   I::U32,        O::Pid,           Scalar,     0),
     Opt::new("pid_slice",               U32_PATH,   I::U32,        O::Pid,           Slice,      0),
     Opt::new("pointer",                 USIZE_PATH, I::HexSize,    O::Default,       Scalar,     0),
@@ -73,18 +87,26 @@ pub static FIELD_OPTIONS: &[Opt] = &[
     Opt::new("raw_field_slice",         U8_PATH,    I::Invalid,    O::Default,       RawFieldSlice,  0),
     Opt::new("raw_meta",                &[],        I::Invalid,    O::Default,       RawMeta,        0),
     Opt::new("raw_meta_slice",          &[],        I::Invalid,    O::Default,       RawMetaSlice,   0),
+    Opt::new("raw_pointer",             USIZE_PATH, I::HexSize,    O::Default,       Address,        0),
+    Opt::new("raw_pointer_slice",       USIZE_PATH, I::HexSize,    O::Default,       AddressSlice,   0),
     Opt::new("raw_struct",              &[],        I::Struct,     O::Default,       RawStruct,      0),
     Opt::new("raw_struct_slice",        &[],        I::Struct,     O::Default,       RawStructSlice, 0),
+    Opt::new("raw_struct_value",        &[],        I::Struct,     O::Default,       RawStructValue, 0),
     Opt::new("socketaddress",           U8_PATH,    I::Binary,     O::SocketAddress, Counted,        0),
     Opt::new("socketaddressc",          U8_PATH,    I::BinaryC,    O::SocketAddress, Counted,    0),
     Opt::new("str16",                   U16_PATH,   I::Str16,      O::Default,       Counted,    0),
+    Opt::new("str16_from_utf8",         U16_PATH,   I::Str16,      O::Default,       Utf16,      0),
     Opt::new("str16_json",              U16_PATH,   I::Str16,      O::Json,          Counted,    0),
+    Opt::new("str16_opt",               U16_PATH,   I::Str16,      O::Default,       CountedOpt, 0),
     Opt::new("str16_xml",               U16_PATH,   I::Str16,      O::Xml,           Counted,    0),
     Opt::new("str8",                    U8_PATH,    I::Str8,       O::Utf8,          Counted,    0),
+    Opt::new("str8_chunked",            U8_PATH,    I::Str8,       O::Utf8,          Chunked,    0),
     Opt::new("str8_cp1252",             U8_PATH,    I::Str8,       O::Default,       Counted,    0),
     Opt::new("str8_json",               U8_PATH,    I::Str8,       O::Json,          Counted,    0),
+    Opt::new("str8_opt",                U8_PATH,    I::Str8,       O::Utf8,          CountedOpt, 0),
     Opt::new("str8_xml",                U8_PATH,    I::Str8,       O::Xml,           Counted,    0),
     Opt::new("struct",                  &[],        I::Struct,     O::Default,       Struct,     0),
+    Opt::new("struct_tuple",            &[],        I::Struct,     O::Default,       StructTuple, 0),
     Opt::new("systemtime",              I64_PATH,   I::FileTime,   O::Default,       SystemTime, 0),
     Opt::new("tid",                     U32_PATH,   I::U32,        O::Tid,           Scalar,     0),
     Opt::new("tid_slice",               U32_PATH,   I::U32,        O::Tid,           Slice,      0),
@@ -95,12 +117,16 @@ pub static FIELD_OPTIONS: &[Opt] = &[
     Opt::new("u16_hex_slice",           U16_PATH,   I::U16,        O::Hex,           Slice,      0),
     Opt::new("u16_slice",               U16_PATH,   I::U16,        O::Default,       Slice,      0),
     Opt::new("u32",                     U32_PATH,   I::U32,        O::Default,       Scalar,     0),
+    Opt::new("u32_be",                  U8_PATH,    I::Binary,     O::Default,       ByteOrder,  4),
     Opt::new("u32_hex",                 U32_PATH,   I::Hex32,      O::Default,       Scalar,     0),
     Opt::new("u32_hex_slice",           U32_PATH,   I::Hex32,      O::Default,       Slice,      0),
+    Opt::new("u32_le",                  U8_PATH,    I::Binary,     O::Default,       ByteOrder,  4),
     Opt::new("u32_slice",               U32_PATH,   I::U32,        O::Default,       Slice,      0),
     Opt::new("u64",                     U64_PATH,   I::U64,        O::Default,       Scalar,     0),
+    Opt::new("u64_be",                  U8_PATH,    I::Binary,     O::Default,       ByteOrder,  8),
     Opt::new("u64_hex",                 U64_PATH,   I::Hex64,      O::Default,       Scalar,     0),
     Opt::new("u64_hex_slice",           U64_PATH,   I::Hex64,      O::Default,       Slice,      0),
+    Opt::new("u64_le",                  U8_PATH,    I::Binary,     O::Default,       ByteOrder,  8),
     Opt::new("u64_slice",               U64_PATH,   I::U64,        O::Default,       Slice,      0),
     Opt::new("u8",                      U8_PATH,    I::U8,         O::Default,       Scalar,     0),
     Opt::new("u8_hex",                  U8_PATH,    I::U8,         O::Hex,           Scalar,     0),
@@ -118,6 +144,7 @@ pub static FIELD_OPTIONS: &[Opt] = &[
     Opt::new("win_ntstatus_slice",      I32_PATH,   I::Hex32,      O::NtStatus,      Slice,      0),
     Opt::new("win_sid",                 U8_PATH,    I::Sid,        O::Default,       Sid,        0),
     Opt::new("win_systemtime",          U16_PATH,   I::SystemTime, O::Default,       Scalar,     8),
+    Opt::new("win_systemtime_chrono",   U16_PATH,   I::SystemTime, O::Default,       SystemTimeChrono, 8),
     Opt::new("win_systemtime_slice",    U16_PATH,   I::SystemTime, O::Default,       Slice,      8),
     Opt::new("win_systemtime_utc",      U16_PATH,   I::SystemTime, O::DateTimeUtc,   Scalar,     8),
     Opt::new("win_systemtime_utc_slice",U16_PATH,   I::SystemTime, O::DateTimeUtc,   Slice,      8),
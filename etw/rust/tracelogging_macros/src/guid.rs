@@ -130,6 +130,14 @@ impl Guid {
     }
 
     /// Creates a GUID from a u128 value.
+    ///
+    /// The u128 holds the GUID's fields from most-significant to least-significant:
+    /// `data1` in bits 127..96, `data2` in bits 95..80, `data3` in bits 79..64, and
+    /// `data4[0..8]` in bits 63..0 (`data4[0]` most significant). This matches the
+    /// order of the hex digits in the GUID's string form, the big-endian byte order
+    /// of [`Guid::to_bytes_be`], and the `u128` representation used by the `uuid`
+    /// crate, and is independent of the platform's byte order, so
+    /// `Guid::from_u128(&x).to_u128() == x` for any `x`.
     /// ```
     /// # use tracelogging::Guid;
     /// assert_eq!(
@@ -138,9 +146,9 @@ impl Guid {
     /// ```
     pub const fn from_u128(value: &u128) -> Self {
         return Self {
-            data1: u32::from_le(value.wrapping_shr(96) as u32),
-            data2: u16::from_le(value.wrapping_shr(80) as u16),
-            data3: u16::from_le(value.wrapping_shr(64) as u16),
+            data1: value.wrapping_shr(96) as u32,
+            data2: value.wrapping_shr(80) as u16,
+            data3: value.wrapping_shr(64) as u16,
             data4: (*value as u64).to_be_bytes(),
         };
     }
@@ -300,7 +308,8 @@ impl Guid {
         ];
     }
 
-    /// Returns the GUID as a u128 value.
+    /// Returns the GUID as a u128 value. This is the inverse of [`Guid::from_u128`] and
+    /// uses the same field order.
     /// ```
     /// use tracelogging::Guid;
     /// assert_eq!(
@@ -309,9 +318,9 @@ impl Guid {
     /// ```
     #[allow(clippy::wrong_self_convention)]
     pub const fn to_u128(&self) -> u128 {
-        return (self.data1.to_le() as u128) << 96
-            | (self.data2.to_le() as u128) << 80
-            | (self.data3.to_le() as u128) << 64
+        return (self.data1 as u128) << 96
+            | (self.data2 as u128) << 80
+            | (self.data3 as u128) << 64
             | u64::from_be_bytes(self.data4) as u128;
     }
 
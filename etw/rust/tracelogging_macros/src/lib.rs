@@ -12,6 +12,8 @@ use crate::event_generator::EventGenerator;
 use crate::event_info::EventInfo;
 use crate::provider_generator::ProviderGenerator;
 use crate::provider_info::ProviderInfo;
+use crate::raw_struct_generator::RawStructGenerator;
+use crate::raw_struct_info::RawStructInfo;
 
 #[proc_macro]
 pub fn define_provider(arg_tokens: TokenStream) -> TokenStream {
@@ -25,12 +27,39 @@ pub fn define_provider(arg_tokens: TokenStream) -> TokenStream {
 #[proc_macro]
 pub fn write_event(arg_tokens: TokenStream) -> TokenStream {
     let call_site = Span::call_site();
-    return match EventInfo::try_from_tokens(call_site, arg_tokens) {
+    return match EventInfo::try_from_tokens(call_site, arg_tokens, false) {
         Err(error_tokens) => error_tokens,
         Ok(prov) => EventGenerator::new(call_site).generate(prov),
     };
 }
 
+#[proc_macro]
+pub fn write_events(arg_tokens: TokenStream) -> TokenStream {
+    let call_site = Span::call_site();
+    return match EventInfo::try_from_tokens(call_site, arg_tokens, true) {
+        Err(error_tokens) => error_tokens,
+        Ok(event) => EventGenerator::new(call_site).generate(event),
+    };
+}
+
+#[proc_macro]
+pub fn write_event_fields(arg_tokens: TokenStream) -> TokenStream {
+    let call_site = Span::call_site();
+    return match EventInfo::try_from_tokens(call_site, arg_tokens, false) {
+        Err(error_tokens) => error_tokens,
+        Ok(event) => EventGenerator::new(call_site).generate_fields(event),
+    };
+}
+
+#[proc_macro_derive(RawStruct)]
+pub fn derive_raw_struct(item_tokens: TokenStream) -> TokenStream {
+    let call_site = Span::call_site();
+    return match RawStructInfo::try_from_tokens(call_site, item_tokens) {
+        Err(error_tokens) => error_tokens,
+        Ok(info) => RawStructGenerator::new(call_site).generate(info),
+    };
+}
+
 // The tracelogging crate depends on the tracelogging_macros crate so the
 // tracelogging_macros crate can't depend on the tracelogging crate. Instead, pull in
 // the source code for needed modules.
@@ -47,8 +76,11 @@ mod field_info;
 mod field_option;
 mod field_options;
 mod ident_builder;
+mod name_style;
 mod parser;
 mod provider_generator;
 mod provider_info;
+mod raw_struct_generator;
+mod raw_struct_info;
 mod strings;
 mod tree;
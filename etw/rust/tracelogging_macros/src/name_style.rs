@@ -0,0 +1,98 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+/// Field name normalization, set by the event's `name_style(...)` option.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum NameStyle {
+    /// Field names are used as written (default).
+    Verbatim,
+    /// e.g. `"http_status_code"`.
+    SnakeCase,
+    /// e.g. `"httpStatusCode"`.
+    LowerCamelCase,
+    /// e.g. `"HttpStatusCode"`.
+    UpperCamelCase,
+}
+
+impl NameStyle {
+    /// Returns the style for a `name_style(...)` value, e.g. `snake_case`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        return match name {
+            "snake_case" => Some(NameStyle::SnakeCase),
+            "lowerCamelCase" => Some(NameStyle::LowerCamelCase),
+            "UpperCamelCase" => Some(NameStyle::UpperCamelCase),
+            _ => None,
+        };
+    }
+
+    /// Returns the name converted to this style. Names without any word characters
+    /// (e.g. `"_"`) are returned unchanged.
+    pub fn apply(self, name: &str) -> String {
+        let words = split_words(name);
+        if self == NameStyle::Verbatim || words.is_empty() {
+            return name.to_string();
+        }
+
+        let mut result = String::with_capacity(name.len() + words.len());
+        for (index, word) in words.iter().enumerate() {
+            match self {
+                NameStyle::Verbatim => unreachable!(),
+                NameStyle::SnakeCase => {
+                    if index != 0 {
+                        result.push('_');
+                    }
+                    result.extend(word.chars().flat_map(char::to_lowercase));
+                }
+                NameStyle::LowerCamelCase if index == 0 => {
+                    result.extend(word.chars().flat_map(char::to_lowercase));
+                }
+                NameStyle::LowerCamelCase | NameStyle::UpperCamelCase => {
+                    let mut chars = word.chars();
+                    if let Some(first) = chars.next() {
+                        result.extend(first.to_uppercase());
+                    }
+                    result.extend(chars.flat_map(char::to_lowercase));
+                }
+            }
+        }
+        return result;
+    }
+}
+
+/// Splits a name into words at `'_'`, `'-'`, and `' '` and at case changes, e.g.
+/// `"HTTPStatus_code"` is split into `"HTTP"`, `"Status"`, and `"code"`. Digits
+/// stay with the preceding word.
+fn split_words(name: &str) -> Vec<&str> {
+    let mut words = Vec::new();
+    let chars: Vec<(usize, char)> = name.char_indices().collect();
+    let mut word_start: Option<usize> = None;
+    for (i, &(pos, ch)) in chars.iter().enumerate() {
+        if ch == '_' || ch == '-' || ch == ' ' {
+            if let Some(start) = word_start.take() {
+                words.push(&name[start..pos]);
+            }
+            continue;
+        }
+
+        if let Some(start) = word_start {
+            let prev = chars[i - 1].1;
+            let next_is_lower = chars.get(i + 1).map_or(false, |next| next.1.is_lowercase());
+            if ch.is_uppercase()
+                && (prev.is_lowercase()
+                    || prev.is_numeric()
+                    || (prev.is_uppercase() && next_is_lower))
+            {
+                // "fooBar", "foo2Bar", or the "B" in "FOOBar".
+                words.push(&name[start..pos]);
+                word_start = Some(pos);
+            }
+        } else {
+            word_start = Some(pos);
+        }
+    }
+
+    if let Some(start) = word_start {
+        words.push(&name[start..]);
+    }
+    return words;
+}
@@ -131,6 +131,118 @@ impl<'a> Parser<'a> {
         return result;
     }
 
+    /// Reads an integer literal then moves to the next comma or the end-of-stream.
+    /// Emits "expected ..." error for other tokens encountered before comma or end-of-stream.
+    pub fn next_int_literal(
+        &mut self,
+        constraints: ArgConstraints,
+        error_message: &str,
+    ) -> Option<(u64, Span)> {
+        let result;
+        match self.move_next() {
+            Some(TokenTree::Literal(literal)) => {
+                if let Some(value) = parse_int(&literal.to_string()) {
+                    result = Some((value, literal.span()));
+                    self.next_comma(constraints);
+                } else {
+                    self.errors.add(literal.span(), error_message);
+                    if self.skip_to_comma(TokenTree::Literal(literal)) {
+                        self.comma_after_item(constraints);
+                    }
+                    result = None;
+                }
+            }
+            Some(token) => {
+                self.errors.add(token.span(), error_message);
+                if self.skip_to_comma(token) {
+                    self.comma_after_item(constraints);
+                }
+                result = None;
+            }
+            None => {
+                self.eos_before_item(constraints, error_message);
+                result = None;
+            }
+        }
+        return result;
+    }
+
+    /// Reads a byte string literal (`b"..."` or `&b"..."`) or a byte array literal
+    /// (`[1, 2, ...]` or `&[1, 2, ...]`) then moves to the next comma or the end-of-stream.
+    /// Emits "expected ..." error for other tokens encountered before comma or end-of-stream.
+    pub fn next_bytes_literal(
+        &mut self,
+        constraints: ArgConstraints,
+        error_message: &str,
+    ) -> Option<(Vec<u8>, Span)> {
+        let mut token = self.move_next();
+        if let Some(TokenTree::Punct(punct)) = &token {
+            if punct.as_char() == '&' {
+                token = self.move_next();
+            }
+        }
+
+        let result;
+        match token {
+            Some(TokenTree::Literal(literal)) => {
+                let lit_str = literal.to_string();
+                if let Some(bytes) = lit_str
+                    .strip_prefix("b\"")
+                    .and_then(|s| s.strip_suffix('"'))
+                    .and_then(unescape_bytes)
+                {
+                    result = Some((bytes, literal.span()));
+                    self.next_comma(constraints);
+                } else {
+                    self.errors.add(literal.span(), error_message);
+                    if self.skip_to_comma(TokenTree::Literal(literal)) {
+                        self.comma_after_item(constraints);
+                    }
+                    result = None;
+                }
+            }
+            Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Bracket => {
+                let mut bytes = Vec::new();
+                let mut valid = true;
+                for (index, item) in group.stream().into_iter().enumerate() {
+                    match item {
+                        TokenTree::Punct(punct) if index % 2 == 1 && punct.as_char() == ',' => {}
+                        TokenTree::Literal(literal) if index % 2 == 0 => {
+                            match parse_int(&literal.to_string()) {
+                                Some(value) if value <= u8::MAX as u64 => bytes.push(value as u8),
+                                _ => valid = false,
+                            }
+                        }
+                        _ => valid = false,
+                    }
+                }
+
+                if valid {
+                    result = Some((bytes, group.span()));
+                    self.next_comma(constraints);
+                } else {
+                    self.errors.add(group.span(), error_message);
+                    if self.skip_to_comma(TokenTree::Group(group)) {
+                        self.comma_after_item(constraints);
+                    }
+                    result = None;
+                }
+            }
+            Some(token) => {
+                self.errors.add(token.span(), error_message);
+                if self.skip_to_comma(token) {
+                    self.comma_after_item(constraints);
+                }
+                result = None;
+            }
+            None => {
+                self.eos_before_item(constraints, error_message);
+                result = None;
+            }
+        }
+        return result;
+    }
+
     /// Reads tokens to the next comma or the end-of-stream.
     /// Emits an error if no tokens or if ';'.
     pub fn next_tokens(&mut self, constraints: ArgConstraints, error_message: &str) -> TokenStream {
@@ -181,7 +293,7 @@ impl<'a> Parser<'a> {
     /// Reads OptionIdent(ArgsGroup) or {...} then moves to the next comma or the end-of-stream.
     /// Emits "expected option" errors for non-option syntax.
     /// Emits "expected ..." error for other tokens encountered before comma or end-of-stream.
-    pub fn next_arg(&mut self, want_struct: bool) -> ArgResult {
+    pub fn next_arg(&mut self, want_struct: bool) -> ArgResult<'_> {
         const EXPECTED_OPTION: &str = "expected identifier for option name, e.g. Option(args...)";
         const EXPECTED_OPTION_OR_STRUCT: &str =
             "expected '{' for struct or identifier for option name, e.g. Option(args...)";
@@ -439,6 +551,79 @@ fn unescape_u(dest: &mut String, it: &mut str::Chars) -> bool {
     return false; // Too many digits
 }
 
+/// Parses an unsigned integer literal, e.g. `123`, `0x7B`, `0b1111_011`, or `123u8`.
+/// Returns None if the literal is not an unsigned integer or does not fit in u64.
+pub fn parse_int(lit_str: &str) -> Option<u64> {
+    const SUFFIXES: &[&str] = &[
+        "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128", "isize",
+    ];
+
+    let mut digits = lit_str;
+    for suffix in SUFFIXES {
+        if let Some(stripped) = digits.strip_suffix(suffix) {
+            digits = stripped;
+            break;
+        }
+    }
+
+    let radix;
+    if let Some(stripped) = digits.strip_prefix("0x") {
+        radix = 16;
+        digits = stripped;
+    } else if let Some(stripped) = digits.strip_prefix("0o") {
+        radix = 8;
+        digits = stripped;
+    } else if let Some(stripped) = digits.strip_prefix("0b") {
+        radix = 2;
+        digits = stripped;
+    } else {
+        radix = 10;
+    }
+
+    let mut value: u64 = 0;
+    let mut any_digits = false;
+    for ch in digits.chars() {
+        if ch == '_' {
+            continue;
+        }
+
+        let digit = ch.to_digit(radix)?;
+        value = value.checked_mul(radix as u64)?.checked_add(digit as u64)?;
+        any_digits = true;
+    }
+
+    return if any_digits { Some(value) } else { None };
+}
+
+/// Unescapes the content of a byte string literal, i.e. the part between `b"` and `"`.
+fn unescape_bytes(src: &str) -> Option<Vec<u8>> {
+    let mut dest = Vec::with_capacity(src.len());
+    let mut it = src.bytes();
+    while let Some(ch) = it.next() {
+        if ch != b'\\' {
+            dest.push(ch);
+        } else {
+            match it.next() {
+                Some(b'0') => dest.push(0),
+                Some(b'n') => dest.push(b'\n'),
+                Some(b'r') => dest.push(b'\r'),
+                Some(b't') => dest.push(b'\t'),
+                Some(b'\\') => dest.push(b'\\'),
+                Some(b'\'') => dest.push(b'\''),
+                Some(b'"') => dest.push(b'"'),
+                Some(b'x') => {
+                    let hi = (it.next()? as char).to_digit(16)?;
+                    let lo = (it.next()? as char).to_digit(16)?;
+                    dest.push((hi << 4 | lo) as u8);
+                }
+                _ => return None,
+            }
+        }
+    }
+
+    return Some(dest);
+}
+
 fn unescape(src: &str) -> Option<String> {
     let mut dest = String::with_capacity(src.len());
     let mut it = src.chars();
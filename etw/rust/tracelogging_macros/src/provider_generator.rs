@@ -3,7 +3,7 @@
 
 use proc_macro::*;
 
-use crate::provider_info::ProviderInfo;
+use crate::provider_info::{defaults_const_ident, ProviderInfo, KEYWORD_NAMES_TRAIT_TYPE};
 use crate::strings::*;
 use crate::tree::Tree;
 
@@ -24,7 +24,16 @@ impl ProviderGenerator {
         };
     }
 
-    pub fn generate(&mut self, provider: ProviderInfo) -> TokenStream {
+    pub fn generate(&mut self, mut provider: ProviderInfo) -> TokenStream {
+        // If the "disabled" feature is enabled, the provider is a stub with an empty
+        // name and no traits.
+        if cfg!(feature = "disabled") {
+            provider.name.clear();
+            provider.group_id = None;
+            provider.traits.clear();
+            provider.keyword_names.clear();
+        }
+
         // Reserve space for size.
         let mut meta = Vec::<u8>::new();
         meta.push(0);
@@ -42,13 +51,143 @@ impl ProviderGenerator {
             meta.extend_from_slice(&group_id.to_bytes_le());
         }
 
+        for (trait_type, trait_bytes) in &provider.traits {
+            // Custom provider trait
+            let trait_size = 3 + trait_bytes.len(); // sizeof(size) + sizeof(type) + data
+            meta.push(trait_size as u8);
+            meta.push((trait_size >> 8) as u8);
+            meta.push(*trait_type);
+            meta.extend_from_slice(trait_bytes);
+        }
+
+        let keyword_names = provider.keyword_names_trait_data();
+        if !keyword_names.is_empty() {
+            // Keyword names trait
+            let trait_size = 3 + keyword_names.len(); // sizeof(size) + sizeof(type) + data
+            meta.push(trait_size as u8);
+            meta.push((trait_size >> 8) as u8);
+            meta.push(KEYWORD_NAMES_TRAIT_TYPE);
+            meta.extend_from_slice(&keyword_names);
+        }
+
         meta[0] = meta.len() as u8;
         meta[1] = (meta.len() >> 8) as u8;
 
         let id_fields = provider.id.to_fields();
 
+        // level default: Level::Verbose
+        if provider.default_level.is_empty() {
+            provider.default_level.tokens =
+                self.tree1.add_path(LEVEL_VERBOSE_PATH).drain().collect();
+        }
+
+        // keyword default: 1u64
+        if provider.default_keyword.is_empty() {
+            provider.default_keyword.tokens = self
+                .tree1
+                .add_literal(Literal::u64_suffixed(1))
+                .drain()
+                .collect();
+        }
+
+        // opcode default: Opcode::Info
+        if provider.default_opcode.is_empty() {
+            provider.default_opcode.tokens =
+                self.tree1.add_path(OPCODE_INFO_PATH).drain().collect();
+        }
+
+        // task default: 0
+        if provider.default_task.is_empty() {
+            provider.default_task.tokens = self
+                .tree1
+                .add_literal(Literal::u16_unsuffixed(0))
+                .drain()
+                .collect();
+        }
+
+        // task_names: &[(TASK, "Name"), ...]
+        for (task, name) in &provider.task_names {
+            self.tree2.add_group_paren(
+                self.tree3
+                    .add_literal(Literal::u16_unsuffixed(*task))
+                    .add_punct(",")
+                    .add_literal(Literal::string(name))
+                    .drain(),
+            );
+            self.tree2.add_punct(",");
+        }
+        let task_names: Vec<TokenTree> = self
+            .tree1
+            .add_punct("&")
+            .add_group_square(self.tree2.drain())
+            .drain()
+            .collect();
+
+        // ProviderDefaults { level: ..., keyword: ..., ... }
+        let defaults_const = defaults_const_ident(&provider.symbol);
+        let defaults_tokens: Vec<TokenTree> = self
+            .tree1
+            .add_path(PROVIDER_DEFAULTS_PATH)
+            .add_group_curly(
+                self.tree2
+                    .add_ident("level")
+                    .add_punct(":")
+                    .push_span(provider.default_level.context)
+                    .add_tokens(provider.default_level.tokens)
+                    .pop_span()
+                    .add_punct(",")
+                    .add_ident("keyword")
+                    .add_punct(":")
+                    .push_span(provider.default_keyword.context)
+                    .add_tokens(provider.default_keyword.tokens)
+                    .pop_span()
+                    .add_punct(",")
+                    .add_ident("opcode")
+                    .add_punct(":")
+                    .push_span(provider.default_opcode.context)
+                    .add_tokens(provider.default_opcode.tokens)
+                    .pop_span()
+                    .add_punct(",")
+                    .add_ident("task")
+                    .add_punct(":")
+                    .push_span(provider.default_task.context)
+                    .add_tokens(provider.default_task.tokens)
+                    .pop_span()
+                    .add_punct(",")
+                    .add_ident("derive_event_ids")
+                    .add_punct(":")
+                    .add_ident(if provider.derive_event_ids {
+                        "true"
+                    } else {
+                        "false"
+                    })
+                    .add_punct(",")
+                    .add_ident("task_names")
+                    .add_punct(":")
+                    .add_tokens(task_names)
+                    .drain(),
+            )
+            .drain()
+            .collect();
+
         let prov_tokens = self
             .prov_tree
+            // Defaults used by write_event! when the event does not specify them. A
+            // constant so that events can use the defaults in their descriptors.
+            // #[doc(hidden)] #[allow(non_upper_case_globals)]
+            // const _TLG_DEFAULTS_PROVIDER: ProviderDefaults = ProviderDefaults { ... };
+            .add_outer_attribute("doc", self.tree1.add_ident("hidden").drain())
+            .add_outer_attribute(
+                "allow",
+                self.tree1.add_ident("non_upper_case_globals").drain(),
+            )
+            .add_ident("const")
+            .add_token(defaults_const.clone())
+            .add_punct(":")
+            .add_path(PROVIDER_DEFAULTS_PATH)
+            .add_punct("=")
+            .add_tokens(defaults_tokens)
+            .add_punct(";")
             // static PROVIDER: ::tracelogging::Provider = unsafe { ... };
             .add_ident("static")
             .add_token(provider.symbol.clone())
@@ -80,6 +219,10 @@ impl ProviderGenerator {
                                     .add_literal(Literal::byte_string(&id_fields.3))
                                     .drain(),
                             )
+                            // , &_TLG_DEFAULTS_PROVIDER
+                            .add_punct(",")
+                            .add_punct("&")
+                            .add_token(defaults_const)
                             .drain(),
                     )
                     .drain(),
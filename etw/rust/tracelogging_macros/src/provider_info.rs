@@ -4,14 +4,49 @@
 use proc_macro::*;
 
 use crate::errors::Errors;
+use crate::event_info::{
+    check_ascii_name, expected_enum_message, filter_enum_tokens, unrecognized_option_message,
+};
+use crate::expression::Expression;
 use crate::guid::Guid;
 use crate::parser::{ArgConstraints::*, ArgResult, Parser};
+use crate::strings::{LEVEL_ENUMS, OPCODE_ENUMS, TLG_DEFAULTS_CONST_PREFIX};
+use crate::tree::Tree;
+
+const PROVIDER_OPTIONS: &[&str] = &[
+    "debug",
+    "default_keyword",
+    "default_level",
+    "default_opcode",
+    "default_task",
+    "derive_event_ids",
+    "group_id",
+    "group_name",
+    "id",
+    "keyword_name",
+    "task_name",
+    "trait",
+];
+const PROVIDER_METADATA_MAX: usize = 32768; // Provider name limit, applied to all metadata.
+const TRAIT_DATA_MAX: usize = u16::MAX as usize - 3; // Trait size (u16) includes 3-byte header.
+
+/// Trait type used for the keyword names set via `keyword_name(...)`.
+pub const KEYWORD_NAMES_TRAIT_TYPE: u8 = 128;
 
 pub struct ProviderInfo {
     pub symbol: Ident,
     pub name: String,
     pub id: Guid,
     pub group_id: Option<Guid>,
+    pub traits: Vec<(u8, Vec<u8>)>,
+    pub keyword_names: Vec<(u64, String)>,
+    pub default_level: Expression,
+    pub default_keyword: Expression,
+    pub default_opcode: Expression,
+    pub default_task: Expression,
+    /// From task_name(...): (task, name). Names are valid Rust identifiers.
+    pub task_names: Vec<(u16, String)>,
+    pub derive_event_ids: bool,
     pub debug: bool,
 }
 
@@ -24,10 +59,19 @@ impl ProviderInfo {
         let mut group_name_set = false;
         let mut errors = Errors::new();
         let mut root_parser = Parser::new(&mut errors, arg_span, arg_tokens);
+        let mut scratch_tree = Tree::new(arg_span);
         let mut prov = ProviderInfo {
             name: String::new(),
             id: Guid::zero(),
             group_id: None,
+            traits: Vec::new(),
+            keyword_names: Vec::new(),
+            default_level: Expression::empty(arg_span),
+            default_keyword: Expression::empty(arg_span),
+            default_opcode: Expression::empty(arg_span),
+            default_task: Expression::empty(arg_span),
+            task_names: Vec::new(),
+            derive_event_ids: false,
             debug: false,
             symbol: Ident::new("x", arg_span),
         };
@@ -54,9 +98,10 @@ impl ProviderInfo {
             if prov.name.contains('\0') {
                 root_parser.errors().add(span, "provider name must not contain '\\0'");
             }
+            check_ascii_name(root_parser.errors(), span, "provider", &prov.name);
         }
 
-        // provider options (id or group_id)
+        // provider options
 
         while let ArgResult::Option(option_name_ident, mut option_args_parser) =
             root_parser.next_arg(false)
@@ -67,6 +112,10 @@ impl ProviderInfo {
                     prov.debug = true;
                     continue;
                 }
+                "derive_event_ids" => {
+                    prov.derive_event_ids = true;
+                    continue;
+                }
                 "id" => {
                     if prov_id_set {
                         errors.add(option_name_ident.span(), "id already set");
@@ -97,10 +146,176 @@ impl ProviderInfo {
                     }
                     continue;
                 }
-                _ => {
+                "trait" => {
+                    if let Some((trait_type, type_span)) = option_args_parser.next_int_literal(
+                        RequiredNotLast,
+                        "expected trait type (integer literal in range 2..255), e.g. trait(2, b\"...\")",
+                    ) {
+                        if trait_type == 1 {
+                            option_args_parser.errors().add(
+                                type_span,
+                                "trait type 1 (provider group) must be set via group_id(\"GUID\")",
+                            );
+                        } else if trait_type == 0 || trait_type > 255 {
+                            option_args_parser
+                                .errors()
+                                .add(type_span, "trait type must be in range 2..255");
+                        }
+
+                        if let Some((trait_bytes, bytes_span)) = option_args_parser
+                            .next_bytes_literal(
+                                RequiredLast,
+                                "expected trait data (byte string or byte array literal), e.g. b\"...\" or &[1, 2, 3]",
+                            )
+                        {
+                            if trait_bytes.len() > TRAIT_DATA_MAX {
+                                option_args_parser
+                                    .errors()
+                                    .add(bytes_span, "trait data must be less than 64KB");
+                            } else {
+                                prov.traits.push((trait_type as u8, trait_bytes));
+                            }
+                        }
+                    }
+                    continue;
+                }
+                "keyword_name" => {
+                    if let Some((keyword, keyword_span)) = option_args_parser.next_int_literal(
+                        RequiredNotLast,
+                        "expected keyword bit (integer literal), e.g. keyword_name(0x10, \"Network\")",
+                    ) {
+                        if keyword == 0 {
+                            option_args_parser
+                                .errors()
+                                .add(keyword_span, "keyword must not be 0");
+                        } else if prov.keyword_names.iter().any(|k| k.0 == keyword) {
+                            option_args_parser
+                                .errors()
+                                .add(keyword_span, "keyword name already set for this keyword");
+                        }
+
+                        if let Some((name, name_span)) = option_args_parser
+                            .next_string_literal(RequiredLast, "expected \"KeywordName\"")
+                        {
+                            if name.is_empty() || name.contains('\0') {
+                                option_args_parser.errors().add(
+                                    name_span,
+                                    "keyword name must be non-empty and must not contain '\\0'",
+                                );
+                            } else {
+                                check_ascii_name(
+                                    option_args_parser.errors(),
+                                    name_span,
+                                    "keyword",
+                                    &name,
+                                );
+                                prov.keyword_names.push((keyword, name));
+                            }
+                        }
+                    }
+                    continue;
+                }
+                "task_name" => {
+                    let task = option_args_parser.next_int_literal(
+                        RequiredNotLast,
+                        "expected task value (integer literal), e.g. task_name(1, \"Download\")",
+                    );
+                    if let Some((task, task_span)) = task {
+                        if task == 0 || task > 65535 {
+                            option_args_parser
+                                .errors()
+                                .add(task_span, "task must be in the range 1 to 65535");
+                        } else if prov.task_names.iter().any(|t| t.0 as u64 == task) {
+                            option_args_parser
+                                .errors()
+                                .add(task_span, "task name already set for this task");
+                        }
+                    }
+
+                    if let Some((name, name_span)) = option_args_parser
+                        .next_string_literal(RequiredLast, "expected \"TaskName\"")
+                    {
+                        if !is_task_name(&name) {
+                            option_args_parser.errors().add(
+                                name_span,
+                                "task name must be a valid identifier, e.g. \"Download\"",
+                            );
+                        } else if prov.task_names.iter().any(|t| t.1 == name) {
+                            option_args_parser
+                                .errors()
+                                .add(name_span, "task name already used for another task");
+                        } else if let Some((task, _)) = task {
+                            if task != 0 && task <= 65535 {
+                                prov.task_names.push((task as u16, name));
+                            }
+                        }
+                    }
+                    continue;
+                }
+                "default_opcode" => {
+                    if !prov.default_opcode.is_empty() {
+                        errors.add(option_name_ident.span(), "default_opcode already set");
+                    }
+                    prov.default_opcode = Expression::new(
+                        option_name_ident.span(),
+                        filter_enum_tokens(
+                            option_args_parser.next_tokens(
+                                RequiredLast,
+                                &expected_enum_message("Opcode", "Info", 0),
+                            ),
+                            "Opcode",
+                            OPCODE_ENUMS,
+                            option_name_ident.span(),
+                            &mut scratch_tree,
+                        ),
+                    );
+                    continue;
+                }
+                "default_task" => {
+                    if !prov.default_task.is_empty() {
+                        errors.add(option_name_ident.span(), "default_task already set");
+                    }
+                    prov.default_task = Expression::new(
+                        option_name_ident.span(),
+                        option_args_parser
+                            .next_tokens(RequiredLast, "expected Task value, e.g. 1 or 0x2001"),
+                    );
+                    continue;
+                }
+                "default_level" => {
+                    if !prov.default_level.is_empty() {
+                        errors.add(option_name_ident.span(), "default_level already set");
+                    }
+                    prov.default_level = Expression::new(
+                        option_name_ident.span(),
+                        filter_enum_tokens(
+                            option_args_parser.next_tokens(
+                                RequiredLast,
+                                &expected_enum_message("Level", "Verbose", 5),
+                            ),
+                            "Level",
+                            LEVEL_ENUMS,
+                            option_name_ident.span(),
+                            &mut scratch_tree,
+                        ),
+                    );
+                    continue;
+                }
+                "default_keyword" => {
+                    if !prov.default_keyword.is_empty() {
+                        errors.add(option_name_ident.span(), "default_keyword already set");
+                    }
+                    prov.default_keyword = Expression::new(
+                        option_name_ident.span(),
+                        option_args_parser
+                            .next_tokens(RequiredLast, "expected Keyword value, e.g. 0x100F"),
+                    );
+                    continue;
+                }
+                option_name => {
                     errors.add(
                         option_name_ident.span(),
-                        "expected id(\"GUID\") or group_id(\"GUID\")",
+                        &unrecognized_option_message(option_name, PROVIDER_OPTIONS.iter().copied()),
                     );
                     continue;
                 }
@@ -123,10 +338,71 @@ impl ProviderInfo {
             prov.id = Guid::from_name(&prov.name);
         }
 
+        if !prov.keyword_names.is_empty()
+            && prov.traits.iter().any(|t| t.0 == KEYWORD_NAMES_TRAIT_TYPE)
+        {
+            errors.add(
+                arg_span,
+                "trait type 128 is used by keyword_name and must not be set via trait",
+            );
+        }
+
+        // size + name + nul + group trait + custom traits + keyword names trait
+        let keyword_names_len = prov.keyword_names_trait_data().len();
+        let meta_len = 2
+            + prov.name.len()
+            + 1
+            + if prov.group_id.is_some() { 19 } else { 0 }
+            + prov.traits.iter().map(|t| 3 + t.1.len()).sum::<usize>()
+            + if keyword_names_len != 0 {
+                3 + keyword_names_len
+            } else {
+                0
+            };
+        if meta_len >= PROVIDER_METADATA_MAX && prov.name.len() < 32768 {
+            errors.add(
+                arg_span,
+                "provider metadata (name and traits) must be less than 32KB",
+            );
+        }
+
         return if errors.is_empty() {
             Ok(prov)
         } else {
             Err(errors.into_items())
         };
     }
+
+    /// Returns the data for the keyword names trait: for each `keyword_name`,
+    /// the keyword as a little-endian u64 followed by the nul-terminated name.
+    /// Returns an empty vector if there are no keyword names.
+    pub fn keyword_names_trait_data(&self) -> Vec<u8> {
+        let mut data = Vec::new();
+        for (keyword, name) in &self.keyword_names {
+            data.extend_from_slice(&keyword.to_le_bytes());
+            data.extend_from_slice(name.as_bytes());
+            data.push(0);
+        }
+        return data;
+    }
+}
+
+/// Returns the hidden constant that define_provider generates to hold the provider's
+/// ProviderDefaults, e.g. `_TLG_DEFAULTS_MY_PROVIDER` for `MY_PROVIDER`.
+pub fn defaults_const_ident(provider_symbol: &Ident) -> Ident {
+    return Ident::new(
+        &format!("{}{}", TLG_DEFAULTS_CONST_PREFIX, provider_symbol),
+        provider_symbol.span(),
+    );
+}
+
+/// True if name can be used as a task name, i.e. it is an ASCII Rust identifier.
+pub fn is_task_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    return match chars.next() {
+        Some(first) if first.is_ascii_alphabetic() || first == '_' => {
+            chars.all(|ch| ch.is_ascii_alphanumeric() || ch == '_')
+        }
+        _ => false,
+    };
 }
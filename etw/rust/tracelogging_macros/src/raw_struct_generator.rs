@@ -0,0 +1,186 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+use proc_macro::*;
+
+use crate::enums::{EnumToken, OutType};
+use crate::raw_struct_info::RawStructInfo;
+use crate::strings::*;
+use crate::tree::Tree;
+
+pub struct RawStructGenerator {
+    /// scratch tree 1
+    tree1: Tree,
+    /// scratch tree 2
+    tree2: Tree,
+}
+
+impl RawStructGenerator {
+    pub fn new(span: Span) -> Self {
+        return Self {
+            tree1: Tree::new(span),
+            tree2: Tree::new(span),
+        };
+    }
+
+    pub fn generate(&mut self, info: RawStructInfo) -> TokenStream {
+        let span = info.ident.span();
+        let mut meta_tree = Tree::new(span);
+        let mut size_tree = Tree::new(span);
+        let mut check_tree = Tree::new(span);
+
+        for (index, field) in info.fields.iter().enumerate() {
+            let has_out = !matches!(field.field_type.outtype, OutType::Default);
+
+            // name, 0,
+            for &b in field.name.as_bytes() {
+                meta_tree
+                    .add_literal(Literal::u8_unsuffixed(b))
+                    .add_punct(",");
+            }
+            meta_tree
+                .add_literal(Literal::u8_unsuffixed(0))
+                .add_punct(",");
+
+            // intype,
+            match field.field_type.intype.to_token() {
+                EnumToken::U8(intype) => {
+                    meta_tree.add_literal(Literal::u8_unsuffixed(
+                        intype | if has_out { 0x80 } else { 0 },
+                    ));
+                }
+                EnumToken::Str(intype_name) => {
+                    meta_tree
+                        .add_path(INTYPE_PATH)
+                        .add_punct("::")
+                        .add_ident(intype_name)
+                        .add_punct(".")
+                        .add_ident("as_int")
+                        .add_group_paren([]);
+                    if has_out {
+                        meta_tree
+                            .add_punct("|")
+                            .add_literal(Literal::u8_unsuffixed(0x80));
+                    }
+                }
+            }
+            meta_tree.add_punct(",");
+
+            // outtype,
+            if has_out {
+                meta_tree
+                    .add_literal(Literal::u8_unsuffixed(field.field_type.outtype as u8))
+                    .add_punct(",");
+            }
+
+            // size_of::<field_type>() + ...
+            if index != 0 {
+                size_tree.add_punct("+");
+            }
+            size_tree
+                .add_path(MEM_SIZE_OF_PATH)
+                .add_punct("::")
+                .add_punct("<")
+                .add_path(field.field_type.type_path)
+                .add_punct(">")
+                .add_group_paren([]);
+
+            // let _: &field_type = &_tlg_value.field;
+            check_tree
+                .add_ident("let")
+                .add_ident("_")
+                .add_punct(":")
+                .add_punct("&")
+                .push_span(field.ident.span())
+                .add_path(field.field_type.type_path)
+                .pop_span()
+                .add_punct("=")
+                .add_punct("&")
+                .add_ident(TLG_VALUE_VAR)
+                .add_punct(".")
+                .add_token(field.ident.clone())
+                .add_punct(";");
+        }
+
+        let mut impl_tree = Tree::new(span);
+        impl_tree
+            // unsafe impl RawStruct for Ident { ... }
+            .add_ident("unsafe")
+            .add_ident("impl")
+            .add_path(RAW_STRUCT_PATH)
+            .add_ident("for")
+            .add_token(info.ident.clone())
+            .add_group_curly(
+                self.tree1
+                    // const FIELD_COUNT: u8 = N;
+                    .add_const_from_tokens(
+                        "FIELD_COUNT",
+                        U8_PATH,
+                        [TokenTree::from(Literal::u8_unsuffixed(
+                            info.fields.len() as u8
+                        ))],
+                    )
+                    // const RAW_META: &'static [u8] = &[...];
+                    .add_ident("const")
+                    .add_ident("RAW_META")
+                    .add_punct(":")
+                    .add_punct("&")
+                    .add_token(Punct::new('\'', Spacing::Joint))
+                    .add_ident("static")
+                    .add_group_square(self.tree2.add_path(U8_PATH).drain())
+                    .add_punct("=")
+                    .add_punct("&")
+                    .add_group_square(meta_tree.drain())
+                    .add_punct(";")
+                    .drain(),
+            )
+            // assert!(size_of::<Ident>() == size_of::<field_type>() + ..., "...");
+            .add_path(ASSERT_PATH)
+            .add_punct("!")
+            .add_group_paren(
+                self.tree1
+                    .add_path(MEM_SIZE_OF_PATH)
+                    .add_punct("::")
+                    .add_punct("<")
+                    .add_token(info.ident.clone())
+                    .add_punct(">")
+                    .add_group_paren([])
+                    .add_punct("==")
+                    .add_tokens(size_tree.drain())
+                    .add_punct(",")
+                    .add_literal(Literal::string("RawStruct type must not contain padding"))
+                    .drain(),
+            )
+            .add_punct(";")
+            // let _: fn(&Ident) = |_tlg_value| { let _: &field_type = &_tlg_value.field; ... };
+            .add_ident("let")
+            .add_ident("_")
+            .add_punct(":")
+            .add_ident("fn")
+            .add_group_paren(
+                self.tree1
+                    .add_punct("&")
+                    .add_token(info.ident.clone())
+                    .drain(),
+            )
+            .add_punct("=")
+            .add_punct("|")
+            .add_ident(TLG_VALUE_VAR)
+            .add_punct("|")
+            .add_group_curly(check_tree.drain())
+            .add_punct(";");
+
+        // const _: () = { ... };
+        return self
+            .tree1
+            .add_ident("const")
+            .add_ident("_")
+            .add_punct(":")
+            .add_group_paren([])
+            .add_punct("=")
+            .add_group_curly(impl_tree.drain())
+            .add_punct(";")
+            .drain()
+            .collect();
+    }
+}
@@ -0,0 +1,251 @@
+// Copyright (c) Microsoft Corporation.
+// Licensed under the MIT license.
+
+use proc_macro::*;
+
+use crate::enums::{InType, OutType};
+use crate::errors::Errors;
+use crate::strings::*;
+
+const STRUCT_FIELDS_MAX: usize = 127; // TraceLogging limit
+
+/// Field types supported by `#[derive(RawStruct)]`, sorted by type name.
+const RAW_STRUCT_TYPES: &[RawStructType] = &[
+    RawStructType::new("Guid", GUID_PATH, InType::Guid, OutType::Default),
+    RawStructType::new("bool", BOOL_PATH, InType::U8, OutType::Boolean),
+    RawStructType::new("f32", F32_PATH, InType::F32, OutType::Default),
+    RawStructType::new("f64", F64_PATH, InType::F64, OutType::Default),
+    RawStructType::new("i16", I16_PATH, InType::I16, OutType::Default),
+    RawStructType::new("i32", I32_PATH, InType::I32, OutType::Default),
+    RawStructType::new("i64", I64_PATH, InType::I64, OutType::Default),
+    RawStructType::new("i8", I8_PATH, InType::I8, OutType::Default),
+    RawStructType::new("isize", ISIZE_PATH, InType::ISize, OutType::Default),
+    RawStructType::new("u16", U16_PATH, InType::U16, OutType::Default),
+    RawStructType::new("u32", U32_PATH, InType::U32, OutType::Default),
+    RawStructType::new("u64", U64_PATH, InType::U64, OutType::Default),
+    RawStructType::new("u8", U8_PATH, InType::U8, OutType::Default),
+    RawStructType::new("usize", USIZE_PATH, InType::USize, OutType::Default),
+];
+
+pub struct RawStructType {
+    pub type_name: &'static str,
+    pub type_path: &'static [&'static str],
+    pub intype: InType,
+    pub outtype: OutType,
+}
+
+impl RawStructType {
+    const fn new(
+        type_name: &'static str,
+        type_path: &'static [&'static str],
+        intype: InType,
+        outtype: OutType,
+    ) -> Self {
+        return Self {
+            type_name,
+            type_path,
+            intype,
+            outtype,
+        };
+    }
+}
+
+pub struct RawStructField {
+    pub ident: Ident,
+    pub name: String,
+    pub field_type: &'static RawStructType,
+}
+
+pub struct RawStructInfo {
+    pub ident: Ident,
+    pub fields: Vec<RawStructField>,
+}
+
+impl RawStructInfo {
+    pub fn try_from_tokens(
+        arg_span: Span,
+        arg_tokens: TokenStream,
+    ) -> Result<RawStructInfo, TokenStream> {
+        let mut errors = Errors::new();
+        let mut info = RawStructInfo {
+            ident: Ident::new("x", arg_span),
+            fields: Vec::new(),
+        };
+
+        let mut tokens = arg_tokens.into_iter().peekable();
+
+        // Attributes: look for #[repr(C)].
+
+        let mut repr_c = false;
+        while let Some(TokenTree::Punct(punct)) = tokens.peek() {
+            if punct.as_char() != '#' {
+                break;
+            }
+
+            tokens.next();
+            if let Some(TokenTree::Group(group)) = tokens.next() {
+                repr_c |= is_repr_c(group.stream());
+            }
+        }
+
+        // Visibility, then "struct".
+
+        let struct_span = loop {
+            match tokens.next() {
+                Some(TokenTree::Ident(ident)) => {
+                    let ident_str = ident.to_string();
+                    if ident_str == "struct" {
+                        break ident.span();
+                    } else if ident_str == "enum" || ident_str == "union" {
+                        errors.add(ident.span(), "RawStruct can only be derived for a struct");
+                        return Err(errors.into_items());
+                    }
+                }
+                Some(_) => {}
+                None => {
+                    errors.add(arg_span, "expected struct");
+                    return Err(errors.into_items());
+                }
+            }
+        };
+
+        if let Some(TokenTree::Ident(ident)) = tokens.next() {
+            info.ident = ident;
+        }
+
+        if !repr_c {
+            errors.add(
+                info.ident.span(),
+                "RawStruct requires #[repr(C)] (field layout must match the declared field order)",
+            );
+        }
+
+        match tokens.next() {
+            Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Brace => {
+                info.parse_fields(&mut errors, group.stream());
+                if info.fields.is_empty() {
+                    errors.add(info.ident.span(), "RawStruct requires at least one field");
+                } else if info.fields.len() > STRUCT_FIELDS_MAX {
+                    errors.add(info.ident.span(), "too many fields in struct (limit 127)");
+                }
+            }
+            Some(TokenTree::Punct(punct)) if punct.as_char() == '<' => {
+                errors.add(
+                    punct.span(),
+                    "RawStruct cannot be derived for a struct with generic parameters",
+                );
+            }
+            _ => {
+                errors.add(
+                    struct_span,
+                    "RawStruct can only be derived for a struct with named fields",
+                );
+            }
+        }
+
+        return if errors.is_empty() {
+            Ok(info)
+        } else {
+            Err(errors.into_items())
+        };
+    }
+
+    fn parse_fields(&mut self, errors: &mut Errors, fields_tokens: TokenStream) {
+        let mut tokens = fields_tokens.into_iter().peekable();
+        loop {
+            // Attributes and visibility.
+            let field_ident = loop {
+                match tokens.next() {
+                    None => return,
+                    Some(TokenTree::Punct(punct)) if punct.as_char() == '#' => {
+                        tokens.next(); // [...]
+                    }
+                    Some(TokenTree::Ident(ident)) if ident.to_string() == "pub" => {
+                        if let Some(TokenTree::Group(group)) = tokens.peek() {
+                            if group.delimiter() == Delimiter::Parenthesis {
+                                tokens.next(); // pub(crate)
+                            }
+                        }
+                    }
+                    Some(TokenTree::Ident(ident)) => break ident,
+                    Some(other) => {
+                        errors.add(other.span(), "expected field name");
+                        return;
+                    }
+                }
+            };
+
+            tokens.next(); // ':'
+
+            // Type: all tokens up to the next top-level ','.
+            let mut type_tokens = Vec::new();
+            let mut angle_depth = 0;
+            for token in tokens.by_ref() {
+                if let TokenTree::Punct(punct) = &token {
+                    match punct.as_char() {
+                        ',' if angle_depth == 0 => break,
+                        '<' => angle_depth += 1,
+                        '>' => angle_depth -= 1,
+                        _ => {}
+                    }
+                }
+                type_tokens.push(token);
+            }
+
+            if let Some(field_type) = find_raw_struct_type(&type_tokens) {
+                let name = field_ident.to_string();
+                self.fields.push(RawStructField {
+                    name: match name.strip_prefix("r#") {
+                        Some(unprefixed) => unprefixed.to_string(),
+                        None => name,
+                    },
+                    ident: field_ident,
+                    field_type,
+                });
+            } else {
+                errors.add(
+                    type_tokens
+                        .first()
+                        .map_or(field_ident.span(), |token| token.span()),
+                    "unsupported RawStruct field type (supported types: bool, f32, f64, i8, i16, i32, i64, isize, u8, u16, u32, u64, usize, Guid)",
+                );
+            }
+        }
+    }
+}
+
+/// Returns the supported type named by the given type tokens, if any. Accepts a path,
+/// e.g. u32, core::primitive::u32, or tracelogging::Guid. The type is identified by the
+/// last path segment.
+pub fn find_raw_struct_type(type_tokens: &[TokenTree]) -> Option<&'static RawStructType> {
+    let is_path = type_tokens.iter().all(|token| match token {
+        TokenTree::Ident(_) => true,
+        TokenTree::Punct(punct) => punct.as_char() == ':',
+        _ => false,
+    });
+    let type_name = match type_tokens.last() {
+        Some(TokenTree::Ident(ident)) if is_path => ident.to_string(),
+        _ => return None,
+    };
+
+    return RAW_STRUCT_TYPES
+        .binary_search_by(|t| t.type_name.cmp(type_name.as_str()))
+        .ok()
+        .map(|index| &RAW_STRUCT_TYPES[index]);
+}
+
+/// Returns true if the attribute tokens are repr(...) with a C in the list.
+fn is_repr_c(attribute_tokens: TokenStream) -> bool {
+    let mut tokens = attribute_tokens.into_iter();
+    return match (tokens.next(), tokens.next()) {
+        (Some(TokenTree::Ident(ident)), Some(TokenTree::Group(group)))
+            if ident.to_string() == "repr" =>
+        {
+            group.stream().into_iter().any(|token| match token {
+                TokenTree::Ident(ident) => ident.to_string() == "C",
+                _ => false,
+            })
+        }
+        _ => false,
+    };
+}
@@ -106,14 +106,21 @@ pub const TLG_META_CONST: &str = "_TLG_META";
 pub const TLG_LENGTHS_VAR: &str = "_tlg_lengths";
 pub const TLG_DESC_VAR: &str = "_tlg_desc";
 pub const TLG_DESC_CONST: &str = "_TLG_DESC";
-pub const TLG_ACTIVITY_ID_VAR: &str = "_tlg_aid";
-pub const TLG_RELATED_ID_VAR: &str = "_tlg_rid";
+pub const TLG_DEFAULTS_VAR: &str = "_tlg_defaults";
+/// Prefix for the provider defaults constant generated by define_provider.
+pub const TLG_DEFAULTS_CONST_PREFIX: &str = "_TLG_DEFAULTS_";
+pub const TLG_FIELDS_CONST: &str = "_TLG_FIELDS";
+pub const TLG_OPTIONS_VAR: &str = "_tlg_opts";
 pub const TLG_DUR_VAR: &str = "_tlg_dur";
+pub const TLG_VALUE_VAR: &str = "_tlg_value";
+pub const TLG_RESULT_VAR: &str = "_tlg_result";
+pub const TLG_ERR_VAR: &str = "_tlg_err";
 
 pub const BORROW_BORROW_PATH: &[&str] = &["core", "borrow", "Borrow", "borrow"];
 pub const ASREF_PATH: &[&str] = &["core", "convert", "AsRef"];
 pub const IDENTITY_PATH: &[&str] = &["core", "convert", "identity"];
 pub const BOOL_PATH: &[&str] = &["core", "primitive", "bool"];
+pub const STR_PATH: &[&str] = &["core", "primitive", "str"];
 pub const F32_PATH: &[&str] = &["core", "primitive", "f32"];
 pub const F64_PATH: &[&str] = &["core", "primitive", "f64"];
 pub const I8_PATH: &[&str] = &["core", "primitive", "i8"];
@@ -127,9 +134,12 @@ pub const U32_PATH: &[&str] = &["core", "primitive", "u32"];
 pub const U64_PATH: &[&str] = &["core", "primitive", "u64"];
 pub const USIZE_PATH: &[&str] = &["core", "primitive", "usize"];
 pub const ASSERT_PATH: &[&str] = &["core", "assert"];
+pub const MEM_SIZE_OF_PATH: &[&str] = &["core", "mem", "size_of"];
 pub const OPTION_PATH: &[&str] = &["core", "option", "Option"];
 pub const OPTION_NONE_PATH: &[&str] = &["core", "option", "Option", "None"];
 pub const OPTION_SOME_PATH: &[&str] = &["core", "option", "Option", "Some"];
+pub const OPTION_UNWRAP_OR_DEFAULT_PATH: &[&str] =
+    &["core", "option", "Option", "unwrap_or_default"];
 pub const RESULT_OK_PATH: &[&str] = &["core", "result", "Result", "Ok"];
 pub const RESULT_ERR_PATH: &[&str] = &["core", "result", "Result", "Err"];
 pub const SYSTEMTIME_DURATION_SINCE_PATH: &[&str] =
@@ -137,24 +147,36 @@ pub const SYSTEMTIME_DURATION_SINCE_PATH: &[&str] =
 pub const SYSTEMTIME_UNIX_EPOCH_PATH: &[&str] = &["std", "time", "SystemTime", "UNIX_EPOCH"];
 
 pub const CHANNEL_TRACELOGGING_PATH: &[&str] = &["tracelogging", "Channel", "TraceLogging"];
+pub const EVENT_ID_FROM_NAME_PATH: &[&str] = &["tracelogging", "event_id_from_name"];
 pub const INTYPE_PATH: &[&str] = &["tracelogging", "InType"];
+pub const INTYPE_FROM_INT_PATH: &[&str] = &["tracelogging", "InType", "from_int"];
 pub const LEVEL_PATH: &[&str] = &["tracelogging", "Level"];
 pub const LEVEL_VERBOSE_PATH: &[&str] = &["tracelogging", "Level", "Verbose"];
 pub const OPCODE_INFO_PATH: &[&str] = &["tracelogging", "Opcode", "Info"];
+pub const OPCODE_PATH: &[&str] = &["tracelogging", "Opcode"];
 pub const OUTTYPE_PATH: &[&str] = &["tracelogging", "OutType"];
 pub const OUTTYPE_FROM_INT_PATH: &[&str] = &["tracelogging", "OutType", "from_int"];
+pub const OUTTYPE_DEFAULT_PATH: &[&str] = &["tracelogging", "OutType", "Default"];
 pub const GUID_PATH: &[&str] = &["tracelogging", "Guid"];
 pub const GUID_FROM_FIELDS_PATH: &[&str] = &["tracelogging", "Guid", "from_fields"];
 pub const PROVIDER_PATH: &[&str] = &["tracelogging", "Provider"];
+pub const RAW_STRUCT_PATH: &[&str] = &["tracelogging", "RawStruct"];
 
 pub const PROVIDER_NEW_PATH: &[&str] = &["tracelogging", "_internal", "provider_new"];
+pub const PROVIDER_DEFAULTS_PATH: &[&str] = &["tracelogging", "_internal", "ProviderDefaults"];
+pub const PROVIDER_DEFAULTS_FN_PATH: &[&str] = &["tracelogging", "_internal", "provider_defaults"];
 pub const PROVIDER_WRITE_TRANSFER_PATH: &[&str] =
     &["tracelogging", "_internal", "provider_write_transfer"];
+pub const PROVIDER_WRITE_TRANSFER_EX_PATH: &[&str] =
+    &["tracelogging", "_internal", "provider_write_transfer_ex"];
+pub const EVENT_WRITE_OPTIONS_PATH: &[&str] = &["tracelogging", "_internal", "EventWriteOptions"];
 pub const META_AS_BYTES_PATH: &[&str] = &["tracelogging", "_internal", "meta_as_bytes"];
 pub const TAG_ENCODE_PATH: &[&str] = &["tracelogging", "_internal", "tag_encode"];
 pub const TAG_SIZE_PATH: &[&str] = &["tracelogging", "_internal", "tag_size"];
 pub const COUNTED_SIZE_PATH: &[&str] = &["tracelogging", "_internal", "counted_size"];
 pub const SLICE_COUNT_PATH: &[&str] = &["tracelogging", "_internal", "slice_count"];
+pub const RAW_META_ARRAY_PATH: &[&str] = &["tracelogging", "_internal", "raw_meta_array"];
+pub const STR8_CHUNKS_PATH: &[&str] = &["tracelogging", "_internal", "str8_chunks"];
 pub const FILETIME_FROM_DURATION_AFTER_PATH: &[&str] = &[
     "tracelogging",
     "_internal",
@@ -169,6 +191,27 @@ pub const FILETIME_FROM_TIME32_PATH: &[&str] =
     &["tracelogging", "_internal", "filetime_from_time32"];
 pub const FILETIME_FROM_TIME64_PATH: &[&str] =
     &["tracelogging", "_internal", "filetime_from_time64"];
+pub const BOOL32_FROM_BOOL_PATH: &[&str] = &["tracelogging", "_internal", "bool32_from_bool"];
+pub const ERROR_ARITHMETIC_OVERFLOW_PATH: &[&str] =
+    &["tracelogging", "_internal", "ERROR_ARITHMETIC_OVERFLOW"];
+pub const BOOL32_ARRAY_NEW_PATH: &[&str] = &["tracelogging", "_internal", "Bool32Array", "new"];
+pub const F32_FROM_F16_PATH: &[&str] = &["tracelogging", "_internal", "f32_from_f16"];
+pub const F32_ARRAY_NEW_PATH: &[&str] = &["tracelogging", "_internal", "F32Array", "new"];
+pub const ADDRESS_FROM_POINTER_PATH: &[&str] =
+    &["tracelogging", "_internal", "address_from_pointer"];
+pub const ADDRESSES_FROM_POINTERS_PATH: &[&str] =
+    &["tracelogging", "_internal", "addresses_from_pointers"];
+pub const FMT_BUFFER_FROM_DISPLAY_PATH: &[&str] =
+    &["tracelogging", "_internal", "FmtBuffer", "from_display"];
+pub const FMT_BUFFER_FROM_DEBUG_PATH: &[&str] =
+    &["tracelogging", "_internal", "FmtBuffer", "from_debug"];
+pub const TO_JSON_BYTES_PATH: &[&str] = &["tracelogging", "_internal", "to_json_bytes"];
+pub const UTF16_BUFFER_NEW_PATH: &[&str] = &["tracelogging", "_internal", "Utf16Buffer", "new"];
+pub const MAP_STR8_BUFFER_PATH: &[&str] = &["tracelogging", "_internal", "MapStr8Buffer"];
+pub const MAP_STR8_BUFFER_NEW_PATH: &[&str] =
+    &["tracelogging", "_internal", "MapStr8Buffer", "new"];
+pub const SYSTEMTIME_WORDS_FROM_NAIVE_PATH: &[&str] =
+    &["tracelogging", "_internal", "systemtime_words_from_naive"];
 
 pub const EVENTDESC_PATH: &[&str] = &["tracelogging", "_internal", "EventDescriptor"];
 pub const EVENTDESC_FROM_PARTS_PATH: &[&str] =